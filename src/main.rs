@@ -4,6 +4,8 @@ use iced::widget::{
 };
 use iced::keyboard::{self, key, Key};
 use iced::event::{self, Event};
+use iced::mouse;
+use iced::window;
 use iced::{Element, Fill, Font, Length, Padding, Task, Theme};
 use iced::time::{self, Duration, Instant};
 use std::time::Instant as StdInstant;
@@ -46,7 +48,87 @@ fn theme_palette() -> iced::theme::Palette {
     }
 }
 
+// Shared widget styles
+fn tab_button<'a>(label: &'a str, active: bool, accent: iced::Color) -> iced::widget::Button<'a, Message> {
+    button(text(label).size(11))
+        .padding([10, 16])
+        .style(move |_, _| {
+            let (bg, txt, border) = if active {
+                (colors::BG_PANEL, colors::TEXT_PRIMARY, accent)
+            } else {
+                (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
+            };
+            button::Style {
+                background: Some(bg.into()),
+                text_color: txt,
+                border: iced::Border { color: border, width: if active { 2.0 } else { 0.0 }, radius: 0.0.into() },
+                ..Default::default()
+            }
+        })
+}
+
+fn primary_button_style(_theme: &Theme, status: button::Status) -> button::Style {
+    let bg = match status {
+        button::Status::Hovered => colors::ACCENT_CORAL,
+        button::Status::Disabled => colors::BG_ELEVATED,
+        _ => colors::ACCENT_PURPLE,
+    };
+    button::Style {
+        background: Some(bg.into()),
+        text_color: colors::BG_DARKEST,
+        border: iced::Border { radius: 0.0.into(), ..Default::default() },
+        ..Default::default()
+    }
+}
+
+fn secondary_button_style(_theme: &Theme, status: button::Status) -> button::Style {
+    let bg = match status {
+        button::Status::Hovered => colors::BG_ELEVATED,
+        _ => colors::BG_DARK,
+    };
+    button::Style {
+        background: Some(bg.into()),
+        text_color: colors::TEXT_SECONDARY,
+        border: iced::Border {
+            color: colors::BORDER,
+            width: 1.0,
+            radius: 0.0.into(),
+        },
+        ..Default::default()
+    }
+}
+
+fn input_style(_theme: &Theme, _status: text_input::Status) -> text_input::Style {
+    text_input::Style {
+        background: colors::BG_ELEVATED.into(),
+        border: iced::Border {
+            color: colors::BORDER,
+            width: 1.0,
+            radius: 0.0.into(),
+        },
+        icon: colors::TEXT_SECONDARY,
+        placeholder: colors::TEXT_SECONDARY,
+        value: colors::TEXT_PRIMARY,
+        selection: colors::ACCENT_PURPLE,
+    }
+}
+
+fn pick_list_style(_theme: &Theme, _status: pick_list::Status) -> pick_list::Style {
+    pick_list::Style {
+        text_color: colors::TEXT_PRIMARY,
+        placeholder_color: colors::TEXT_SECONDARY,
+        handle_color: colors::TEXT_SECONDARY,
+        background: colors::BG_ELEVATED.into(),
+        border: iced::Border {
+            color: colors::BORDER,
+            width: 1.0,
+            radius: 0.0.into(),
+        },
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 enum Method {
     #[default]
     GET,
@@ -64,6 +146,11 @@ impl Method {
         Method::DELETE, Method::HEAD, Method::OPTIONS,
     ];
 
+    /// Methods that carry a request body by default
+    fn has_body(self) -> bool {
+        matches!(self, Method::POST | Method::PUT | Method::PATCH)
+    }
+
     fn color(&self) -> iced::Color {
         match self {
             Method::GET => colors::SUCCESS,
@@ -91,18 +178,68 @@ impl std::fmt::Display for Method {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-enum Tab { #[default] Body, Headers, Params, Auth, Timing }
+enum Tab { #[default] Body, Tree, Flat, Headers, Cookies, Params, Auth, Vars, Timing, Schema, Wire, Compare }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Response tab to switch to when a response arrives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+enum AfterSendTab {
+    #[default]
+    Keep,
+    Body,
+    Tree,
+    Headers,
+    Timing,
+    Wire,
+}
+
+impl AfterSendTab {
+    const ALL: &'static [AfterSendTab] = &[
+        AfterSendTab::Keep,
+        AfterSendTab::Body,
+        AfterSendTab::Tree,
+        AfterSendTab::Headers,
+        AfterSendTab::Timing,
+        AfterSendTab::Wire,
+    ];
+
+    fn tab(self) -> Option<Tab> {
+        match self {
+            AfterSendTab::Keep => None,
+            AfterSendTab::Body => Some(Tab::Body),
+            AfterSendTab::Tree => Some(Tab::Tree),
+            AfterSendTab::Headers => Some(Tab::Headers),
+            AfterSendTab::Timing => Some(Tab::Timing),
+            AfterSendTab::Wire => Some(Tab::Wire),
+        }
+    }
+}
+
+impl std::fmt::Display for AfterSendTab {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            AfterSendTab::Keep => "Stay on current tab",
+            AfterSendTab::Body => "Body",
+            AfterSendTab::Tree => "Tree",
+            AfterSendTab::Headers => "Headers",
+            AfterSendTab::Timing => "Timing",
+            AfterSendTab::Wire => "Wire",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 enum AuthType {
     #[default]
     None,
     Bearer,
     Basic,
+    /// NTLMv2 challenge-response, as used by IIS and other Windows services
+    Ntlm,
 }
 
 impl AuthType {
-    const ALL: &'static [AuthType] = &[AuthType::None, AuthType::Bearer, AuthType::Basic];
+    const ALL: &'static [AuthType] = &[AuthType::None, AuthType::Bearer, AuthType::Basic, AuthType::Ntlm];
 }
 
 impl std::fmt::Display for AuthType {
@@ -111,18 +248,155 @@ impl std::fmt::Display for AuthType {
             AuthType::None => "No Auth",
             AuthType::Bearer => "Bearer Token",
             AuthType::Basic => "Basic Auth",
+            AuthType::Ntlm => "NTLM",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+enum BodyMode {
+    #[default]
+    Text,
+    Hex,
+    /// The editor holds a path; the file is streamed from disk when sent
+    File,
+    /// An image pasted from the clipboard, sent as PNG; not kept when saved
+    Image,
+}
+
+impl BodyMode {
+    const ALL: &'static [BodyMode] = &[BodyMode::Text, BodyMode::Hex, BodyMode::File, BodyMode::Image];
+}
+
+impl std::fmt::Display for BodyMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            BodyMode::Text => "Text",
+            BodyMode::Hex => "Hex bytes",
+            BodyMode::File => "File",
+            BodyMode::Image => "Pasted image",
         })
     }
 }
 
+/// Everything `send_request` needs, captured from the editors at send time
+#[derive(Debug, Clone)]
+struct OutgoingRequest {
+    url: String,
+    method: Method,
+    body: Vec<u8>,
+    /// Streamed instead of `body` when set
+    body_file: Option<String>,
+    headers: String,
+    auth_type: AuthType,
+    auth_token: String,
+    auth_username: String,
+    auth_password: String,
+    auth_domain: String,
+    /// Sent when the headers editor doesn't set a Content-Type
+    default_content_type: Option<&'static str>,
+    client: ClientOptions,
+    /// Attach the body regardless of method
+    body_any_method: bool,
+    /// Sent when the headers editor doesn't set a User-Agent
+    user_agent: Option<String>,
+    /// Stream the body with chunked transfer-encoding instead of a Content-Length
+    chunked: bool,
+    /// Compress the body and send it with `Content-Encoding: gzip`
+    gzip: bool,
+    /// Shared client built from `client`, so connections are reused across sends
+    pooled: Option<reqwest::Client>,
+}
+
 #[derive(Debug, Clone)]
 struct Response {
+    /// Final URL after redirects
+    url: String,
     status: u16,
     status_text: String,
     headers: Vec<(String, String)>,
     body: String,
+    /// The body as received when it isn't UTF-8 text; `body` is then a lossy copy
+    raw_body: Option<Vec<u8>>,
     duration: std::time::Duration,
     size: usize,
+    /// Request line, headers and body as sent
+    raw_request: String,
+    /// Status line and headers as received
+    raw_response_headers: String,
+}
+
+impl Response {
+    /// A response was received, but the server reported a 4xx/5xx status
+    fn is_http_error(&self) -> bool {
+        self.status >= 400
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Where a 3xx points, with a relative or protocol-relative `Location`
+    /// resolved against the URL that answered
+    fn redirect_target(&self) -> Option<String> {
+        if !(300..400).contains(&self.status) {
+            return None;
+        }
+        let location = self.header("location")?.trim();
+        let base = reqwest::Url::parse(&self.url).ok()?;
+        base.join(location).ok().map(|url| url.to_string())
+    }
+
+    fn is_html(&self) -> bool {
+        self.header("content-type").is_some_and(|ct| ct.trim_start().starts_with("text/html"))
+    }
+
+    /// How the body was delimited on the wire
+    fn framing(&self) -> String {
+        if let Some(encoding) = self.header("transfer-encoding") {
+            format!("Transfer-Encoding: {}", encoding)
+        } else if let Some(length) = self.header("content-length") {
+            format!("Content-Length: {}", length)
+        } else {
+            "No Content-Length or Transfer-Encoding (read until close)".to_string()
+        }
+    }
+
+    /// Declared as Markdown, or untyped/plain text that reads like it
+    fn is_markdown(&self) -> bool {
+        match self.header("content-type").map(str::trim_start) {
+            Some(ct) if ct.starts_with("text/markdown") || ct.starts_with("text/x-markdown") => true,
+            Some(ct) if !ct.starts_with("text/plain") => false,
+            _ => looks_like_markdown(&self.body),
+        }
+    }
+}
+
+/// Findings about the current response body used by the response panel, so
+/// a large body isn't parsed again on every frame
+#[derive(Debug, Clone, Default)]
+struct ResponseScan {
+    /// A JSON array of objects, which can be exported as CSV or TSV
+    is_table: bool,
+    jwt: Option<String>,
+    envelope: Option<Envelope>,
+    problem: Option<Problem>,
+    /// Line and size summary for the footer
+    stats: String,
+}
+
+impl ResponseScan {
+    fn new(response: &Response) -> Self {
+        Self {
+            is_table: is_object_array(&response.body),
+            jwt: find_jwt(&response.body),
+            envelope: detect_envelope(&response.body),
+            problem: detect_problem(response),
+            stats: text_stats(&response.body, response.size),
+        }
+    }
 }
 
 struct App {
@@ -131,9 +405,31 @@ struct App {
     request_tab: Tab,
     response_tab: Tab,
     request_body: text_editor::Content,
+    body_mode: BodyMode,
+    body_any_method: bool,
     request_headers: text_editor::Content,
     query_params: text_editor::Content,
+    request_variables: text_editor::Content,
+    header_captures: text_editor::Content,
+    // What the capture rules found in the last response: variable, header or
+    // body path, and its value if the response had it
+    captured_headers: Vec<(String, String, Option<String>)>,
+    // The last response was incognito or off record, so its captures weren't stored
+    captures_discarded: bool,
     response: Option<Response>,
+    // Read-only copies of the response body and headers, so text can be selected
+    response_body_view: text_editor::Content,
+    // The whole formatted body, of which the first `response_body_shown`
+    // bytes are in `response_body_view`
+    response_body_formatted: String,
+    // What the response panel shows about the body, worked out once per response
+    response_scan: ResponseScan,
+    response_body_shown: usize,
+    // `line[:column]` or `@offset` typed into the Body view's go-to box
+    body_goto: String,
+    response_headers_view: text_editor::Content,
+    // `path = value` per JSON leaf; None when the body isn't JSON
+    response_flat_view: Option<text_editor::Content>,
     loading: bool,
     error: Option<String>,
     history: Vec<HistoryEntry>,
@@ -142,17 +438,157 @@ struct App {
     auth_token: String,
     auth_username: String,
     auth_password: String,
+    /// NTLM only; blank takes the domain from a `DOMAIN\user` username
+    auth_domain: String,
+    // Last loaded/saved state, to detect unsaved edits
+    request_snapshot: SavedRequest,
+    confirm_new_request: bool,
+    // A "Resend as" method waiting for a second click, for DELETE
+    confirm_resend: Option<Method>,
+    // Scheme, host, port, path, query and fragment fields under the URL bar
+    show_url_parts: bool,
     // cURL import
     show_curl_import: bool,
     curl_input: String,
+    // Find/replace in the body editor
+    show_find_replace: bool,
+    show_body_preview: bool,
+    find_query: String,
+    replace_text: String,
+    // Environments
+    environments: Vec<Environment>,
+    active_environment: Option<usize>,
+    show_environments: bool,
+    // Cmd+E quick switcher: filter text, highlighted row, and environments
+    // most recently switched to, newest first
+    show_env_switcher: bool,
+    env_switcher_query: String,
+    env_switcher_index: usize,
+    recent_environments: Vec<usize>,
+    // Cmd+K command palette: filter text and highlighted row
+    show_palette: bool,
+    palette_query: String,
+    palette_index: usize,
+    // Keyboard shortcut reference
+    show_shortcuts: bool,
+    env_variables_editor: text_editor::Content,
+    // Result of the last background connectivity probe
+    online: bool,
+    // Recent durations per URL for this session, oldest first
+    latency_history: std::collections::HashMap<String, Vec<Duration>>,
+    // Responses this session that took longer than the latency budget
+    over_budget: usize,
+    // Session-only: recent responses' durations, newest last
+    timing_log: Vec<TimingEntry>,
+    show_timing_log: bool,
+    timing_log_slowest_first: bool,
+    // Pagination
+    fetch_all_pages: bool,
+    // Re-send the current request on an interval
+    polling: bool,
+    poll_interval: PollInterval,
+    poll_stop_on_error: bool,
+    // Delayed send: "30s", "5m" or "14:05", and the one send waiting to go out
+    schedule_input: String,
+    scheduled_send: Option<ScheduledSend>,
+    // Retry with backoff until the status matches: "2xx", "200", "200-204, 304"
+    watch_status: String,
+    watch_limit: WatchLimit,
+    watch: Option<Watch>,
+    next_page_path: String,
+    pagination: Option<Pagination>,
+    // JSON pointers of the expanded containers in the Tree tab
+    tree_expanded: std::collections::HashSet<String>,
+    // Shorten long string values in the Body and Tree tabs
+    fold_long_strings: bool,
+    // Long strings opened back up: `#n` for the nth string value in the Body
+    // tab, JSON pointers in the Tree tab
+    expanded_strings: std::collections::HashSet<String>,
+    // Path of the last value clicked in the Body tab, shown in the status bar
+    json_breadcrumb: Option<String>,
+    // Render response objects with keys sorted (display only)
+    sort_response_keys: bool,
+    // Known-good response shown beside the current one in the Compare tab
+    pinned_response: Option<Response>,
+    // Label scalar values with their JSON type in the Body tab
+    show_value_types: bool,
+    // Send the body chunked instead of with a Content-Length
+    chunked_body: bool,
+    // Gzip the body before sending
+    gzip_body: bool,
+    // Body for BodyMode::Image
+    pasted_image: Option<PastedImage>,
+    // File-mode body: the path last checked and its size, None if it isn't a file.
+    // Read when the path changes rather than on every render.
+    body_file: Option<(String, Option<u64>)>,
+    // The preflight in flight, and the verdict on the last one
+    cors_pending: Option<CorsPreflight>,
+    // Token open in the JWT inspector
+    inspected_jwt: Option<String>,
+    cors_verdict: Option<Result<CorsVerdict, String>>,
+    // Send a JSON body with sorted keys and no whitespace
+    canonical_json_body: bool,
+    // Show Markdown responses as source instead of rendered
+    markdown_raw: bool,
+    // Summary banner above GraphQL and JSON:API bodies
+    show_envelope: bool,
+    // History sidebar grouped under collapsible host headers
+    group_history_by_host: bool,
+    expanded_hosts: std::collections::HashSet<String>,
+    // Show auth headers unmasked in the Wire tab
+    reveal_credentials: bool,
+    // Notifications
+    toasts: Vec<Toast>,
+    // Settings
+    settings: Settings,
+    show_settings: bool,
+    max_history_input: String,
+    // Session-only: while on, nothing is added to history
+    incognito: bool,
+    // Incognito for the current request only; cleared when another is loaded
+    off_record: bool,
+    // Reused across sends for connection pooling, with the options it was built from
+    http_client: Option<(ClientOptions, reqwest::Client)>,
+    // History/saved URLs matching what's typed, and the highlighted one
+    url_suggestions: Vec<String>,
+    url_suggestion: Option<usize>,
+    // Variable names offered after a `{{` in the URL or a request editor
+    variable_completion: Option<VariableCompletion>,
+    // Overrides the after-send tab setting for the send in flight
+    show_tab_after_send: Option<Tab>,
+    default_headers_editor: text_editor::Content,
+    /// Subject of the loaded client certificate, or why it failed to load
+    client_cert_status: Option<Result<String, String>>,
+    /// The certificate fields changed since `client_cert_status` was worked out
+    client_cert_stale: bool,
+    // Collections
+    collections: Vec<Collection>,
+    show_save_request: bool,
+    save_name: String,
+    save_collection: String,
+    collection_run: Option<CollectionRun>,
+    run_stop_on_failure: bool,
+    // Batch: the current request sent to each pasted URL
+    show_batch: bool,
+    batch_urls: text_editor::Content,
+    batch: Option<BatchRun>,
+    // Snippets
+    snippets: Vec<SavedRequest>,
+    show_snippets: bool,
+    snippet_name: String,
     // Panel sizing
     sidebar_width: f32,
     request_width: f32,
     dragging: Option<DragTarget>,
+    drag_last_x: Option<f32>,
+    // Last known window size, kept current by resize events
+    window_width: f32,
+    window_height: f32,
     // Animation
-    last_tick: Option<Instant>,
     sidebar_width_target: f32,
     request_width_target: f32,
+    // Focused panel and the request ratio to restore afterwards
+    panel_focus: Option<(PanelFocus, f32)>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -161,6 +597,13 @@ enum DragTarget {
     RequestPanel,
 }
 
+/// Which panel focus mode has maximized
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PanelFocus {
+    Request,
+    Response,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct HistoryEntry {
     method: Method,
@@ -168,1415 +611,11665 @@ struct HistoryEntry {
     status: u16,
 }
 
+const CONNECTIVITY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A send waiting for its time, with the request as it was when scheduled
 #[derive(Debug, Clone)]
-enum Message {
-    UrlChanged(String),
-    MethodSelected(Method),
-    RequestTabSelected(Tab),
-    ResponseTabSelected(Tab),
-    RequestBodyChanged(text_editor::Action),
-    RequestHeadersChanged(text_editor::Action),
-    QueryParamsChanged(text_editor::Action),
-    Send,
-    ResponseReceived(Result<Response, String>),
-    HistoryEntryClicked(usize),
-    CopyResponse,
-    // Auth
-    AuthTypeSelected(AuthType),
-    AuthTokenChanged(String),
-    AuthUsernameChanged(String),
-    AuthPasswordChanged(String),
-    // cURL import
-    ToggleCurlImport,
-    CurlInputChanged(String),
-    ImportCurl,
-    // Resizing
-    StartDrag(DragTarget),
-    Drag(f32),
-    EndDrag,
-    // Animation
-    Tick(Instant),
+struct ScheduledSend {
+    at: Instant,
+    request: SavedRequest,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self {
-            url: String::from("https://httpbin.org/get"),
-            method: Method::GET,
-            request_tab: Tab::Body,
-            response_tab: Tab::Body,
-            request_body: text_editor::Content::new(),
-            request_headers: text_editor::Content::with_text("Content-Type: application/json\n"),
-            query_params: text_editor::Content::new(),
-            response: None,
-            loading: false,
-            error: None,
-            history: load_history(),
-            auth_type: AuthType::None,
-            auth_token: String::new(),
-            auth_username: String::new(),
-            auth_password: String::new(),
-            show_curl_import: false,
-            curl_input: String::new(),
-            sidebar_width: 200.0,
-            request_width: 0.5, // 50% of remaining space
-            dragging: None,
-            last_tick: None,
-            sidebar_width_target: 200.0,
-            request_width_target: 0.5,
-        }
+/// Longest wait between watch attempts
+const WATCH_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+const WATCH_LIMITS: &[WatchLimit] = &[WatchLimit(60), WatchLimit(300), WatchLimit(900), WatchLimit(3600)];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WatchLimit(u64);
+
+impl std::fmt::Display for WatchLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "for up to {}", format_countdown(Duration::from_secs(self.0)))
     }
 }
 
-impl App {
-    fn boot() -> (Self, Task<Message>) {
-        (Self::default(), Task::none())
+/// Re-sends the request with exponential backoff until a wanted status comes back
+#[derive(Debug, Clone)]
+struct Watch {
+    started: Instant,
+    attempts: u32,
+    // None while an attempt is in flight
+    next_at: Option<Instant>,
+    statuses: Vec<std::ops::RangeInclusive<u16>>,
+    limit: Duration,
+}
+
+impl Watch {
+    fn matches(&self, status: u16) -> bool {
+        self.statuses.iter().any(|range| range.contains(&status))
     }
 
-    fn theme(&self) -> Theme {
-        Theme::custom("Dashboard", theme_palette())
+    /// 1s, 2s, 4s ... capped at `WATCH_MAX_BACKOFF`
+    fn backoff(&self) -> Duration {
+        Duration::from_secs(1 << self.attempts.saturating_sub(1).min(5)).min(WATCH_MAX_BACKOFF)
     }
+}
 
-    fn subscription(&self) -> iced::Subscription<Message> {
-        let keyboard_sub = event::listen_with(|event, _status, _id| {
-            if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event {
-                // Cmd/Ctrl + Enter to send
-                if key == Key::Named(key::Named::Enter) && modifiers.command() {
-                    return Some(Message::Send);
-                }
+/// Status condition for a watch: comma-separated codes (`200`), classes
+/// (`2xx`) or ranges (`200-204`); blank means any 2xx
+fn parse_watch_statuses(input: &str) -> Result<Vec<std::ops::RangeInclusive<u16>>, String> {
+    if input.trim().is_empty() {
+        return Ok(vec![200..=299]);
+    }
+    let code = |part: &str| match part.trim().parse::<u16>() {
+        Ok(n) if (100..=599).contains(&n) => Ok(n),
+        _ => Err(format!("Expected a status like 200, 2xx or 200-204, not \"{}\"", part.trim())),
+    };
+    input
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let part = part.trim();
+            if let Some(class) = part.strip_suffix("xx").or_else(|| part.strip_suffix("XX"))
+                && let Ok(digit @ 1..=5) = class.parse::<u16>()
+            {
+                return Ok(digit * 100..=digit * 100 + 99);
             }
-            None
-        });
+            match part.split_once('-') {
+                Some((low, high)) => Ok(code(low)?..=code(high)?),
+                None => code(part).map(|n| n..=n),
+            }
+        })
+        .collect()
+}
 
-        // Animate panel sizes smoothly
-        let needs_animation = (self.sidebar_width - self.sidebar_width_target).abs() > 0.5
-            || (self.request_width - self.request_width_target).abs() > 0.001;
+/// Longest delay a send can be scheduled for
+const SCHEDULE_MAX: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Delay until a scheduled send: a number of seconds (`30`, `30s`), minutes
+/// (`5m`), or a local wall-clock time (`14:05`, the next one to come)
+fn parse_schedule(input: &str, now: chrono::DateTime<chrono::Local>) -> Result<Duration, String> {
+    let input = input.trim().trim_start_matches("at ").trim_start_matches("in ").trim();
+    if let Some((hours, minutes)) = input.split_once(':') {
+        let time = chrono::NaiveTime::from_hms_opt(
+            hours.trim().parse().map_err(|_| format!("Invalid time: {}", input))?,
+            minutes.trim().parse().map_err(|_| format!("Invalid time: {}", input))?,
+            0,
+        )
+        .ok_or_else(|| format!("Invalid time: {}", input))?;
+        let mut date = now.date_naive();
+        if time <= now.time() {
+            date = date.succ_opt().ok_or("Invalid time")?;
+        }
+        let target = date.and_time(time)
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .ok_or_else(|| format!("{} doesn't exist in the local time zone", input))?;
+        return (target - now).to_std().map_err(|e| e.to_string());
+    }
+    let (digits, scale) = match input.strip_suffix('m') {
+        Some(n) => (n, 60),
+        None => (input.strip_suffix('s').unwrap_or(input), 1),
+    };
+    match digits.trim().parse::<u64>() {
+        Ok(n) if n > 0 => match n.checked_mul(scale).map(Duration::from_secs) {
+            Some(delay) if delay <= SCHEDULE_MAX => Ok(delay),
+            _ => Err("Sends can be scheduled at most 24 hours ahead".to_string()),
+        },
+        _ => Err(format!("Expected a delay like 30s or 5m, or a time like 14:05, not \"{}\"", input)),
+    }
+}
 
-        if needs_animation || self.dragging.is_some() {
-            iced::Subscription::batch([
-                keyboard_sub,
-                time::every(Duration::from_millis(16)).map(Message::Tick),
-            ])
-        } else {
-            keyboard_sub
+/// `45s`, `4m 05s` or `1h 02m` for a countdown
+fn format_countdown(d: Duration) -> String {
+    let secs = d.as_secs_f64().ceil() as u64;
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// The sidebar never takes more than this share of the window
+const SIDEBAR_MAX_FRACTION: f32 = 0.35;
+
+/// Below this window width the sidebar is hidden to leave room for the panels
+const NARROW_WINDOW_WIDTH: f32 = 720.0;
+
+/// Combined width of the two drag handles between the panels
+const RESIZE_HANDLES_WIDTH: f32 = 8.0;
+
+/// Hosts probed to decide whether we're online; any one answering is enough
+const CONNECTIVITY_PROBES: &[&str] = &["1.1.1.1:443", "8.8.8.8:443"];
+
+/// Try a TCP connection to a well-known host, or to the proxy when one is
+/// configured since that's all requests need to reach. Uses IP addresses so
+/// a broken resolver isn't mistaken for being offline.
+async fn check_online(proxy: String) -> bool {
+    let probes: Vec<String> = match reqwest::Url::parse(proxy.trim()) {
+        Ok(url) if !proxy.trim().is_empty() => match (url.host_str(), url.port_or_known_default()) {
+            (Some(host), Some(port)) => vec![format!("{}:{}", host.trim_start_matches('[').trim_end_matches(']'), port)],
+            _ => return true,
+        },
+        _ => CONNECTIVITY_PROBES.iter().map(|p| p.to_string()).collect(),
+    };
+    for probe in probes {
+        let connect = tokio::net::TcpStream::connect(probe);
+        if let Ok(Ok(_)) = tokio::time::timeout(Duration::from_secs(3), connect).await {
+            return true;
         }
     }
+    false
+}
 
-    fn update(&mut self, message: Message) -> Task<Message> {
-        match message {
-            Message::UrlChanged(url) => { self.url = url; }
-            Message::MethodSelected(method) => { self.method = method; }
-            Message::RequestTabSelected(tab) => { self.request_tab = tab; }
-            Message::ResponseTabSelected(tab) => { self.response_tab = tab; }
-            Message::RequestBodyChanged(action) => { self.request_body.perform(action); }
-            Message::RequestHeadersChanged(action) => { self.request_headers.perform(action); }
-            Message::QueryParamsChanged(action) => { self.query_params.perform(action); }
-            Message::AuthTypeSelected(auth_type) => { self.auth_type = auth_type; }
-            Message::AuthTokenChanged(token) => { self.auth_token = token; }
-            Message::AuthUsernameChanged(username) => { self.auth_username = username; }
-            Message::AuthPasswordChanged(password) => { self.auth_password = password; }
-            Message::ToggleCurlImport => { self.show_curl_import = !self.show_curl_import; }
-            Message::CurlInputChanged(input) => { self.curl_input = input; }
-            Message::ImportCurl => {
-                if let Some(parsed) = parse_curl(&self.curl_input) {
-                    self.url = parsed.url;
-                    self.method = parsed.method;
-                    if !parsed.headers.is_empty() {
-                        self.request_headers = text_editor::Content::with_text(&parsed.headers);
-                    }
-                    if !parsed.body.is_empty() {
-                        self.request_body = text_editor::Content::with_text(&parsed.body);
-                    }
-                    if let Some((auth_type, token, user, pass)) = parsed.auth {
-                        self.auth_type = auth_type;
-                        self.auth_token = token;
-                        self.auth_username = user;
-                        self.auth_password = pass;
-                    }
-                }
-                self.show_curl_import = false;
-                self.curl_input.clear();
-            }
-            Message::Send => {
-                self.loading = true;
-                self.error = None;
-                // Build URL with query params
-                let mut url = self.url.clone();
-                let params = self.query_params.text();
-                if !params.trim().is_empty() {
-                    let param_pairs: Vec<&str> = params.lines()
-                        .filter(|l| !l.trim().is_empty() && l.contains('='))
-                        .collect();
-                    if !param_pairs.is_empty() {
-                        let separator = if url.contains('?') { "&" } else { "?" };
-                        url.push_str(separator);
-                        url.push_str(&param_pairs.join("&"));
-                    }
-                }
-                let method = self.method;
-                let body = self.request_body.text();
-                let headers = self.request_headers.text();
-                let auth_type = self.auth_type;
-                let auth_token = self.auth_token.clone();
-                let auth_username = self.auth_username.clone();
-                let auth_password = self.auth_password.clone();
-                return Task::perform(
-                    async move {
-                        send_request(url, method, body, headers, auth_type, auth_token, auth_username, auth_password).await
-                    },
-                    Message::ResponseReceived,
-                );
-            }
-            Message::ResponseReceived(result) => {
-                self.loading = false;
-                match result {
-                    Ok(response) => {
-                        self.history.push(HistoryEntry {
-                            method: self.method,
-                            url: self.url.clone(),
-                            status: response.status,
-                        });
-                        save_history(&self.history);
-                        self.response = Some(response);
-                        self.error = None;
-                    }
-                    Err(e) => {
-                        self.error = Some(e);
-                        self.response = None;
-                    }
-                }
-            }
-            Message::HistoryEntryClicked(index) => {
-                if let Some(entry) = self.history.get(index) {
-                    self.url = entry.url.clone();
-                    self.method = entry.method;
-                }
-            }
-            Message::CopyResponse => {
-                if let Some(ref response) = self.response {
-                    let text = match self.response_tab {
-                        Tab::Body | Tab::Params | Tab::Auth => format_json(&response.body),
-                        Tab::Headers => response.headers.iter()
-                            .map(|(k, v)| format!("{}: {}", k, v))
-                            .collect::<Vec<_>>()
-                            .join("\n"),
-                        Tab::Timing => format!(
-                            "Total Time: {}ms\nResponse Size: {}\nTransfer Speed: {:.1} KB/s",
-                            response.duration.as_millis(),
-                            format_size(response.size),
-                            if response.duration.as_secs_f64() > 0.0 {
-                                (response.size as f64 / response.duration.as_secs_f64()) / 1024.0
-                            } else { 0.0 }
-                        ),
-                    };
-                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                        let _ = clipboard.set_text(text);
-                    }
-                }
-            }
-            Message::StartDrag(target) => {
-                self.dragging = Some(target);
-            }
-            Message::Drag(delta) => {
-                if let Some(target) = self.dragging {
-                    match target {
-                        DragTarget::Sidebar => {
-                            self.sidebar_width_target = (self.sidebar_width_target + delta).clamp(120.0, 400.0);
-                            self.sidebar_width = self.sidebar_width_target;
-                        }
-                        DragTarget::RequestPanel => {
-                            let delta_ratio = delta / 800.0; // approximate
-                            self.request_width_target = (self.request_width_target + delta_ratio).clamp(0.25, 0.75);
-                            self.request_width = self.request_width_target;
-                        }
-                    }
-                }
-            }
-            Message::EndDrag => {
-                self.dragging = None;
-            }
-            Message::Tick(_now) => {
-                // Smooth animation with easing
-                let ease = 0.15;
-                self.sidebar_width += (self.sidebar_width_target - self.sidebar_width) * ease;
-                self.request_width += (self.request_width_target - self.request_width) * ease;
-            }
+/// Targets on this machine, which work without a network connection
+fn is_local_url(url: &str) -> bool {
+    if url.trim_start().starts_with("unix://") {
+        return true;
+    }
+    let Ok(url) = reqwest::Url::parse(url.trim()) else { return false };
+    let Some(host) = url.host_str() else { return false };
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>() {
+        Ok(std::net::IpAddr::V4(ip)) => ip.is_loopback() || ip.is_private(),
+        Ok(std::net::IpAddr::V6(ip)) => ip.is_loopback(),
+        Err(_) => host == "localhost" || host.ends_with(".localhost"),
+    }
+}
+
+/// The credential a plain-http request would expose, if any. Loopback
+/// targets are left out since that traffic never leaves the machine.
+fn cleartext_credential(url: &str, auth_type: AuthType, headers: &str) -> Option<&'static str> {
+    let parsed = reqwest::Url::parse(url.trim()).ok()?;
+    if parsed.scheme() != "http" {
+        return None;
+    }
+    let host = parsed.host_str().unwrap_or("");
+    let loopback = match host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback(),
+        Err(_) => host == "localhost" || host.ends_with(".localhost"),
+    };
+    if loopback {
+        return None;
+    }
+    match auth_type {
+        AuthType::Basic => Some("Basic auth password"),
+        // Challenge-response, but the exchange can still be cracked offline
+        AuthType::Ntlm => Some("NTLM credentials"),
+        AuthType::Bearer => Some("Bearer token"),
+        AuthType::None if header_value(headers, "authorization").is_some_and(|v| !v.trim().is_empty()) => {
+            Some("Authorization header")
         }
-        Task::none()
+        AuthType::None => None,
     }
+}
 
-    fn view(&self) -> Element<Message> {
-        let url_bar = self.view_url_bar();
+/// Editor bodies above this size get a warning suggesting File mode
+const LARGE_INLINE_BODY: usize = 5 * 1024 * 1024;
 
-        let sidebar = self.view_sidebar();
-        let sidebar_handle = self.view_resize_handle(DragTarget::Sidebar);
+/// Expand All stops at this depth
+const TREE_MAX_EXPAND_DEPTH: usize = 10;
+/// Expand All stops after this many containers
+const TREE_MAX_EXPANDED: usize = 2000;
+/// Rows rendered in the Tree tab before the rest is cut off
+const TREE_MAX_ROWS: usize = 5000;
 
-        let request_panel = self.view_request_panel();
-        let panel_handle = self.view_resize_handle(DragTarget::RequestPanel);
-        let response_panel = self.view_response_panel();
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TreeDepth(usize);
 
-        let main_content = row![request_panel, panel_handle, response_panel]
-            .height(Fill);
+impl std::fmt::Display for TreeDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Depth {}", self.0)
+    }
+}
 
-        let content = column![url_bar, main_content].spacing(1).width(Fill);
+/// JSON pointer of a child, e.g. `/items/0`
+fn child_path(parent: &str, key: &str) -> String {
+    format!("{}/{}", parent, key.replace('~', "~0").replace('/', "~1"))
+}
 
-        let main_view = row![
-            container(sidebar).width(Length::Fixed(self.sidebar_width)),
-            sidebar_handle,
-            content
-        ]
-        .height(Fill);
+/// Collect the paths of containers at depth < `depth` (the root is depth 0).
+/// Returns true if it stopped early at TREE_MAX_EXPANDED.
+fn collect_container_paths(value: &serde_json::Value, path: String, depth: usize, out: &mut Vec<String>) -> bool {
+    if depth == 0 {
+        return false;
+    }
+    let children: Vec<(String, &serde_json::Value)> = match value {
+        serde_json::Value::Object(map) => map.iter().map(|(k, v)| (child_path(&path, k), v)).collect(),
+        serde_json::Value::Array(items) => items.iter().enumerate().map(|(i, v)| (child_path(&path, &i.to_string()), v)).collect(),
+        _ => return false,
+    };
+    if out.len() >= TREE_MAX_EXPANDED {
+        return true;
+    }
+    out.push(path);
+    children.into_iter().any(|(child, v)| collect_container_paths(v, child, depth - 1, out))
+}
 
-        let status_bar = self.view_status_bar();
+fn max_depth(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Object(map) => 1 + map.values().map(max_depth).max().unwrap_or(0),
+        serde_json::Value::Array(items) => 1 + items.iter().map(max_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
 
-        let with_status = column![main_view, status_bar].height(Fill);
+/// Flatten the visible part of `value` into indented rows
+fn tree_rows<'a>(
+    value: &serde_json::Value,
+    key: Option<&str>,
+    path: String,
+    depth: usize,
+    expanded: &std::collections::HashSet<String>,
+    // Long strings shown in full, when folding is on
+    unfolded: Option<&std::collections::HashSet<String>>,
+    rows: &mut Vec<Element<'a, Message>>,
+) {
+    if rows.len() >= TREE_MAX_ROWS {
+        return;
+    }
+    let indent = Padding { top: 0.0, right: 0.0, bottom: 0.0, left: depth as f32 * 14.0 };
+    let key_span = key.map(|k| span(format!("{}: ", k)).color(SyntaxKind::Key.color()));
+
+    let (summary, children): (String, Vec<(String, String, &serde_json::Value)>) = match value {
+        serde_json::Value::Object(map) => (
+            format!("{{}} {} keys", map.len()),
+            map.iter().map(|(k, v)| (k.clone(), child_path(&path, k), v)).collect(),
+        ),
+        serde_json::Value::Array(items) => (
+            format!("[] {} items", items.len()),
+            items.iter().enumerate().map(|(i, v)| (i.to_string(), child_path(&path, &i.to_string()), v)).collect(),
+        ),
+        serde_json::Value::String(s) if let Some(unfolded) = unfolded
+            && s.chars().count() > FOLD_STRING_OVER =>
+        {
+            let open = unfolded.contains(&path);
+            let spans: Vec<iced::widget::text::Span<'a, iced::Font>> = key_span
+                .into_iter()
+                .chain(std::iter::once(
+                    span(if open { value.to_string() } else { folded_string(s) }).color(SyntaxKind::String.color()),
+                ))
+                .chain(std::iter::once(span(if open { "  fold" } else { "  expand" }).color(colors::TEXT_SECONDARY)))
+                .collect();
+            rows.push(
+                container(
+                    button(rich_text(spans).size(11))
+                        .padding(0)
+                        .style(|_, _| button::Style::default())
+                        .on_press(Message::ToggleLongString(path.clone())),
+                )
+                .padding(indent)
+                .into(),
+            );
+            return;
+        }
+        leaf => {
+            let kind = match leaf {
+                serde_json::Value::String(_) => SyntaxKind::String,
+                serde_json::Value::Number(_) => SyntaxKind::Number,
+                _ => SyntaxKind::Literal,
+            };
+            let spans: Vec<iced::widget::text::Span<'a, iced::Font>> = key_span
+                .into_iter()
+                .chain(std::iter::once(span(leaf.to_string()).color(kind.color())))
+                .collect();
+            rows.push(container(rich_text(spans).size(11)).padding(indent).into());
+            return;
+        }
+    };
 
-        let base: Element<Message> = container(with_status)
-            .width(Fill)
-            .height(Fill)
-            .style(|_| container::Style {
-                background: Some(colors::BG_DARKEST.into()),
-                ..Default::default()
-            })
-            .into();
+    let is_open = expanded.contains(&path);
+    let spans: Vec<iced::widget::text::Span<'a, iced::Font>> = std::iter::once(
+        span(if is_open { "- " } else { "+ " }).color(colors::TEXT_SECONDARY),
+    )
+    .chain(key_span)
+    .chain(std::iter::once(span(summary).color(SyntaxKind::Punctuation.color())))
+    .collect();
+    rows.push(
+        container(
+            button(rich_text(spans).size(11))
+                .padding(0)
+                .style(|_, _| button::Style::default())
+                .on_press(Message::ToggleTreeNode(path.clone())),
+        )
+        .padding(indent)
+        .into(),
+    );
 
-        // Show curl import modal if needed
-        if self.show_curl_import {
-            use iced::widget::stack;
+    if is_open {
+        for (child_key, child_path, child) in children {
+            tree_rows(child, Some(&child_key), child_path, depth + 1, expanded, unfolded, rows);
+        }
+    }
+}
 
-            let modal_overlay = container(column![])
-                .width(Fill)
-                .height(Fill)
-                .style(|_| container::Style {
-                    background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
-                    ..Default::default()
-                });
+/// String values longer than this many characters can be folded
+const FOLD_STRING_OVER: usize = 120;
 
-            let curl_input = text_input("Paste cURL command here...", &self.curl_input)
-                .on_input(Message::CurlInputChanged)
-                .on_submit(Message::ImportCurl)
-                .padding(12)
-                .size(12)
-                .width(Fill)
-                .style(|_, _| text_input::Style {
-                    background: colors::BG_ELEVATED.into(),
-                    border: iced::Border {
-                        color: colors::BORDER,
-                        width: 1.0,
-                        radius: 0.0.into(),
-                    },
-                    icon: colors::TEXT_SECONDARY,
-                    placeholder: colors::TEXT_SECONDARY,
-                    value: colors::TEXT_PRIMARY,
-                    selection: colors::ACCENT_PURPLE,
-                });
+/// A long string cut to its start plus its full length, e.g. `"eyJhbGciOi…" (1204 chars)`
+fn folded_string(s: &str) -> String {
+    let head: String = s.chars().take(40).collect();
+    let quoted = serde_json::to_string(&head).unwrap_or_default();
+    format!("{}…\" ({} chars)", quoted.trim_end_matches('"'), s.chars().count())
+}
 
-            let import_btn = button(text("IMPORT").size(11))
-                .padding([10, 20])
-                .style(|_, status| {
-                    let bg = match status {
-                        button::Status::Hovered => colors::ACCENT_CORAL,
-                        _ => colors::ACCENT_PURPLE,
-                    };
-                    button::Style {
-                        background: Some(bg.into()),
-                        text_color: colors::BG_DARKEST,
-                        border: iced::Border { radius: 0.0.into(), ..Default::default() },
-                        ..Default::default()
-                    }
-                })
-                .on_press(Message::ImportCurl);
+/// Durations kept per URL for the Timing tab sparkline
+const LATENCY_SAMPLES: usize = 30;
 
-            let cancel_btn = button(text("CANCEL").size(11))
-                .padding([10, 20])
-                .style(|_, status| {
-                    let bg = match status {
-                        button::Status::Hovered => colors::BG_ELEVATED,
-                        _ => colors::BG_DARK,
-                    };
-                    button::Style {
-                        background: Some(bg.into()),
-                        text_color: colors::TEXT_PRIMARY,
-                        border: iced::Border {
-                            color: colors::BORDER,
-                            width: 1.0,
-                            radius: 0.0.into(),
-                        },
-                        ..Default::default()
-                    }
-                })
-                .on_press(Message::ToggleCurlImport);
+/// Responses kept in the session's timing log
+const TIMING_LOG_MAX: usize = 200;
 
-            let modal_content = container(
-                column![
-                    text("IMPORT CURL").size(12).color(colors::TEXT_SECONDARY),
-                    curl_input,
-                    text("Paste a cURL command and press Enter or click Import")
-                        .size(10)
-                        .color(colors::TEXT_SECONDARY),
-                    row![cancel_btn, import_btn].spacing(8),
-                ]
-                .spacing(12)
-                .width(Length::Fixed(500.0))
-            )
-            .padding(20)
-            .style(|_| container::Style {
-                background: Some(colors::BG_PANEL.into()),
-                border: iced::Border {
-                    color: colors::BORDER,
-                    width: 1.0,
-                    radius: 0.0.into(),
-                },
-                ..Default::default()
-            });
+/// One response in the timing log, with the request that produced it
+#[derive(Debug, Clone)]
+struct TimingEntry {
+    request: SavedRequest,
+    status: u16,
+    duration: Duration,
+}
 
-            let modal_centered = container(modal_content)
-                .width(Fill)
-                .height(Fill)
-                .center_x(Fill)
-                .center_y(Fill);
+/// Up to 8 distinct history and saved-request URLs containing `input`,
+/// prefix matches first, newest history first
+fn url_suggestions(input: &str, history: &[HistoryEntry], collections: &[Collection]) -> Vec<String> {
+    let needle = input.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let candidates = history.iter().rev().map(|e| &e.url)
+        .chain(collections.iter().flat_map(|c| c.requests.iter().map(|r| &r.url)));
+    let mut seen = std::collections::HashSet::new();
+    let mut matches: Vec<(bool, &String)> = candidates
+        .filter(|url| url.to_lowercase() != needle && seen.insert(url.as_str()))
+        .filter_map(|url| {
+            let lower = url.to_lowercase();
+            lower.contains(&needle).then(|| (!lower.starts_with(&needle), url))
+        })
+        .collect();
+    // Stable, so recency order survives within each group
+    matches.sort_by_key(|(substring_only, _)| *substring_only);
+    matches.into_iter().take(8).map(|(_, url)| url.clone()).collect()
+}
 
-            stack![base, modal_overlay, modal_centered].into()
-        } else {
-            base
+/// Where a `{{` variable name is being completed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompletionTarget {
+    Url,
+    Body,
+    Headers,
+    Params,
+}
+
+#[derive(Debug, Clone)]
+struct VariableCompletion {
+    target: CompletionTarget,
+    /// Typed so far after the `{{`
+    prefix: String,
+    names: Vec<String>,
+    selected: usize,
+}
+
+/// The partial name after an unclosed `{{` at the end of `before_cursor`
+fn open_variable_prefix(before_cursor: &str) -> Option<&str> {
+    let prefix = &before_cursor[before_cursor.rfind("{{")? + 2..];
+    prefix.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')).then_some(prefix)
+}
+
+/// Up to 8 distinct variable names containing `prefix`, prefix matches first
+fn matching_variable_names(vars: &[(String, String)], prefix: &str) -> Vec<String> {
+    let needle = prefix.to_lowercase();
+    let mut names: Vec<&String> = Vec::new();
+    for (name, _) in vars {
+        if name.to_lowercase().contains(&needle) && !names.contains(&name) {
+            names.push(name);
         }
     }
+    names.sort_by_key(|name| !name.to_lowercase().starts_with(&needle));
+    names.into_iter().take(8).cloned().collect()
+}
 
-    fn view_resize_handle(&self, target: DragTarget) -> Element<Message> {
-        let is_dragging = self.dragging == Some(target);
-        let handle_color = if is_dragging { colors::ACCENT_PURPLE } else { colors::BORDER };
+/// The cursor's line up to the cursor
+fn before_cursor(content: &text_editor::Content) -> String {
+    let position = content.cursor().position;
+    content.line(position.line)
+        .map(|line| {
+            let mut end = position.column.min(line.text.len());
+            while !line.text.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.text[..end].to_string()
+        })
+        .unwrap_or_default()
+}
 
-        mouse_area(
-            container(column![])
-                .width(4)
-                .height(Fill)
-                .style(move |_| container::Style {
-                    background: Some(handle_color.into()),
-                    ..Default::default()
-                })
-        )
-        .on_press(Message::StartDrag(target))
-        .on_release(Message::EndDrag)
-        .into()
+/// Host (and port, if any) a history entry went to, for sidebar grouping.
+/// Scheme-less URLs are treated as http; unparsable ones fall back to the
+/// text before the first path separator.
+fn url_host(url: &str) -> String {
+    let url = url.trim();
+    if url.starts_with("unix://") {
+        return "unix socket".to_string();
+    }
+    let with_scheme = if url.contains("://") { url.to_string() } else { format!("http://{}", url) };
+    if let Ok(parsed) = reqwest::Url::parse(&with_scheme)
+        && let Some(host) = parsed.host_str()
+    {
+        return match parsed.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
     }
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    if host.is_empty() { "(no host)".to_string() } else { host.to_string() }
+}
 
-    fn view_status_bar(&self) -> Element<Message> {
-        let method_color = self.method.color();
+/// Latency samples are grouped by URL without its query string
+fn latency_key(url: &str) -> String {
+    url.split(['?', '#']).next().unwrap_or(url).to_string()
+}
 
-        let left_items = row![
-            text(self.method.to_string()).size(10).color(method_color),
-            text(truncate_str(&self.url, 50)).size(10).color(colors::TEXT_SECONDARY),
-        ]
-        .spacing(8);
+/// Line chart of recent durations; points well above the median are flagged
+struct Sparkline {
+    samples: Vec<f32>,
+    threshold: f32,
+}
 
-        let auth_indicator = match self.auth_type {
-            AuthType::None => text("").size(10),
-            AuthType::Bearer => text("Bearer").size(10).color(colors::SUCCESS),
-            AuthType::Basic => text("Basic").size(10).color(colors::SUCCESS),
-        };
+impl Sparkline {
+    fn new(durations: &[Duration]) -> Self {
+        let samples: Vec<f32> = durations.iter().map(|d| d.as_secs_f32() * 1000.0).collect();
+        let mut sorted = samples.clone();
+        sorted.sort_by(f32::total_cmp);
+        let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+        Self { samples, threshold: median * 2.0 }
+    }
+}
 
-        let status_indicator = if self.loading {
-            text("Sending...").size(10).color(colors::WARNING)
-        } else if self.response.is_some() {
-            text("Ready").size(10).color(colors::SUCCESS)
-        } else if self.error.is_some() {
-            text("Error").size(10).color(colors::ERROR)
-        } else {
-            text("Ready").size(10).color(colors::TEXT_SECONDARY)
+impl iced::widget::canvas::Program<Message> for Sparkline {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        _theme: &Theme,
+        bounds: iced::Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<iced::widget::canvas::Geometry> {
+        use iced::widget::canvas::{Frame, Path, Stroke};
+
+        let mut frame = Frame::new(renderer, bounds.size());
+        let max = self.samples.iter().copied().fold(1.0_f32, f32::max);
+        let step = bounds.width / (self.samples.len().max(2) - 1) as f32;
+        let point = |i: usize, ms: f32| {
+            iced::Point::new(i as f32 * step, bounds.height - 3.0 - (ms / max) * (bounds.height - 6.0))
         };
 
-        let history_count = text(format!("{} requests", self.history.len()))
-            .size(10)
-            .color(colors::TEXT_SECONDARY);
-
-        let shortcut_hint = text("Cmd+Enter to send")
-            .size(10)
-            .color(colors::TEXT_SECONDARY);
+        let line = Path::new(|builder| {
+            for (i, &ms) in self.samples.iter().enumerate() {
+                if i == 0 {
+                    builder.move_to(point(i, ms));
+                } else {
+                    builder.line_to(point(i, ms));
+                }
+            }
+        });
+        frame.stroke(&line, Stroke::default().with_color(colors::ACCENT_PURPLE).with_width(1.5));
 
-        let right_items = row![
-            auth_indicator,
-            status_indicator,
-            history_count,
-            shortcut_hint,
-        ]
-        .spacing(16);
+        for (i, &ms) in self.samples.iter().enumerate() {
+            let color = if ms > self.threshold { colors::WARNING } else { colors::ACCENT_PURPLE };
+            frame.fill(&Path::circle(point(i, ms), 2.5), color);
+        }
 
-        container(
-            row![left_items, right_items]
-                .width(Fill)
-                .align_y(iced::Alignment::Center)
-        )
-        .padding(Padding { top: 6.0, right: 16.0, bottom: 6.0, left: 16.0 })
-        .width(Fill)
-        .style(|_| container::Style {
-            background: Some(colors::BG_DARK.into()),
-            border: iced::Border {
-                color: colors::BORDER,
-                width: 1.0,
-                radius: 0.0.into(),
-            },
-            ..Default::default()
-        })
-        .into()
+        vec![frame.into_geometry()]
     }
+}
 
-    fn view_sidebar(&self) -> Element<Message> {
-        let title = text("HISTORY").size(10).color(colors::TEXT_SECONDARY);
+/// Stop following next-page links after this many pages
+const MAX_PAGES: usize = 50;
 
-        let history_content: Element<Message> = if self.history.is_empty() {
-            container(
-                text("No requests yet").size(11).color(colors::TEXT_SECONDARY)
-            )
-            .padding(8)
-            .into()
-        } else {
-            let items: Vec<Element<Message>> = self
-                .history
-                .iter()
-                .rev()
-                .enumerate()
-                .take(50)
-                .map(|(i, entry)| {
-                    let status_color = match entry.status {
-                        200..=299 => colors::SUCCESS,
-                        400..=499 => colors::WARNING,
-                        500..=599 => colors::ERROR,
-                        _ => colors::TEXT_PRIMARY,
-                    };
-
-                    let url_display = if entry.url.len() > 18 {
-                        format!("{}...", &entry.url[..15])
-                    } else {
-                        entry.url.clone()
-                    };
+/// Progress of a "Fetch all pages" pass
+#[derive(Debug, Clone)]
+struct Pagination {
+    pages: usize,
+    items: Vec<serde_json::Value>,
+    error: Option<String>,
+}
 
-                    let idx = self.history.len() - 1 - i;
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+const TOAST_FADE: Duration = Duration::from_millis(400);
 
-                    button(
-                        column![
-                            row![
-                                text(entry.method.to_string())
-                                    .size(10)
-                                    .color(entry.method.color()),
-                                text(entry.status.to_string())
-                                    .size(10)
-                                    .color(status_color),
-                            ].spacing(8),
-                            text(url_display).size(10).color(colors::TEXT_SECONDARY),
-                        ].spacing(2),
-                    )
-                    .width(Fill)
-                    .padding(8)
-                    .style(|_, status| {
-                        let bg = match status {
-                            button::Status::Hovered => colors::BG_ELEVATED,
-                            _ => colors::BG_PANEL,
-                        };
-                        button::Style {
-                            background: Some(bg.into()),
-                            text_color: colors::TEXT_PRIMARY,
-                            border: iced::Border::default(),
-                            ..Default::default()
-                        }
-                    })
-                    .on_press(Message::HistoryEntryClicked(idx))
-                    .into()
-                })
-                .collect();
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ToastKind {
+    Success,
+    Warning,
+    Error,
+}
 
-            scrollable(Column::from_vec(items).spacing(4).width(Fill))
-                .height(Fill)
-                .into()
-        };
+#[derive(Debug, Clone)]
+struct Toast {
+    message: String,
+    kind: ToastKind,
+    created: Instant,
+    age: Duration,
+}
 
-        container(
-            column![title, history_content].spacing(12).width(Fill)
-        )
-        .padding(12)
-        .width(Fill)
-        .height(Fill)
-        .style(|_| container::Style {
-            background: Some(colors::BG_DARK.into()),
-            ..Default::default()
-        })
-        .into()
+impl Toast {
+    /// Fully opaque until the last TOAST_FADE of its lifetime
+    fn opacity(&self) -> f32 {
+        let remaining = TOAST_DURATION.saturating_sub(self.age);
+        (remaining.as_secs_f32() / TOAST_FADE.as_secs_f32()).min(1.0)
     }
+}
 
-    fn view_url_bar(&self) -> Element<Message> {
-        let method_picker = pick_list(Method::ALL, Some(self.method), Message::MethodSelected)
-            .text_size(12)
-            .padding(10)
-            .width(90)
-            .style(|_, _| pick_list::Style {
-                text_color: self.method.color(),
-                placeholder_color: colors::TEXT_SECONDARY,
-                handle_color: colors::TEXT_SECONDARY,
-                background: colors::BG_ELEVATED.into(),
-                border: iced::Border {
-                    color: colors::BORDER,
-                    width: 1.0,
-                    radius: 0.0.into(),
-                },
-            });
+/// User preferences, persisted to settings.json
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Settings {
+    /// Oldest history entries beyond this are dropped
+    #[serde(default = "default_max_history")]
+    max_history: usize,
+    #[serde(default)]
+    client_cert: ClientCert,
+    /// Sent unless the Headers editor sets its own User-Agent; blank sends none
+    #[serde(default)]
+    user_agent: String,
+    /// Seconds allowed to establish the connection; 0 waits forever
+    #[serde(default = "default_connect_timeout_secs")]
+    connect_timeout_secs: u64,
+    /// Whole-request timeout in seconds, connecting included; 0 waits forever
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+    /// Redirects followed before giving up; 0 returns the 3xx itself
+    #[serde(default = "default_max_redirects")]
+    max_redirects: usize,
+    /// e.g. `http://127.0.0.1:8080`; blank uses the system proxy settings
+    #[serde(default)]
+    proxy: String,
+    /// Skip certificate validation, for self-signed dev servers
+    #[serde(default)]
+    accept_invalid_certs: bool,
+    /// `Key: Value` lines added to every request unless its Headers tab sets the same key
+    #[serde(default)]
+    default_headers: String,
+    /// Response tab shown once a response arrives
+    #[serde(default)]
+    after_send_tab: AfterSendTab,
+    /// How repeated query parameter keys are sent
+    #[serde(default)]
+    query_array_style: ArrayStyle,
+    /// Every response is also written here; blank turns archiving off
+    #[serde(default)]
+    archive_dir: String,
+    /// Idle keep-alive connections kept per host; 0 opens a new one every time
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pool_max_idle_per_host: usize,
+    /// Idle pooled connections are closed after this many seconds
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pool_idle_timeout_secs: u64,
+    /// Pretty-print the body when pasting leaves it as valid JSON
+    #[serde(default)]
+    beautify_on_paste: bool,
+    /// KB of the formatted body put in the Body tab at first and per "Show more"
+    #[serde(default = "default_body_chunk_kb")]
+    body_chunk_kb: usize,
+    /// Show and copy JSON bodies as the server sent them instead of pretty-printed
+    #[serde(default)]
+    preserve_body_formatting: bool,
+    /// Responses slower than this many milliseconds are flagged; 0 turns it off
+    #[serde(default)]
+    latency_budget_ms: u64,
+    /// Probe every 30s whether the network (or the proxy) is reachable; while
+    /// it isn't, the status bar says Offline and Send is disabled for remote URLs
+    #[serde(default = "default_check_connectivity")]
+    check_connectivity: bool,
+}
 
-        let url_input = text_input("https://api.example.com/endpoint", &self.url)
-            .on_input(Message::UrlChanged)
-            .on_submit(Message::Send)
-            .padding(10)
-            .size(12)
-            .width(Fill)
-            .style(|_, _| text_input::Style {
-                background: colors::BG_ELEVATED.into(),
-                border: iced::Border {
-                    color: colors::BORDER,
-                    width: 1.0,
-                    radius: 0.0.into(),
-                },
-                icon: colors::TEXT_SECONDARY,
-                placeholder: colors::TEXT_SECONDARY,
-                value: colors::TEXT_PRIMARY,
-                selection: colors::ACCENT_PURPLE,
-            });
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_history: default_max_history(),
+            client_cert: ClientCert::default(),
+            user_agent: String::new(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            timeout_secs: default_timeout_secs(),
+            max_redirects: default_max_redirects(),
+            proxy: String::new(),
+            accept_invalid_certs: false,
+            default_headers: String::new(),
+            after_send_tab: AfterSendTab::default(),
+            query_array_style: ArrayStyle::default(),
+            archive_dir: String::new(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
+            beautify_on_paste: false,
+            preserve_body_formatting: false,
+            body_chunk_kb: default_body_chunk_kb(),
+            latency_budget_ms: 0,
+            check_connectivity: default_check_connectivity(),
+        }
+    }
+}
 
-        let send_text = if self.loading { "..." } else { "SEND" };
-        let send_button = button(text(send_text).size(11))
-            .padding([10, 20])
-            .style(|_, status| {
-                let bg = match status {
-                    button::Status::Hovered => colors::ACCENT_CORAL,
-                    button::Status::Pressed => colors::WARNING,
-                    _ => colors::ACCENT_PURPLE,
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: colors::BG_DARKEST,
-                    border: iced::Border {
-                        radius: 0.0.into(),
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                }
-            })
-            .on_press_maybe(if self.loading { None } else { Some(Message::Send) });
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct UserAgentPreset {
+    name: &'static str,
+    value: &'static str,
+}
 
-        let import_button = button(text("cURL").size(10))
-            .padding([10, 12])
-            .style(|_, status| {
-                let bg = match status {
-                    button::Status::Hovered => colors::BG_ELEVATED,
-                    _ => colors::BG_DARK,
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: colors::TEXT_SECONDARY,
-                    border: iced::Border {
-                        color: colors::BORDER,
-                        width: 1.0,
-                        radius: 0.0.into(),
-                    },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::ToggleCurlImport);
+impl std::fmt::Display for UserAgentPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
 
-        let bar = row![method_picker, url_input, import_button, send_button]
-            .spacing(8)
-            .padding(12);
+const USER_AGENT_PRESETS: &[UserAgentPreset] = &[
+    UserAgentPreset {
+        name: "Chrome (Windows)",
+        value: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+    },
+    UserAgentPreset {
+        name: "Firefox (Linux)",
+        value: "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0",
+    },
+    UserAgentPreset {
+        name: "Safari (macOS)",
+        value: "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_4) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Safari/605.1.15",
+    },
+    UserAgentPreset {
+        name: "Safari (iPhone)",
+        value: "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+    },
+    UserAgentPreset { name: "curl", value: "curl/8.7.1" },
+    UserAgentPreset { name: "BadGateway", value: "badgateway/0.1" },
+];
+
+/// Client certificate for mutual TLS: a `.p12`/`.pfx` bundle, or a PEM
+/// certificate with a PKCS#8 key (in the same file or `key_path`)
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct ClientCert {
+    #[serde(default)]
+    cert_path: String,
+    #[serde(default)]
+    key_path: String,
+    /// Kept in memory only, never written to settings.json
+    #[serde(skip)]
+    passphrase: String,
+}
 
-        container(bar)
-            .width(Fill)
-            .style(|_| container::Style {
-                background: Some(colors::BG_PANEL.into()),
-                border: iced::Border {
-                    color: colors::BORDER,
-                    width: 0.0,
-                    radius: 0.0.into(),
-                },
-                ..Default::default()
-            })
-            .into()
+impl ClientCert {
+    fn is_configured(&self) -> bool {
+        !self.cert_path.trim().is_empty()
     }
+}
 
-    fn view_request_panel(&self) -> Element<Message> {
-        let body_active = self.request_tab == Tab::Body;
-        let headers_active = self.request_tab == Tab::Headers;
-        let params_active = self.request_tab == Tab::Params;
-        let auth_active = self.request_tab == Tab::Auth;
+fn default_max_history() -> usize {
+    500
+}
 
-        let body_tab = button(text("Body").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if body_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if body_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::RequestTabSelected(Tab::Body));
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
 
-        let headers_tab = button(text("Headers").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if headers_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if headers_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::RequestTabSelected(Tab::Headers));
+fn default_timeout_secs() -> u64 {
+    30
+}
 
-        let params_tab = button(text("Params").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if params_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if params_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::RequestTabSelected(Tab::Params));
+fn default_max_redirects() -> usize {
+    10
+}
 
-        let auth_tab = button(text("Auth").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if auth_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if auth_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::RequestTabSelected(Tab::Auth));
+fn default_pool_max_idle_per_host() -> usize {
+    16
+}
 
-        let tabs = row![body_tab, headers_tab, params_tab, auth_tab].spacing(0);
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
 
-        let content: Element<Message> = match self.request_tab {
-            Tab::Body | Tab::Timing => text_editor(&self.request_body)
-                .placeholder("{\n  \"key\": \"value\"\n}")
-                .on_action(Message::RequestBodyChanged)
-                .padding(12)
-                .height(Fill)
-                .style(|_, _| text_editor::Style {
-                    background: colors::BG_PANEL.into(),
-                    border: iced::Border::default(),
-                    placeholder: colors::TEXT_SECONDARY,
-                    value: colors::TEXT_PRIMARY,
-                    selection: colors::ACCENT_PURPLE,
-                })
-                .into(),
-            Tab::Headers => text_editor(&self.request_headers)
-                .placeholder("Content-Type: application/json\nAuthorization: Bearer token")
-                .on_action(Message::RequestHeadersChanged)
-                .padding(12)
-                .height(Fill)
-                .style(|_, _| text_editor::Style {
-                    background: colors::BG_PANEL.into(),
-                    border: iced::Border::default(),
-                    placeholder: colors::TEXT_SECONDARY,
-                    value: colors::TEXT_PRIMARY,
-                    selection: colors::ACCENT_PURPLE,
-                })
-                .into(),
-            Tab::Params => text_editor(&self.query_params)
-                .placeholder("key=value\npage=1\nlimit=10")
-                .on_action(Message::QueryParamsChanged)
-                .padding(12)
-                .height(Fill)
-                .style(|_, _| text_editor::Style {
-                    background: colors::BG_PANEL.into(),
-                    border: iced::Border::default(),
-                    placeholder: colors::TEXT_SECONDARY,
-                    value: colors::TEXT_PRIMARY,
-                    selection: colors::ACCENT_PURPLE,
-                })
-                .into(),
-            Tab::Auth => self.view_auth_panel(),
-        };
+fn default_body_chunk_kb() -> usize {
+    256
+}
 
-        let header = row![
-            text("REQUEST").size(10).color(colors::TEXT_SECONDARY),
-        ];
+fn default_check_connectivity() -> bool {
+    true
+}
 
-        let panel = column![
-            container(header).padding(Padding { top: 12.0, right: 16.0, bottom: 8.0, left: 16.0 }),
-            container(tabs).style(|_| container::Style {
-                background: Some(colors::BG_DARK.into()),
-                ..Default::default()
-            }),
-            container(content).padding(0).height(Fill),
+const TIMEOUT_CHOICES: &[TimeoutChoice] = &[
+    TimeoutChoice(5), TimeoutChoice(10), TimeoutChoice(30),
+    TimeoutChoice(60), TimeoutChoice(120), TimeoutChoice(300), TimeoutChoice(0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TimeoutChoice(u64);
+
+impl std::fmt::Display for TimeoutChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "No timeout"),
+            secs => write!(f, "{} s", secs),
+        }
+    }
+}
+
+const LATENCY_BUDGETS: &[LatencyBudget] = &[
+    LatencyBudget(0), LatencyBudget(100), LatencyBudget(250), LatencyBudget(500),
+    LatencyBudget(1000), LatencyBudget(2000), LatencyBudget(5000),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LatencyBudget(u64);
+
+impl std::fmt::Display for LatencyBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "No budget"),
+            ms => write!(f, "{} ms", ms),
+        }
+    }
+}
+
+const CONNECT_TIMEOUT_CHOICES: &[TimeoutChoice] = &[
+    TimeoutChoice(1), TimeoutChoice(3), TimeoutChoice(5), TimeoutChoice(10), TimeoutChoice(30), TimeoutChoice(0),
+];
+
+const POLL_INTERVALS: &[PollInterval] = &[
+    PollInterval(1), PollInterval(2), PollInterval(5), PollInterval(10), PollInterval(30), PollInterval(60),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PollInterval(u64);
+
+impl std::fmt::Display for PollInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "every {}s", self.0)
+    }
+}
+
+const REDIRECT_CHOICES: &[RedirectChoice] = &[
+    RedirectChoice(0), RedirectChoice(1), RedirectChoice(5), RedirectChoice(10), RedirectChoice(20),
+];
+
+const BODY_CHUNK_CHOICES: &[BodyChunkChoice] = &[
+    BodyChunkChoice(64), BodyChunkChoice(256), BodyChunkChoice(1024), BodyChunkChoice(4096),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BodyChunkChoice(usize);
+
+impl std::fmt::Display for BodyChunkChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            kb if kb >= 1024 => write!(f, "{} MB at a time", kb / 1024),
+            kb => write!(f, "{} KB at a time", kb),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RedirectChoice(usize);
+
+impl std::fmt::Display for RedirectChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "Don't follow"),
+            max => write!(f, "Follow up to {}", max),
+        }
+    }
+}
+
+/// Per-client configuration taken from settings when a request is sent
+#[derive(Debug, Clone, PartialEq)]
+struct ClientOptions {
+    client_cert: Option<ClientCert>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    max_redirects: usize,
+    proxy: Option<String>,
+    accept_invalid_certs: bool,
+    pool_max_idle_per_host: usize,
+    pool_idle_timeout: Duration,
+}
+
+impl ClientOptions {
+    fn from_settings(settings: &Settings) -> Self {
+        Self {
+            client_cert: settings.client_cert.is_configured().then(|| settings.client_cert.clone()),
+            connect_timeout: (settings.connect_timeout_secs > 0)
+                .then(|| Duration::from_secs(settings.connect_timeout_secs)),
+            timeout: (settings.timeout_secs > 0).then(|| Duration::from_secs(settings.timeout_secs)),
+            max_redirects: settings.max_redirects,
+            proxy: Some(settings.proxy.trim().to_string()).filter(|p| !p.is_empty()),
+            accept_invalid_certs: settings.accept_invalid_certs,
+            pool_max_idle_per_host: settings.pool_max_idle_per_host,
+            pool_idle_timeout: Duration::from_secs(settings.pool_idle_timeout_secs),
+        }
+    }
+
+    fn client_builder(self) -> Result<reqwest::ClientBuilder, String> {
+        let mut builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .redirect(match self.max_redirects {
+                0 => reqwest::redirect::Policy::none(),
+                max => reqwest::redirect::Policy::limited(max),
+            });
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            let proxy = reqwest::Proxy::all(&proxy).map_err(|e| format!("Invalid proxy {}: {}", proxy, e))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(cert) = self.client_cert {
+            let (identity, _) = load_client_identity(&cert)?;
+            builder = builder.identity(identity);
+        }
+        Ok(builder)
+    }
+}
+
+const POOL_SIZE_CHOICES: &[PoolSizeChoice] = &[
+    PoolSizeChoice(0), PoolSizeChoice(1), PoolSizeChoice(4), PoolSizeChoice(16), PoolSizeChoice(64),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PoolSizeChoice(usize);
+
+impl std::fmt::Display for PoolSizeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "No keep-alive"),
+            n => write!(f, "{} per host", n),
+        }
+    }
+}
+
+const POOL_IDLE_CHOICES: &[PoolIdleChoice] = &[
+    PoolIdleChoice(5), PoolIdleChoice(15), PoolIdleChoice(30), PoolIdleChoice(90), PoolIdleChoice(300),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PoolIdleChoice(u64);
+
+impl std::fmt::Display for PoolIdleChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Close after {} s idle", self.0)
+    }
+}
+
+const DEFAULT_URL: &str = "https://httpbin.org/get";
+const DEFAULT_HEADERS: &str = "Content-Type: application/json\n";
+
+/// A named snapshot of everything needed to re-send a request
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct SavedRequest {
+    name: String,
+    method: Method,
+    url: String,
+    #[serde(default)]
+    headers: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    body_mode: BodyMode,
+    /// Send the body even for methods that normally have none (e.g. GET)
+    #[serde(default)]
+    body_any_method: bool,
+    #[serde(default)]
+    params: String,
+    #[serde(default)]
+    auth_type: AuthType,
+    #[serde(default)]
+    auth_token: String,
+    #[serde(default)]
+    auth_username: String,
+    #[serde(default)]
+    auth_password: String,
+    #[serde(default)]
+    auth_domain: String,
+    /// `key=value` lines that take precedence over the active environment
+    #[serde(default)]
+    variables: String,
+    /// `var=Header-Name` or `var=$.json.path` lines: after each response, the
+    /// header's or body value's text is stored in the active environment as `var`
+    #[serde(default)]
+    header_captures: String,
+}
+
+impl SavedRequest {
+    /// The request a fresh app starts with
+    fn blank() -> Self {
+        Self {
+            name: String::new(),
+            method: Method::GET,
+            url: DEFAULT_URL.to_string(),
+            headers: DEFAULT_HEADERS.to_string(),
+            body: String::new(),
+            body_mode: BodyMode::Text,
+            body_any_method: false,
+            params: String::new(),
+            auth_type: AuthType::None,
+            auth_token: String::new(),
+            auth_username: String::new(),
+            auth_password: String::new(),
+            auth_domain: String::new(),
+            variables: String::new(),
+            header_captures: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Collection {
+    name: String,
+    requests: Vec<SavedRequest>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Environment {
+    name: String,
+    /// Prefixed to URLs typed without a scheme, e.g. `/api/users`
+    #[serde(default)]
+    base_url: String,
+    /// `key=value` lines, referenced as `{{key}}`
+    #[serde(default)]
+    variables: String,
+    /// Names of variables whose values are masked everywhere but on the wire
+    #[serde(default)]
+    secrets: Vec<String>,
+}
+
+impl Environment {
+    fn variables(&self) -> Vec<(String, String)> {
+        parse_variables(&self.variables)
+    }
+
+    /// Values of the secret variables, for `mask_secrets`
+    fn secret_values(&self) -> Vec<String> {
+        self.variables()
+            .into_iter()
+            .filter(|(k, v)| !v.is_empty() && self.secrets.contains(k))
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// The variables text as shown in the editor, secret values masked
+    fn masked_variables(&self) -> String {
+        self.variables.split('\n')
+            .map(|line| match line.split_once('=') {
+                Some((key, _)) if self.secrets.iter().any(|s| s == key.trim()) => format!("{}={}", key, SECRET_MASK),
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Take edited editor text back, keeping the stored value of every
+    /// secret line still showing the mask
+    fn unmask_variables(&self, edited: &str) -> String {
+        let stored = self.variables();
+        edited.split('\n')
+            .map(|line| match line.split_once('=') {
+                Some((key, value)) if value.trim() == SECRET_MASK && self.secrets.iter().any(|s| s == key.trim()) => {
+                    match stored.iter().rev().find(|(k, _)| k == key.trim()) {
+                        Some((_, real)) => format!("{}={}", key, real),
+                        None => line.to_string(),
+                    }
+                }
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A "Secret" checkbox per variable of `env`; secret values stay masked
+/// until replaced in the editor
+fn secret_toggles(env: &Environment) -> Element<'_, Message> {
+    let mut names: Vec<String> = Vec::new();
+    for (name, _) in env.variables() {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    if names.is_empty() {
+        return column![].into();
+    }
+    let toggles = names.into_iter().map(|name| {
+        let secret = env.secrets.contains(&name);
+        iced::widget::checkbox(secret)
+            .label(name.clone())
+            .text_size(10)
+            .on_toggle(move |on| Message::EnvironmentSecretToggled(name.clone(), on))
+            .into()
+    });
+    row![text("Secret").size(10).color(colors::TEXT_SECONDARY)]
+        .extend(toggles)
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .wrap()
+        .into()
+}
+
+/// Shown in place of a secret variable's value
+const SECRET_MASK: &str = "••••••";
+
+/// Replace every occurrence of a secret value in text meant for display
+fn mask_secrets(text: &str, secrets: &[String]) -> String {
+    let mut masked = text.to_string();
+    // Longest first, so a secret containing another is masked whole
+    let mut secrets: Vec<&String> = secrets.iter().collect();
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    for secret in secrets {
+        masked = masked.replace(secret.as_str(), SECRET_MASK);
+    }
+    masked
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct EnvironmentStore {
+    #[serde(default)]
+    active: Option<usize>,
+    #[serde(default)]
+    environments: Vec<Environment>,
+}
+
+/// Entry in the environment picker; `index` is None for "no environment"
+#[derive(Debug, Clone, PartialEq)]
+struct EnvironmentChoice {
+    index: Option<usize>,
+    name: String,
+}
+
+impl std::fmt::Display for EnvironmentChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// State of an in-progress (or finished) "Run collection" pass
+#[derive(Debug, Clone)]
+struct CollectionRun {
+    collection: usize,
+    next: usize,
+    results: Vec<RunStepResult>,
+    stopped: bool,
+    /// The editors and their saved snapshot from before the run, put back
+    /// once it ends, since every step loads its request into them
+    editor: Option<(SavedRequest, SavedRequest)>,
+    off_record: bool,
+}
+
+impl CollectionRun {
+    /// A step has been sent but its response hasn't arrived yet
+    fn in_flight(&self) -> bool {
+        self.results.len() < self.next
+    }
+}
+
+/// Sends in flight at once during a batch
+const BATCH_CONCURRENCY: usize = 4;
+
+/// The current request sent to each of a list of URLs
+#[derive(Debug, Clone)]
+struct BatchRun {
+    /// As typed, one per line; resolved when each is sent
+    urls: Vec<String>,
+    /// Per URL, once its response (or error) is in
+    results: Vec<Option<Result<Response, String>>>,
+    next: usize,
+    stopped: bool,
+    /// The request every URL is sent with, captured when the batch started
+    request: OutgoingRequest,
+}
+
+impl BatchRun {
+    fn done(&self) -> usize {
+        self.results.iter().filter(|r| r.is_some()).count()
+    }
+
+    fn finished(&self) -> bool {
+        self.done() == self.next && (self.stopped || self.next == self.urls.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RunStepResult {
+    status: Option<u16>,
+    error: Option<String>,
+    duration: Option<std::time::Duration>,
+}
+
+impl RunStepResult {
+    fn passed(&self) -> bool {
+        matches!(self.status, Some(status) if status < 400)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    UrlChanged(String),
+    UrlSubmitted,
+    UrlSuggestionMoved(isize),
+    UrlSuggestionSelected(usize),
+    VariableCompletionSelected(usize),
+    AcceptVariableCompletion,
+    DismissVariableCompletion,
+    DismissUrlSuggestions,
+    MethodSelected(Method),
+    RequestTabSelected(Tab),
+    ResponseTabSelected(Tab),
+    RequestBodyChanged(text_editor::Action),
+    PastedBodyFormatted(String, Option<String>), // (body as pasted, formatted if it was JSON)
+    ResponseBodyViewAction(text_editor::Action),
+    ResponseHeadersViewAction(text_editor::Action),
+    ResponseFlatViewAction(text_editor::Action),
+    BodyModeSelected(BodyMode),
+    BodyPreviewToggled(bool),
+    BodyAnyMethodToggled(bool),
+    ChunkedBodyToggled(bool),
+    GzipBodyToggled(bool),
+    PasteImage,
+    CheckCors,
+    CorsChecked(Result<Response, String>),
+    DismissCors,
+    InspectJwt(String),
+    ToggleHeaderLines,
+    CanonicalizeHeaders,
+    CloseJwt,
+    CanonicalJsonToggled(bool),
+    RequestHeadersChanged(text_editor::Action),
+    HeaderPresetSelected(&'static str),
+    QuickHeaderChanged(&'static str, String),
+    ContentTypeSelected(ContentTypeChoice),
+    QueryParamsChanged(text_editor::Action),
+    RequestVariablesChanged(text_editor::Action),
+    HeaderCapturesChanged(text_editor::Action),
+    Beautify,
+    NewRequest,
+    ConfirmNewRequest,
+    CancelNewRequest,
+    ResendAs(Method),
+    ToggleBatch,
+    BatchUrlsAction(text_editor::Action),
+    RunBatch,
+    StopBatch,
+    BatchResponse(usize, Result<Response, String>),
+    BatchResultClicked(usize),
+    ToggleUrlParts,
+    UrlPartChanged(UrlPart, String),
+    ToggleTimingLog,
+    TimingLogSortToggled(bool),
+    TimingLogEntryClicked(usize),
+    ClearTimingLog,
+    ConfirmResend,
+    CancelResend,
+    Send,
+    FollowRedirect(String),
+    ResponseReceived(Result<Response, String>),
+    PageReceived(Result<Response, String>),
+    FetchAllPagesToggled(bool),
+    PollingToggled(bool),
+    PollIntervalSelected(PollInterval),
+    PollStopOnErrorToggled(bool),
+    PollTick,
+    ScheduleInputChanged(String),
+    ScheduleSend,
+    CancelScheduledSend,
+    ScheduleTick(Instant),
+    WatchToggled(bool),
+    WatchStatusChanged(String),
+    WatchLimitSelected(WatchLimit),
+    WatchTick(Instant),
+    NextPagePathChanged(String),
+    HistoryEntryClicked(usize),
+    ClearHistory,
+    GroupHistoryByHostToggled(bool),
+    ToggleHostGroup(String),
+    FocusPanel(PanelFocus),
+    RepeatLastRequest,
+    CopyResponse,
+    UseResponseAsBody,
+    CopySummary,
+    CopyRustTypes,
+    CopyJsonValue(String),
+    // A value in the Body tab's rich view: copy it and show its path
+    JsonValueClicked { value: String, path: String },
+    PinResponse,
+    UnpinResponse,
+    OpenInBrowser,
+    ToggleTreeNode(String),
+    FoldLongStringsToggled(bool),
+    ToggleLongString(String),
+    ExpandAll,
+    CollapseAll,
+    ExpandToDepth(usize),
+    SortResponseKeysToggled(bool),
+    ShowValueTypesToggled(bool),
+    MarkdownRawToggled(bool),
+    ShowEnvelopeToggled(bool),
+    ToggleRevealCredentials,
+    CopyAsTable(char), // field delimiter
+    // Auth
+    AuthTypeSelected(AuthType),
+    AuthTokenChanged(String),
+    AuthUsernameChanged(String),
+    AuthPasswordChanged(String),
+    AuthDomainChanged(String),
+    // cURL import
+    ToggleCurlImport,
+    ImportCurlFromUrl,
+    CurlInputChanged(String),
+    ImportCurl,
+    // Find/replace
+    ToggleFindReplace,
+    FindQueryChanged(String),
+    ReplaceTextChanged(String),
+    FindNext,
+    ReplaceNext,
+    ReplaceAll,
+    // Environments
+    EnvironmentSelected(EnvironmentChoice),
+    OpenEnvSwitcher,
+    CloseEnvSwitcher,
+    EnvSwitcherQueryChanged(String),
+    EnvSwitcherSubmit,
+    // Command palette
+    TogglePalette,
+    ToggleShortcuts,
+    PaletteQueryChanged(String),
+    PaletteRun(usize), // index into the filtered commands
+    ToggleEnvironments,
+    ToggleSettings,
+    MaxHistoryChanged(String),
+    IncognitoToggled(bool),
+    OffRecordToggled(bool),
+    ConnectTimeoutSelected(TimeoutChoice),
+    TimeoutSelected(TimeoutChoice),
+    MaxRedirectsSelected(RedirectChoice),
+    PoolSizeSelected(PoolSizeChoice),
+    PoolIdleSelected(PoolIdleChoice),
+    AfterSendTabSelected(AfterSendTab),
+    LatencyBudgetSelected(LatencyBudget),
+    BodyChunkSelected(BodyChunkChoice),
+    ShowMoreBody,
+    ShowWholeBody,
+    BodyGotoChanged(String),
+    GoToBodyPosition,
+    ArrayStyleSelected(ArrayStyle),
+    SendAndShow(Tab),
+    ProxyChanged(String),
+    ArchiveDirChanged(String),
+    AcceptInvalidCertsToggled(bool),
+    BeautifyOnPasteToggled(bool),
+    PreserveBodyFormattingToggled(bool),
+    DefaultHeadersChanged(text_editor::Action),
+    UserAgentChanged(String),
+    UserAgentPresetSelected(UserAgentPreset),
+    ClientCertPathChanged(String),
+    ClientKeyPathChanged(String),
+    ClientCertPassphraseChanged(String),
+    CheckClientCert,
+    AddEnvironment,
+    DeleteEnvironment,
+    EnvironmentNameChanged(String),
+    EnvironmentBaseUrlChanged(String),
+    EnvironmentVariablesChanged(text_editor::Action),
+    EnvironmentSecretToggled(String, bool),
+    // Collections
+    ToggleSaveRequest,
+    SaveNameChanged(String),
+    SaveCollectionChanged(String),
+    SaveRequest,
+    SavedRequestClicked(usize, usize),
+    DeleteSavedRequest(usize, usize),
+    ToggleSnippets,
+    SnippetNameChanged(String),
+    SaveSnippet,
+    SnippetClicked(usize),
+    DeleteSnippet(usize),
+    ExportCollection(usize),
+    FileExported(Result<Option<String>, String>), // saved path; None if cancelled
+    ImportPostman,
+    PostmanFileLoaded(Result<Option<(String, String)>, String>), // file stem and contents; None if cancelled
+    ImportHttpFile,
+    HttpFileLoaded(Result<Option<(String, String)>, String>),
+    ExportHttpFile,
+    SaveResponseBody,
+    RunCollection(usize),
+    RunCollectionStep,
+    StopOnFailureToggled(bool),
+    StopCollectionRun,
+    CloseModal,
+    CheckConnectivity,
+    ConnectivityChecked(bool),
+    CheckConnectivityToggled(bool),
+    FocusNext,
+    FocusPrevious,
+    CloseCollectionRun,
+    // Resizing
+    StartDrag(DragTarget),
+    Drag(f32), // cursor x position
+    EndDrag,
+    WindowResized(iced::Size),
+    // Animation
+    Tick(Instant),
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self {
+            url: String::from(DEFAULT_URL),
+            method: Method::GET,
+            request_tab: Tab::Body,
+            response_tab: Tab::Body,
+            request_body: text_editor::Content::new(),
+            body_mode: BodyMode::Text,
+            body_any_method: false,
+            request_headers: text_editor::Content::with_text(DEFAULT_HEADERS),
+            query_params: text_editor::Content::new(),
+            request_variables: text_editor::Content::new(),
+            header_captures: text_editor::Content::new(),
+            captured_headers: Vec::new(),
+            captures_discarded: false,
+            response: None,
+            response_body_view: text_editor::Content::new(),
+            response_body_formatted: String::new(),
+            response_scan: ResponseScan::default(),
+            response_body_shown: 0,
+            body_goto: String::new(),
+            response_headers_view: text_editor::Content::new(),
+            response_flat_view: None,
+            loading: false,
+            error: None,
+            history: load_history(),
+            auth_type: AuthType::None,
+            auth_token: String::new(),
+            auth_username: String::new(),
+            auth_password: String::new(),
+            auth_domain: String::new(),
+            request_snapshot: SavedRequest::blank(),
+            confirm_new_request: false,
+            confirm_resend: None,
+            show_url_parts: false,
+            show_curl_import: false,
+            curl_input: String::new(),
+            show_find_replace: false,
+            show_body_preview: false,
+            find_query: String::new(),
+            replace_text: String::new(),
+            environments: Vec::new(),
+            active_environment: None,
+            show_env_switcher: false,
+            env_switcher_query: String::new(),
+            env_switcher_index: 0,
+            recent_environments: Vec::new(),
+            show_palette: false,
+            show_shortcuts: false,
+            palette_query: String::new(),
+            palette_index: 0,
+            show_environments: false,
+            env_variables_editor: text_editor::Content::new(),
+            online: true,
+            latency_history: std::collections::HashMap::new(),
+            over_budget: 0,
+            timing_log: Vec::new(),
+            show_timing_log: false,
+            timing_log_slowest_first: false,
+            fetch_all_pages: false,
+            polling: false,
+            poll_interval: PollInterval(5),
+            poll_stop_on_error: true,
+            schedule_input: String::new(),
+            scheduled_send: None,
+            watch_status: String::new(),
+            watch_limit: WatchLimit(300),
+            watch: None,
+            next_page_path: String::new(),
+            pagination: None,
+            tree_expanded: std::collections::HashSet::new(),
+            fold_long_strings: false,
+            expanded_strings: std::collections::HashSet::new(),
+            json_breadcrumb: None,
+            sort_response_keys: false,
+            pinned_response: None,
+            show_value_types: false,
+            chunked_body: false,
+            gzip_body: false,
+            pasted_image: None,
+            body_file: None,
+            cors_pending: None,
+            inspected_jwt: None,
+            cors_verdict: None,
+            canonical_json_body: false,
+            markdown_raw: false,
+            show_envelope: true,
+            group_history_by_host: false,
+            expanded_hosts: std::collections::HashSet::new(),
+            reveal_credentials: false,
+            toasts: Vec::new(),
+            settings: Settings::default(),
+            show_settings: false,
+            max_history_input: String::new(),
+            incognito: false,
+            off_record: false,
+            http_client: None,
+            url_suggestions: Vec::new(),
+            url_suggestion: None,
+            variable_completion: None,
+            show_tab_after_send: None,
+            default_headers_editor: text_editor::Content::new(),
+            client_cert_status: None,
+            client_cert_stale: false,
+            collections: load_collections(),
+            snippets: load_snippets(),
+            show_snippets: false,
+            snippet_name: String::new(),
+            show_save_request: false,
+            save_name: String::new(),
+            save_collection: String::from("Default"),
+            collection_run: None,
+            show_batch: false,
+            batch_urls: text_editor::Content::new(),
+            batch: None,
+            run_stop_on_failure: false,
+            sidebar_width: 200.0,
+            request_width: 0.5, // 50% of remaining space
+            dragging: None,
+            drag_last_x: None,
+            window_width: 1024.0,
+            window_height: 768.0,
+            sidebar_width_target: 200.0,
+            request_width_target: 0.5,
+            panel_focus: None,
+        }
+    }
+}
+
+impl App {
+    fn boot() -> (Self, Task<Message>) {
+        let store = load_environments();
+        let settings = load_settings();
+        let mut app = Self {
+            active_environment: store.active.filter(|&i| i < store.environments.len()),
+            environments: store.environments,
+            max_history_input: settings.max_history.to_string(),
+            default_headers_editor: text_editor::Content::with_text(&settings.default_headers),
+            settings,
+            ..Self::default()
+        };
+        app.sync_env_variables_editor();
+        app.refresh_client_cert_status();
+        if trim_history(&mut app.history, app.settings.max_history) {
+            save_history(&app.history);
+        }
+        (app, Task::done(Message::CheckConnectivity))
+    }
+
+    fn theme(&self) -> Theme {
+        Theme::custom("Dashboard", theme_palette())
+    }
+
+    fn subscription(&self) -> iced::Subscription<Message> {
+        let keyboard_sub = event::listen_with(|event, status, _id| {
+            if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event {
+                // Escape only ever closes the topmost modal
+                if key == Key::Named(key::Named::Escape) {
+                    return Some(Message::CloseModal);
+                }
+                // Up / Down walk the URL suggestions
+                if status == event::Status::Ignored
+                    && let Key::Named(named @ (key::Named::ArrowUp | key::Named::ArrowDown)) = key
+                {
+                    return Some(Message::UrlSuggestionMoved(if named == key::Named::ArrowDown { 1 } else { -1 }));
+                }
+                // Tab / Shift+Tab move focus, unless a widget used the key itself
+                if key == Key::Named(key::Named::Tab) && status == event::Status::Ignored {
+                    return Some(if modifiers.shift() { Message::FocusPrevious } else { Message::FocusNext });
+                }
+                // "?" outside a text field opens the shortcut reference
+                if status == event::Status::Ignored && key.as_ref() == Key::Character("?") {
+                    return Some(Message::ToggleShortcuts);
+                }
+                if modifiers.command() {
+                    return command_shortcut(key.as_ref(), modifiers.shift());
+                }
+            }
+            None
+        });
+
+        // Animate panel sizes smoothly
+        let needs_animation = (self.sidebar_width - self.sidebar_width_target).abs() > 0.5
+            || (self.request_width - self.request_width_target).abs() > 0.001
+            || !self.toasts.is_empty();
+
+        let resize_sub = window::resize_events().map(|(_, size)| Message::WindowResized(size));
+        let mut background = vec![resize_sub];
+        // Background probe so an offline machine shows up before Send fails
+        if self.settings.check_connectivity && !self.incognito {
+            background.push(time::every(CONNECTIVITY_INTERVAL).map(|_| Message::CheckConnectivity));
+        }
+        if self.polling {
+            background.push(time::every(Duration::from_secs(self.poll_interval.0)).map(|_| Message::PollTick));
+        }
+        if self.scheduled_send.is_some() {
+            background.push(time::every(Duration::from_millis(250)).map(Message::ScheduleTick));
+        }
+        if self.watch.is_some() {
+            background.push(time::every(Duration::from_millis(250)).map(Message::WatchTick));
+        }
+        let background_sub = iced::Subscription::batch(background);
+
+        if self.dragging.is_some() {
+            // Track the cursor anywhere in the window while a handle is held
+            let drag_sub = event::listen_with(|event, _status, _id| match event {
+                Event::Mouse(mouse::Event::CursorMoved { position }) => Some(Message::Drag(position.x)),
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => Some(Message::EndDrag),
+                _ => None,
+            });
+            iced::Subscription::batch([
+                keyboard_sub,
+                background_sub,
+                drag_sub,
+                time::every(Duration::from_millis(16)).map(Message::Tick),
+            ])
+        } else if needs_animation {
+            iced::Subscription::batch([
+                keyboard_sub,
+                background_sub,
+                time::every(Duration::from_millis(16)).map(Message::Tick),
+            ])
+        } else {
+            iced::Subscription::batch([keyboard_sub, background_sub])
+        }
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::UrlChanged(mut url) => {
+                if let Some((method, rest)) = split_method_prefix(&url) {
+                    self.method = method;
+                    url = rest;
+                }
+                self.url_suggestions = url_suggestions(&url, &self.history, &self.collections);
+                self.url_suggestion = None;
+                // Typing happens at the end of the URL, so that's where a `{{` is completed
+                self.update_variable_completion(CompletionTarget::Url, &url);
+                if self.variable_completion.is_some() {
+                    self.dismiss_url_suggestions();
+                }
+                self.url = url;
+            }
+            Message::ToggleUrlParts => { self.show_url_parts = !self.show_url_parts; }
+            Message::UrlPartChanged(part, value) => {
+                let mut parts = UrlParts::split(&self.url);
+                parts.set(part, value);
+                self.url = parts.join();
+            }
+            Message::UrlSubmitted => {
+                if self.variable_completion.as_ref().is_some_and(|c| c.target == CompletionTarget::Url) {
+                    return self.update(Message::AcceptVariableCompletion);
+                }
+                if let Some(url) = self.url_suggestion.and_then(|i| self.url_suggestions.get(i)) {
+                    self.url = url.clone();
+                    self.dismiss_url_suggestions();
+                } else {
+                    return self.update(Message::Send);
+                }
+            }
+            Message::UrlSuggestionMoved(step) if self.show_palette => {
+                let count = self.palette_commands().len() as isize;
+                if count > 0 {
+                    self.palette_index = (self.palette_index as isize + step).rem_euclid(count) as usize;
+                }
+            }
+            Message::UrlSuggestionMoved(step) if self.show_env_switcher => {
+                // The arrows drive the environment switcher while it's open
+                let count = self.env_switcher_choices().len() as isize;
+                if count > 0 {
+                    self.env_switcher_index = (self.env_switcher_index as isize + step).rem_euclid(count) as usize;
+                }
+            }
+            Message::UrlSuggestionMoved(step) if self.variable_completion.is_some() => {
+                if let Some(completion) = self.variable_completion.as_mut() {
+                    let count = completion.names.len() as isize;
+                    completion.selected = (completion.selected as isize + step).rem_euclid(count) as usize;
+                }
+            }
+            Message::UrlSuggestionMoved(step) => {
+                let count = self.url_suggestions.len() as isize;
+                if count > 0 {
+                    let current = self.url_suggestion.map_or(if step > 0 { -1 } else { count }, |i| i as isize);
+                    self.url_suggestion = Some((current + step).rem_euclid(count) as usize);
+                }
+            }
+            Message::UrlSuggestionSelected(index) => {
+                if let Some(url) = self.url_suggestions.get(index) {
+                    self.url = url.clone();
+                }
+                self.dismiss_url_suggestions();
+            }
+            Message::DismissUrlSuggestions => self.dismiss_url_suggestions(),
+            Message::VariableCompletionSelected(index) => {
+                if let Some(completion) = self.variable_completion.as_mut() {
+                    completion.selected = index;
+                }
+                return self.update(Message::AcceptVariableCompletion);
+            }
+            Message::AcceptVariableCompletion => {
+                if let Some(completion) = self.variable_completion.take()
+                    && let Some(name) = completion.names.get(completion.selected)
+                {
+                    self.complete_variable(completion.target, &completion.prefix, name);
+                }
+            }
+            Message::DismissVariableCompletion => { self.variable_completion = None; }
+            Message::MethodSelected(method) => { self.method = method; }
+            Message::RequestTabSelected(tab) => { self.request_tab = tab; }
+            Message::ResponseTabSelected(tab) => { self.response_tab = tab; }
+            Message::RequestBodyChanged(action) => {
+                let pasted = matches!(action, text_editor::Action::Edit(text_editor::Edit::Paste(_)));
+                let edited = action.is_edit();
+                let moved = !edited && !matches!(action, text_editor::Action::Scroll { .. });
+                self.request_body.perform(action);
+                if self.body_mode == BodyMode::Text {
+                    self.editor_changed(CompletionTarget::Body, edited, moved);
+                }
+                if edited {
+                    self.refresh_body_file();
+                }
+                if pasted && self.settings.beautify_on_paste && self.body_mode == BodyMode::Text {
+                    // Parsing a big paste can take a while, so it runs as a task
+                    let body = self.request_body.text();
+                    return Task::perform(
+                        async move {
+                            let formatted = serde_json::from_str::<serde_json::Value>(&body)
+                                .ok()
+                                .and_then(|value| serde_json::to_string_pretty(&value).ok());
+                            (body, formatted)
+                        },
+                        |(body, formatted)| Message::PastedBodyFormatted(body, formatted),
+                    );
+                }
+            }
+            Message::PastedBodyFormatted(pasted, formatted) => {
+                // Typing since the paste wins over the formatted copy
+                if let Some(formatted) = formatted
+                    && self.request_body.text() == pasted
+                    && pasted != formatted
+                {
+                    set_editor_text(&mut self.request_body, &formatted);
+                }
+            }
+            // Selection and scrolling only; the response views are never edited
+            Message::ResponseBodyViewAction(action) => {
+                if !action.is_edit() {
+                    let copy_value = matches!(action, text_editor::Action::SelectWord)
+                        && self.response.as_ref().is_some_and(|r| response_syntax(r) == BodySyntax::Json);
+                    self.response_body_view.perform(action);
+                    // Double-clicking a value copies all of it, not just the word
+                    if copy_value
+                        && let Some(value) = json_value_at(&self.response_body_view)
+                    {
+                        return self.update(Message::CopyJsonValue(value));
+                    }
+                }
+            }
+            Message::ResponseHeadersViewAction(action) => {
+                if !action.is_edit() {
+                    self.response_headers_view.perform(action);
+                }
+            }
+            Message::ResponseFlatViewAction(action) => {
+                if !action.is_edit()
+                    && let Some(ref mut view) = self.response_flat_view
+                {
+                    let copy_path = matches!(action, text_editor::Action::SelectWord);
+                    view.perform(action);
+                    // Double-clicking a line copies its path
+                    let path = view.line(view.cursor().position.line)
+                        .and_then(|line| line.text.split_once(" = ").map(|(path, _)| path.to_string()));
+                    if copy_path && let Some(path) = path {
+                        return self.update(Message::CopyJsonValue(path));
+                    }
+                }
+            }
+            Message::BodyPreviewToggled(show) => { self.show_body_preview = show; }
+            Message::BodyAnyMethodToggled(enabled) => { self.body_any_method = enabled; }
+            Message::ChunkedBodyToggled(enabled) => { self.chunked_body = enabled; }
+            Message::GzipBodyToggled(enabled) => { self.gzip_body = enabled; }
+            Message::PasteImage => match paste_image() {
+                Ok(image) => {
+                    self.toast(ToastKind::Success, format!("Pasted {}×{} image", image.width, image.height));
+                    self.pasted_image = Some(image);
+                    self.body_mode = BodyMode::Image;
+                    self.body_file = None;
+                }
+                Err(e) => self.toast(ToastKind::Error, e),
+            },
+            Message::CanonicalJsonToggled(enabled) => { self.canonical_json_body = enabled; }
+            Message::BodyModeSelected(mode) => {
+                self.body_mode = mode;
+                self.refresh_body_file();
+            }
+            Message::RequestHeadersChanged(action) => {
+                let edited = action.is_edit();
+                let moved = !edited && !matches!(action, text_editor::Action::Scroll { .. });
+                self.request_headers.perform(action);
+                self.editor_changed(CompletionTarget::Headers, edited, moved);
+            }
+            Message::HeaderPresetSelected(preset) => {
+                // Insert as its own line below the cursor
+                let cursor_line = self.request_headers.cursor().position.line;
+                let line_is_empty = self.request_headers.line(cursor_line).is_none_or(|l| l.text.trim().is_empty());
+                self.request_headers.perform(text_editor::Action::Move(text_editor::Motion::End));
+                let insert = if line_is_empty { preset.to_string() } else { format!("\n{}", preset) };
+                self.request_headers.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                    std::sync::Arc::new(insert),
+                )));
+            }
+            Message::QuickHeaderChanged(name, value) => {
+                let headers = set_header_value(&self.request_headers.text(), name, &value);
+                self.request_headers = text_editor::Content::with_text(&headers);
+            }
+            Message::ContentTypeSelected(choice) => match choice {
+                ContentTypeChoice::Preset(content_type) => {
+                    let headers = set_header_value(&self.request_headers.text(), "Content-Type", content_type);
+                    set_editor_text(&mut self.request_headers, &headers);
+                }
+                // Anything else is typed into the header line itself
+                ContentTypeChoice::Custom => { self.request_tab = Tab::Headers; }
+            },
+            Message::QueryParamsChanged(action) => {
+                let edited = action.is_edit();
+                let moved = !edited && !matches!(action, text_editor::Action::Scroll { .. });
+                self.query_params.perform(action);
+                self.editor_changed(CompletionTarget::Params, edited, moved);
+            }
+            Message::RequestVariablesChanged(action) => { self.request_variables.perform(action); }
+            Message::HeaderCapturesChanged(action) => { self.header_captures.perform(action); }
+            Message::AuthTypeSelected(auth_type) => { self.auth_type = auth_type; }
+            Message::AuthTokenChanged(token) => { self.auth_token = token; }
+            Message::AuthUsernameChanged(username) => { self.auth_username = username; }
+            Message::AuthPasswordChanged(password) => { self.auth_password = password; }
+            Message::AuthDomainChanged(domain) => { self.auth_domain = domain; }
+            Message::ToggleCurlImport => { self.show_curl_import = !self.show_curl_import; }
+            Message::ImportCurlFromUrl => {
+                self.curl_input = self.url.trim().to_string();
+                self.show_curl_import = true;
+            }
+            Message::CurlInputChanged(input) => { self.curl_input = input; }
+            Message::ImportCurl => {
+                if let Some(parsed) = parse_curl(&self.curl_input) {
+                    self.url = parsed.url;
+                    self.method = parsed.method;
+                    if !parsed.headers.is_empty() {
+                        self.request_headers = text_editor::Content::with_text(&parsed.headers);
+                    }
+                    if !parsed.body.is_empty() {
+                        self.request_body = text_editor::Content::with_text(&parsed.body);
+                        self.refresh_body_file();
+                    }
+                    if !parsed.params.is_empty() {
+                        self.query_params = text_editor::Content::with_text(&parsed.params);
+                    }
+                    if let Some((auth_type, token, user, pass)) = parsed.auth {
+                        self.auth_type = auth_type;
+                        self.auth_token = token;
+                        self.auth_username = user;
+                        self.auth_password = pass;
+                    }
+                    // Redirect handling is global, so -L can only be honoured if it's on
+                    if parsed.follow_redirects && self.settings.max_redirects == 0 {
+                        self.toast(ToastKind::Warning, "Imported; the command uses -L but redirects are off in Settings");
+                    } else {
+                        self.toast(ToastKind::Success, "Imported cURL command");
+                    }
+                } else {
+                    self.toast(ToastKind::Error, "Could not parse cURL command");
+                }
+                self.show_curl_import = false;
+                self.curl_input.clear();
+            }
+            Message::Beautify => {
+                let body = self.request_body.text();
+                if serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+                    set_editor_text(&mut self.request_body, &format_json(&body));
+                }
+                let headers = normalize_headers(&self.request_headers.text());
+                set_editor_text(&mut self.request_headers, &headers);
+                let params = normalize_params(&self.query_params.text());
+                set_editor_text(&mut self.query_params, &params);
+            }
+            Message::NewRequest => {
+                if self.has_unsaved_edits() {
+                    self.confirm_new_request = true;
+                } else {
+                    self.reset_request();
+                }
+            }
+            Message::ConfirmNewRequest => {
+                self.confirm_new_request = false;
+                self.reset_request();
+            }
+            Message::CancelNewRequest => { self.confirm_new_request = false; }
+            // Same URL, headers and body, another method; DELETE asks first
+            Message::ResendAs(method) => {
+                if method == Method::DELETE {
+                    self.confirm_resend = Some(method);
+                } else {
+                    self.method = method;
+                    return self.update(Message::Send);
+                }
+            }
+            Message::ConfirmResend => {
+                if let Some(method) = self.confirm_resend.take() {
+                    self.method = method;
+                    return self.update(Message::Send);
+                }
+            }
+            Message::CancelResend => { self.confirm_resend = None; }
+            Message::ToggleBatch => { self.show_batch = !self.show_batch; }
+            Message::BatchUrlsAction(action) => { self.batch_urls.perform(action); }
+            Message::RunBatch => {
+                if self.batch.as_ref().is_some_and(|b| !b.finished()) {
+                    return Task::none();
+                }
+                let urls: Vec<String> = self.batch_urls.text()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                if urls.is_empty() {
+                    return Task::none();
+                }
+                match self.prepare_request() {
+                    Ok(request) => {
+                        self.batch = Some(BatchRun {
+                            results: vec![None; urls.len()],
+                            urls,
+                            next: 0,
+                            stopped: false,
+                            request,
+                        });
+                        return Task::batch((0..BATCH_CONCURRENCY).map(|_| self.next_batch_send()));
+                    }
+                    Err(e) => self.toast(ToastKind::Error, e),
+                }
+            }
+            Message::StopBatch => {
+                if let Some(ref mut batch) = self.batch {
+                    batch.stopped = true;
+                }
+            }
+            Message::BatchResponse(index, result) => {
+                if let Some(ref mut batch) = self.batch
+                    && let Some(slot) = batch.results.get_mut(index)
+                {
+                    *slot = Some(result);
+                    return self.next_batch_send();
+                }
+            }
+            Message::BatchResultClicked(index) => {
+                let Some(batch) = self.batch.as_ref() else { return Task::none() };
+                if let (Some(url), Some(Some(result))) = (batch.urls.get(index), batch.results.get(index)) {
+                    let (url, result) = (url.clone(), result.clone());
+                    self.url = url;
+                    self.show_batch = false;
+                    match result {
+                        Ok(response) => {
+                            self.response = Some(response);
+                            self.error = None;
+                            self.refresh_response_view();
+                        }
+                        Err(e) => {
+                            self.response = None;
+                            self.error = Some(e);
+                        }
+                    }
+                }
+            }
+            Message::ToggleTimingLog => { self.show_timing_log = !self.show_timing_log; }
+            Message::TimingLogSortToggled(slowest_first) => { self.timing_log_slowest_first = slowest_first; }
+            Message::TimingLogEntryClicked(index) => {
+                if let Some(entry) = self.timing_log.get(index).cloned() {
+                    self.load_saved_request(&entry.request);
+                    self.show_timing_log = false;
+                }
+            }
+            Message::ClearTimingLog => { self.timing_log.clear(); }
+            Message::ToggleFindReplace => {
+                self.show_find_replace = !self.show_find_replace;
+                if self.show_find_replace {
+                    self.request_tab = Tab::Body;
+                    return iced::widget::operation::focus(FIND_INPUT_ID);
+                }
+            }
+            Message::FindQueryChanged(query) => { self.find_query = query; }
+            Message::ReplaceTextChanged(replacement) => { self.replace_text = replacement; }
+            Message::FindNext => {
+                let body = self.request_body.text();
+                let from = position_to_offset(&body, self.request_body.cursor().position);
+                if let Some(start) = find_from(&body, &self.find_query, from) {
+                    let end = start + self.find_query.len();
+                    self.request_body.move_to(text_editor::Cursor {
+                        position: offset_to_position(&body, end),
+                        selection: Some(offset_to_position(&body, start)),
+                    });
+                }
+            }
+            Message::ReplaceNext => {
+                let body = self.request_body.text();
+                // Start at the selection so a match highlighted by FindNext is the one replaced
+                let cursor = self.request_body.cursor();
+                let from = position_to_offset(&body, cursor.position);
+                let from = cursor.selection.map_or(from, |s| from.min(position_to_offset(&body, s)));
+                if let Some(start) = find_from(&body, &self.find_query, from) {
+                    let mut replaced = body.clone();
+                    replaced.replace_range(start..start + self.find_query.len(), &self.replace_text);
+                    self.request_body = text_editor::Content::with_text(&replaced);
+                    let end = start + self.replace_text.len();
+                    self.request_body.move_to(text_editor::Cursor {
+                        position: offset_to_position(&replaced, end),
+                        selection: None,
+                    });
+                    self.refresh_body_file();
+                }
+            }
+            Message::ReplaceAll => {
+                if !self.find_query.is_empty() {
+                    let body = self.request_body.text();
+                    if body.contains(&self.find_query) {
+                        let replaced = body.replace(&self.find_query, &self.replace_text);
+                        set_editor_text(&mut self.request_body, &replaced);
+                        self.refresh_body_file();
+                    }
+                }
+            }
+            Message::EnvironmentSelected(choice) => {
+                if let Some(previous) = self.active_environment
+                    && choice.index != Some(previous)
+                {
+                    self.recent_environments.retain(|&i| i != previous);
+                    self.recent_environments.insert(0, previous);
+                }
+                self.active_environment = choice.index;
+                self.show_env_switcher = false;
+                self.sync_env_variables_editor();
+                self.save_environments();
+            }
+            Message::OpenEnvSwitcher => {
+                if self.show_env_switcher {
+                    // Pressing the shortcut again walks down the list
+                    return self.update(Message::UrlSuggestionMoved(1));
+                }
+                self.show_env_switcher = true;
+                self.env_switcher_query.clear();
+                // Start on the most recent other environment, so Cmd+E, Enter flips back
+                self.env_switcher_index = 1.min(self.env_switcher_choices().len().saturating_sub(1));
+                return iced::widget::operation::focus(ENV_SWITCHER_INPUT_ID);
+            }
+            Message::CloseEnvSwitcher => { self.show_env_switcher = false; }
+            Message::EnvSwitcherQueryChanged(query) => {
+                self.env_switcher_query = query;
+                self.env_switcher_index = 0;
+            }
+            Message::EnvSwitcherSubmit => {
+                if let Some(choice) = self.env_switcher_choices().into_iter().nth(self.env_switcher_index) {
+                    return self.update(Message::EnvironmentSelected(choice));
+                }
+            }
+            Message::ToggleSettings => {
+                // A new history cap only applies once the settings close, so typing
+                // "1000" doesn't trim to 1 entry on the way
+                if self.show_settings
+                    && let Some(max) = parse_max_history(&self.max_history_input)
+                    && max != self.settings.max_history
+                {
+                    self.settings.max_history = max;
+                    save_settings(&self.settings);
+                    if trim_history(&mut self.history, max) {
+                        save_history(&self.history);
+                    }
+                }
+                if self.client_cert_stale {
+                    self.refresh_client_cert_status();
+                }
+                self.show_settings = !self.show_settings;
+                self.max_history_input = self.settings.max_history.to_string();
+            }
+            Message::IncognitoToggled(enabled) => { self.incognito = enabled; }
+            Message::OffRecordToggled(enabled) => { self.off_record = enabled; }
+            Message::MaxHistoryChanged(input) => { self.max_history_input = input; }
+            Message::ConnectTimeoutSelected(TimeoutChoice(secs)) => {
+                self.settings.connect_timeout_secs = secs;
+                save_settings(&self.settings);
+            }
+            Message::TimeoutSelected(TimeoutChoice(secs)) => {
+                self.settings.timeout_secs = secs;
+                save_settings(&self.settings);
+            }
+            Message::MaxRedirectsSelected(RedirectChoice(max)) => {
+                self.settings.max_redirects = max;
+                save_settings(&self.settings);
+            }
+            Message::PoolSizeSelected(PoolSizeChoice(max)) => {
+                self.settings.pool_max_idle_per_host = max;
+                save_settings(&self.settings);
+            }
+            Message::PoolIdleSelected(PoolIdleChoice(secs)) => {
+                self.settings.pool_idle_timeout_secs = secs;
+                save_settings(&self.settings);
+            }
+            Message::LatencyBudgetSelected(LatencyBudget(ms)) => {
+                self.settings.latency_budget_ms = ms;
+                save_settings(&self.settings);
+                // The count is against one budget; start over with the new one
+                self.over_budget = 0;
+            }
+            Message::AfterSendTabSelected(choice) => {
+                self.settings.after_send_tab = choice;
+                save_settings(&self.settings);
+            }
+            Message::BodyChunkSelected(BodyChunkChoice(kb)) => {
+                self.settings.body_chunk_kb = kb;
+                save_settings(&self.settings);
+                self.refresh_response_view();
+            }
+            Message::ShowMoreBody => {
+                self.response_body_shown += self.settings.body_chunk_kb * 1024;
+                self.show_response_body();
+            }
+            Message::ShowWholeBody => {
+                self.response_body_shown = self.response_body_formatted.len();
+                self.show_response_body();
+            }
+            Message::BodyGotoChanged(input) => { self.body_goto = input; }
+            Message::GoToBodyPosition => {
+                let body = &self.response_body_formatted;
+                match body_goto_target(body, self.body_goto.trim()) {
+                    Ok((start, end)) => {
+                        // Load the chunk holding the target; the cursor moving scrolls the viewer to it
+                        if end > self.response_body_shown {
+                            self.response_body_shown = body[end..].find('\n').map_or(body.len(), |i| end + i + 1);
+                            self.show_response_body();
+                        }
+                        let body = &self.response_body_formatted;
+                        self.response_body_view.move_to(text_editor::Cursor {
+                            position: offset_to_position(body, end),
+                            selection: Some(offset_to_position(body, start)),
+                        });
+                    }
+                    Err(e) => self.toast(ToastKind::Error, e),
+                }
+            }
+            Message::ArrayStyleSelected(style) => {
+                self.settings.query_array_style = style;
+                save_settings(&self.settings);
+            }
+            Message::SendAndShow(tab) => {
+                self.show_tab_after_send = Some(tab);
+                return self.update(Message::Send);
+            }
+            Message::ProxyChanged(proxy) => {
+                self.settings.proxy = proxy;
+                save_settings(&self.settings);
+            }
+            Message::ArchiveDirChanged(dir) => {
+                self.settings.archive_dir = dir;
+                save_settings(&self.settings);
+            }
+            Message::AcceptInvalidCertsToggled(accept) => {
+                self.settings.accept_invalid_certs = accept;
+                save_settings(&self.settings);
+            }
+            Message::BeautifyOnPasteToggled(enabled) => {
+                self.settings.beautify_on_paste = enabled;
+                save_settings(&self.settings);
+            }
+            Message::PreserveBodyFormattingToggled(enabled) => {
+                self.settings.preserve_body_formatting = enabled;
+                save_settings(&self.settings);
+                self.refresh_response_view();
+            }
+            Message::DefaultHeadersChanged(action) => {
+                let is_edit = action.is_edit();
+                self.default_headers_editor.perform(action);
+                if is_edit {
+                    self.settings.default_headers = self.default_headers_editor.text();
+                    save_settings(&self.settings);
+                }
+            }
+            Message::UserAgentChanged(user_agent) => {
+                self.settings.user_agent = user_agent;
+                save_settings(&self.settings);
+            }
+            Message::UserAgentPresetSelected(preset) => {
+                self.settings.user_agent = preset.value.to_string();
+                save_settings(&self.settings);
+            }
+            // Loading the certificate reads files and may decrypt, so it waits for Enter
+            Message::ClientCertPathChanged(path) => {
+                self.settings.client_cert.cert_path = path;
+                self.client_cert_stale = true;
+                save_settings(&self.settings);
+            }
+            Message::ClientKeyPathChanged(path) => {
+                self.settings.client_cert.key_path = path;
+                self.client_cert_stale = true;
+                save_settings(&self.settings);
+            }
+            Message::ClientCertPassphraseChanged(passphrase) => {
+                self.settings.client_cert.passphrase = passphrase;
+                self.client_cert_stale = true;
+            }
+            Message::CheckClientCert => self.refresh_client_cert_status(),
+            Message::ToggleEnvironments => {
+                self.show_environments = !self.show_environments;
+                if !self.show_environments {
+                    self.save_environments();
+                }
+            }
+            Message::AddEnvironment => {
+                self.environments.push(Environment {
+                    name: format!("Environment {}", self.environments.len() + 1),
+                    base_url: String::new(),
+                    variables: String::new(),
+                    secrets: Vec::new(),
+                });
+                self.active_environment = Some(self.environments.len() - 1);
+                self.sync_env_variables_editor();
+                self.save_environments();
+            }
+            Message::DeleteEnvironment => {
+                if let Some(index) = self.active_environment.take()
+                    && index < self.environments.len()
+                {
+                    self.environments.remove(index);
+                    self.recent_environments.retain(|&i| i != index);
+                    for i in &mut self.recent_environments {
+                        if *i > index {
+                            *i -= 1;
+                        }
+                    }
+                    self.sync_env_variables_editor();
+                    self.save_environments();
+                }
+            }
+            Message::EnvironmentNameChanged(name) => {
+                if let Some(env) = self.active_environment_mut() {
+                    env.name = name;
+                }
+            }
+            Message::EnvironmentBaseUrlChanged(base_url) => {
+                if let Some(env) = self.active_environment_mut() {
+                    env.base_url = base_url;
+                }
+            }
+            Message::EnvironmentVariablesChanged(action) => {
+                self.env_variables_editor.perform(action);
+                let edited = self.env_variables_editor.text();
+                if let Some(env) = self.active_environment_mut() {
+                    env.variables = env.unmask_variables(&edited);
+                }
+            }
+            Message::EnvironmentSecretToggled(name, secret) => {
+                if let Some(env) = self.active_environment_mut() {
+                    env.secrets.retain(|s| *s != name);
+                    if secret {
+                        env.secrets.push(name);
+                    }
+                }
+                self.sync_env_variables_editor();
+                self.save_environments();
+            }
+            Message::ToggleSaveRequest => { self.show_save_request = !self.show_save_request; }
+            Message::SaveNameChanged(name) => { self.save_name = name; }
+            Message::SaveCollectionChanged(name) => { self.save_collection = name; }
+            Message::SaveRequest => {
+                let name = match self.save_name.trim() {
+                    "" => format!("{} {}", self.method, self.url),
+                    name => name.to_string(),
+                };
+                let collection_name = match self.save_collection.trim() {
+                    "" => "Default".to_string(),
+                    name => name.to_string(),
+                };
+                let saved = self.to_saved_request(name);
+                match self.collections.iter_mut().find(|c| c.name == collection_name) {
+                    Some(collection) => collection.requests.push(saved),
+                    None => self.collections.push(Collection {
+                        name: collection_name.clone(),
+                        requests: vec![saved],
+                    }),
+                }
+                save_collections(&self.collections);
+                self.request_snapshot = self.to_saved_request(String::new());
+                self.toast(ToastKind::Success, format!("Saved to {}", collection_name));
+                self.save_collection = collection_name;
+                self.save_name.clear();
+                self.show_save_request = false;
+            }
+            Message::SavedRequestClicked(collection, index) => {
+                if let Some(saved) = self.collections.get(collection).and_then(|c| c.requests.get(index)) {
+                    let saved = saved.clone();
+                    self.load_saved_request(&saved);
+                }
+            }
+            Message::ToggleSnippets => {
+                self.show_snippets = !self.show_snippets;
+                self.snippet_name.clear();
+            }
+            Message::SnippetNameChanged(name) => { self.snippet_name = name; }
+            Message::SaveSnippet => {
+                let name = self.snippet_name.trim().to_string();
+                if name.is_empty() {
+                    return Task::none();
+                }
+                let snippet = self.to_saved_request(name.clone());
+                // Saving under an existing name replaces that snippet
+                match self.snippets.iter_mut().find(|s| s.name == name) {
+                    Some(existing) => *existing = snippet,
+                    None => self.snippets.push(snippet),
+                }
+                save_snippets(&self.snippets);
+                self.snippet_name.clear();
+                self.toast(ToastKind::Success, format!("Saved snippet {}", name));
+            }
+            Message::SnippetClicked(index) => {
+                if let Some(snippet) = self.snippets.get(index).cloned() {
+                    self.load_saved_request(&snippet);
+                    self.show_snippets = false;
+                    let known = self.variables();
+                    let missing: Vec<String> = unresolved_variables(&snippet_text(&snippet))
+                        .into_iter()
+                        .filter(|name| !known.iter().any(|(k, _)| k == name))
+                        .collect();
+                    if !missing.is_empty() {
+                        self.toast(ToastKind::Warning, format!("Fill in {}", missing.join(", ")));
+                    }
+                }
+            }
+            Message::DeleteSnippet(index) => {
+                if index < self.snippets.len() {
+                    self.snippets.remove(index);
+                    save_snippets(&self.snippets);
+                }
+            }
+            Message::DeleteSavedRequest(collection, index) => {
+                // Indices shift under a running pass, so don't edit collections mid-run
+                if self.collection_run.is_none()
+                    && let Some(c) = self.collections.get_mut(collection)
+                    && index < c.requests.len()
+                {
+                    c.requests.remove(index);
+                    if c.requests.is_empty() {
+                        self.collections.remove(collection);
+                    }
+                    save_collections(&self.collections);
+                }
+            }
+            Message::ExportCollection(index) => {
+                if let Some(collection) = self.collections.get(index) {
+                    let file_name = format!("{}.postman_collection.json", archive_name(&collection.name));
+                    let data = serde_json::to_string_pretty(&postman_collection(collection)).unwrap_or_default();
+                    return Task::perform(export_file(file_name, data.into_bytes(), Some(("JSON", &["json"]))), Message::FileExported);
+                }
+            }
+            Message::ExportHttpFile => {
+                let saved = self.to_saved_request(String::new());
+                let file_name = format!("{}.http", archive_name(&self.url));
+                let data = http_file(&saved, self.response.as_ref());
+                let filter = Some(("HTTP", &["http", "rest"][..]));
+                return Task::perform(export_file(file_name, data.into_bytes(), filter), Message::FileExported);
+            }
+            Message::SaveResponseBody => {
+                if let Some(ref response) = self.response {
+                    let data = response.raw_body.clone().unwrap_or_else(|| response.body.clone().into_bytes());
+                    return Task::perform(export_file(response_file_name(response), data, None), Message::FileExported);
+                }
+            }
+            Message::FileExported(result) => match result {
+                Ok(Some(path)) => self.toast(ToastKind::Success, format!("Exported to {}", path)),
+                Ok(None) => {}
+                Err(e) => self.toast(ToastKind::Error, format!("Export failed: {}", e)),
+            },
+            Message::ImportPostman => {
+                return Task::perform(import_file("JSON", &["json"]), Message::PostmanFileLoaded);
+            }
+            Message::ImportHttpFile => {
+                return Task::perform(import_file("HTTP", &["http", "rest"]), Message::HttpFileLoaded);
+            }
+            Message::HttpFileLoaded(result) => match result {
+                Ok(Some((stem, data))) => {
+                    let (requests, variables) = parse_http_file(&data);
+                    if requests.is_empty() {
+                        self.toast(ToastKind::Error, "Import failed: no requests in the file");
+                        return Task::none();
+                    }
+                    let count = requests.len();
+                    let name = if stem.is_empty() { "Imported".to_string() } else { stem };
+                    match self.collections.iter_mut().find(|c| c.name == name) {
+                        Some(existing) => existing.requests.extend(requests),
+                        None => self.collections.push(Collection { name: name.clone(), requests }),
+                    }
+                    save_collections(&self.collections);
+                    // File variables go to an environment of the same name, added to any already there
+                    if !variables.is_empty() {
+                        let lines: Vec<String> = variables.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                        match self.environments.iter_mut().find(|e| e.name == name) {
+                            Some(env) => {
+                                if !env.variables.is_empty() && !env.variables.ends_with('\n') {
+                                    env.variables.push('\n');
+                                }
+                                env.variables.push_str(&lines.join("\n"));
+                            }
+                            None => self.environments.push(Environment {
+                                name: name.clone(),
+                                base_url: String::new(),
+                                variables: lines.join("\n"),
+                                secrets: Vec::new(),
+                            }),
+                        }
+                        self.save_environments();
+                        self.sync_env_variables_editor();
+                    }
+                    self.toast(
+                        ToastKind::Success,
+                        format!("Imported {} requests and {} variables into {}", count, variables.len(), name),
+                    );
+                }
+                Ok(None) => {}
+                Err(e) => self.toast(ToastKind::Error, format!("Import failed: {}", e)),
+            },
+            Message::PostmanFileLoaded(result) => match result.and_then(|file| file.map(|(_, data)| parse_postman(&data)).transpose()) {
+                Ok(Some(imported)) => {
+                    let requests: usize = imported.iter().map(|c| c.requests.len()).sum();
+                    let collections = imported.len();
+                    // Same-named collections are merged, as when saving a request
+                    for collection in imported {
+                        match self.collections.iter_mut().find(|c| c.name == collection.name) {
+                            Some(existing) => existing.requests.extend(collection.requests),
+                            None => self.collections.push(collection),
+                        }
+                    }
+                    save_collections(&self.collections);
+                    self.toast(
+                        ToastKind::Success,
+                        format!("Imported {} requests into {} collections", requests, collections),
+                    );
+                }
+                Ok(None) => {}
+                Err(e) => self.toast(ToastKind::Error, format!("Import failed: {}", e)),
+            },
+            Message::RunCollection(collection) => {
+                let has_requests = self.collections.get(collection).is_some_and(|c| !c.requests.is_empty());
+                if has_requests && !self.loading {
+                    self.collection_run = Some(CollectionRun {
+                        collection,
+                        next: 0,
+                        results: Vec::new(),
+                        stopped: false,
+                        editor: Some((self.to_saved_request(String::new()), self.request_snapshot.clone())),
+                        off_record: self.off_record,
+                    });
+                    return Task::done(Message::RunCollectionStep);
+                }
+            }
+            Message::RunCollectionStep => {
+                let Some(run) = self.collection_run.as_mut() else { return Task::none() };
+                if run.stopped || run.in_flight() {
+                    return Task::none();
+                }
+                let Some(saved) = self.collections
+                    .get(run.collection)
+                    .and_then(|c| c.requests.get(run.next))
+                    .cloned()
+                else {
+                    return Task::none();
+                };
+                run.next += 1;
+                let off_record = run.off_record;
+                self.load_saved_request(&saved);
+                self.off_record = off_record;
+                return self.update(Message::Send);
+            }
+            Message::StopOnFailureToggled(enabled) => { self.run_stop_on_failure = enabled; }
+            Message::StopCollectionRun => {
+                if let Some(run) = self.collection_run.as_mut() {
+                    run.stopped = true;
+                }
+            }
+            Message::CheckConnectivity if self.settings.check_connectivity && !self.incognito => {
+                return Task::perform(check_online(self.settings.proxy.clone()), Message::ConnectivityChecked);
+            }
+            Message::CheckConnectivity => {}
+            Message::ConnectivityChecked(online) => { self.online = online; }
+            Message::CheckConnectivityToggled(enabled) => {
+                self.settings.check_connectivity = enabled;
+                save_settings(&self.settings);
+                self.online = true;
+                if enabled {
+                    return self.update(Message::CheckConnectivity);
+                }
+            }
+            Message::CloseModal => {
+                if let Some(close) = self.modal_close_message() {
+                    return self.update(close);
+                }
+            }
+            Message::FocusNext => {
+                // Tab accepts a highlighted URL suggestion or variable name
+                if self.variable_completion.as_ref().is_some_and(|c| c.target == CompletionTarget::Url) {
+                    return self.update(Message::AcceptVariableCompletion);
+                }
+                if let Some(index) = self.url_suggestion {
+                    return self.update(Message::UrlSuggestionSelected(index));
+                }
+                return iced::widget::operation::focus_next();
+            }
+            Message::FocusPrevious => return iced::widget::operation::focus_previous(),
+            Message::CloseCollectionRun => {
+                if !self.collection_run.as_ref().is_some_and(CollectionRun::in_flight) {
+                    if let Some(run) = self.collection_run.as_mut() {
+                        run.stopped = true;
+                    }
+                    self.restore_run_editor();
+                    self.collection_run = None;
+                }
+            }
+            Message::FollowRedirect(target) => {
+                // 303 always continues with a GET; other codes keep the method
+                if self.response.as_ref().is_some_and(|r| r.status == 303) {
+                    self.method = Method::GET;
+                }
+                self.url = target;
+                return self.update(Message::Send);
+            }
+            Message::Send => {
+                self.dismiss_url_suggestions();
+                let request = match self.prepare_request() {
+                    Ok(request) => request,
+                    Err(e) => {
+                        self.request_tab = Tab::Body;
+                        self.show_tab_after_send = None;
+                        if self.polling {
+                            self.polling = false;
+                            self.toast(ToastKind::Warning, "Polling stopped: request could not be built");
+                        }
+                        if self.watch.take().is_some() {
+                            self.toast(ToastKind::Warning, "Watch stopped: request could not be built");
+                        }
+                        // A collection run is waiting on this step; let it record the failure
+                        if self.collection_run.as_ref().is_some_and(CollectionRun::in_flight) {
+                            return Task::done(Message::ResponseReceived(Err(e)));
+                        }
+                        return Task::none();
+                    }
+                };
+                self.loading = true;
+                self.error = None;
+                self.pagination = None;
+                return Task::perform(send_request(request), Message::ResponseReceived);
+            }
+            Message::CheckCors => {
+                match self.prepare_request() {
+                    Ok(request) => {
+                        let preflight = cors_preflight(&request);
+                        let options = preflight.request(request);
+                        self.cors_pending = Some(preflight);
+                        self.cors_verdict = None;
+                        return Task::perform(send_request(options), Message::CorsChecked);
+                    }
+                    Err(e) => self.toast(ToastKind::Error, e),
+                }
+            }
+            Message::CorsChecked(result) => {
+                if let Some(preflight) = self.cors_pending.take() {
+                    self.cors_verdict = Some(result.map(|response| cors_verdict(&preflight, &response)));
+                }
+            }
+            Message::DismissCors => { self.cors_verdict = None; }
+            Message::InspectJwt(token) => { self.inspected_jwt = Some(token); }
+            // Comment or uncomment the cursor's line, or every line the selection touches
+            Message::ToggleHeaderLines => {
+                let cursor = self.request_headers.cursor();
+                let anchor = cursor.selection.unwrap_or(cursor.position);
+                let lines = cursor.position.line.min(anchor.line)..=cursor.position.line.max(anchor.line);
+                let toggled = toggle_header_lines(&self.request_headers.text(), lines);
+                set_editor_text(&mut self.request_headers, &toggled);
+            }
+            Message::CanonicalizeHeaders => {
+                let headers = canonicalize_headers(&self.request_headers.text());
+                set_editor_text(&mut self.request_headers, &headers);
+            }
+            Message::CloseJwt => { self.inspected_jwt = None; }
+            Message::ResponseReceived(result) => {
+                self.loading = false;
+                let after_send = self.show_tab_after_send.take().or(self.settings.after_send_tab.tab());
+                let next_step = self.record_run_step(&result);
+                if self.collection_run.is_none() {
+                    self.check_watch(result.as_ref().map(|response| response.status).ok());
+                }
+                match result {
+                    Ok(response) => {
+                        let entry = HistoryEntry {
+                            method: self.method,
+                            url: self.url.clone(),
+                            status: response.status,
+                        };
+                        // Polling only adds to history when the outcome changes
+                        let repeat = self.polling && self.history.last().is_some_and(|last| {
+                            last.method == entry.method && last.url == entry.url && last.status == entry.status
+                        });
+                        // Incognito and off-record sends leave history (in memory and on disk) untouched
+                        let recorded = !self.incognito && !self.off_record;
+                        if !repeat && recorded {
+                            self.history.push(entry);
+                            trim_history(&mut self.history, self.settings.max_history);
+                            save_history(&self.history);
+                        }
+                        if recorded {
+                            self.timing_log.push(TimingEntry {
+                                request: self.to_saved_request(String::new()),
+                                status: response.status,
+                                duration: response.duration,
+                            });
+                            if self.timing_log.len() > TIMING_LOG_MAX {
+                                self.timing_log.remove(0);
+                            }
+                        }
+                        let archive_dir = self.settings.archive_dir.trim();
+                        if !archive_dir.is_empty()
+                            && recorded
+                            && let Err(e) = archive_response(std::path::Path::new(archive_dir), self.method, &response)
+                        {
+                            self.toast(ToastKind::Warning, format!("Could not archive response: {}", e));
+                        }
+                        // Polls, watches and collection steps leave the tab alone
+                        if let Some(tab) = after_send
+                            && !self.polling
+                            && self.watch.is_none()
+                            && self.collection_run.is_none()
+                        {
+                            self.response_tab = tab;
+                        }
+                        if self.polling && self.poll_stop_on_error && response.is_http_error() {
+                            self.polling = false;
+                            self.toast(ToastKind::Warning, format!("Polling stopped: HTTP {}", response.status));
+                        }
+                        if self.over_latency_budget(response.duration) {
+                            self.over_budget += 1;
+                            if self.collection_run.is_none() {
+                                self.toast(
+                                    ToastKind::Warning,
+                                    format!(
+                                        "Took {}ms, over the {}ms budget",
+                                        response.duration.as_millis(),
+                                        self.settings.latency_budget_ms,
+                                    ),
+                                );
+                            }
+                        }
+                        let samples = self.latency_history.entry(latency_key(&response.url)).or_default();
+                        samples.push(response.duration);
+                        if samples.len() > LATENCY_SAMPLES {
+                            samples.remove(0);
+                        }
+                        let follow = self.fetch_all_pages && self.collection_run.is_none();
+                        if follow {
+                            self.pagination = Some(Pagination {
+                                pages: 1,
+                                items: page_items(&response.body),
+                                error: None,
+                            });
+                        }
+                        self.capture_headers(&response, recorded);
+                        self.response = Some(response);
+                        self.error = None;
+                        self.refresh_response_view();
+                        if follow {
+                            return self.fetch_next_page();
+                        }
+                    }
+                    Err(e) => {
+                        if self.polling && self.poll_stop_on_error {
+                            self.polling = false;
+                            self.toast(ToastKind::Warning, "Polling stopped: request failed");
+                        }
+                        self.error = Some(e);
+                        self.response = None;
+                    }
+                }
+                self.restore_run_editor();
+                return next_step;
+            }
+            Message::PageReceived(result) => {
+                self.loading = false;
+                let Some(pagination) = self.pagination.as_mut() else { return Task::none() };
+                match result {
+                    Ok(response) => {
+                        pagination.pages += 1;
+                        pagination.items.extend(page_items(&response.body));
+                        self.response = Some(response);
+                        self.refresh_response_view();
+                        return self.fetch_next_page();
+                    }
+                    Err(e) => {
+                        pagination.error = Some(format!("Page {} failed: {}", pagination.pages + 1, e));
+                        self.finish_pagination();
+                    }
+                }
+            }
+            Message::FetchAllPagesToggled(enabled) => { self.fetch_all_pages = enabled; }
+            Message::PollingToggled(enabled) => {
+                self.polling = enabled;
+                if enabled {
+                    self.watch = None;
+                }
+                if enabled && !self.loading {
+                    return self.update(Message::Send);
+                }
+            }
+            Message::PollIntervalSelected(interval) => { self.poll_interval = interval; }
+            Message::PollStopOnErrorToggled(enabled) => { self.poll_stop_on_error = enabled; }
+            Message::PollTick => {
+                // Skip this tick if the previous send is still running
+                if self.polling && !self.loading && self.collection_run.is_none() {
+                    return self.update(Message::Send);
+                }
+            }
+            Message::ScheduleInputChanged(input) => { self.schedule_input = input; }
+            Message::ScheduleSend => {
+                let delay = parse_schedule(&self.schedule_input, chrono::Local::now()).and_then(|delay| {
+                    let at = Instant::now().checked_add(delay).ok_or("That delay is too long")?;
+                    Ok((delay, at))
+                });
+                match delay {
+                    Ok((delay, at)) => {
+                        // Replaces any send already waiting
+                        self.scheduled_send = Some(ScheduledSend {
+                            at,
+                            request: self.to_saved_request(String::new()),
+                        });
+                        self.toast(ToastKind::Success, format!("Sending in {}", format_countdown(delay)));
+                    }
+                    Err(e) => self.toast(ToastKind::Error, e),
+                }
+            }
+            Message::CancelScheduledSend => {
+                if self.scheduled_send.take().is_some() {
+                    self.toast(ToastKind::Success, "Scheduled send cancelled");
+                }
+            }
+            Message::ScheduleTick(now) => {
+                let Some(ref scheduled) = self.scheduled_send else { return Task::none() };
+                // Editing the request drops the send rather than firing something else
+                if scheduled.request != self.to_saved_request(String::new()) {
+                    self.scheduled_send = None;
+                    self.toast(ToastKind::Warning, "Scheduled send cancelled: the request was edited");
+                } else if now >= scheduled.at && !self.loading {
+                    self.scheduled_send = None;
+                    return self.update(Message::Send);
+                }
+            }
+            Message::WatchToggled(enabled) => {
+                if !enabled {
+                    self.watch = None;
+                    return Task::none();
+                }
+                match parse_watch_statuses(&self.watch_status) {
+                    Ok(statuses) => {
+                        self.polling = false;
+                        self.watch = Some(Watch {
+                            started: Instant::now(),
+                            attempts: 0,
+                            next_at: Some(Instant::now()),
+                            statuses,
+                            limit: Duration::from_secs(self.watch_limit.0),
+                        });
+                    }
+                    Err(e) => self.toast(ToastKind::Error, e),
+                }
+            }
+            Message::WatchStatusChanged(input) => { self.watch_status = input; }
+            Message::WatchLimitSelected(limit) => { self.watch_limit = limit; }
+            Message::WatchTick(now) => {
+                let Some(ref mut watch) = self.watch else { return Task::none() };
+                if !self.loading && watch.next_at.is_some_and(|at| now >= at) {
+                    watch.next_at = None;
+                    return self.update(Message::Send);
+                }
+            }
+            Message::NextPagePathChanged(path) => { self.next_page_path = path; }
+            Message::GroupHistoryByHostToggled(group) => { self.group_history_by_host = group; }
+            Message::ToggleHostGroup(host) => {
+                if !self.expanded_hosts.remove(&host) {
+                    self.expanded_hosts.insert(host);
+                }
+            }
+            Message::FocusPanel(panel) => {
+                match self.panel_focus {
+                    // Same panel again: back to the layout from before focusing
+                    Some((focused, prior)) if focused == panel => {
+                        self.request_width_target = prior;
+                        self.panel_focus = None;
+                    }
+                    other => {
+                        let prior = other.map_or(self.request_width_target, |(_, prior)| prior);
+                        self.request_width_target = match panel {
+                            PanelFocus::Request => 0.9,
+                            PanelFocus::Response => 0.1,
+                        };
+                        self.panel_focus = Some((panel, prior));
+                    }
+                }
+            }
+            Message::ClearHistory => {
+                self.history.clear();
+                save_history(&self.history);
+                self.toast(ToastKind::Success, "History cleared");
+            }
+            Message::TogglePalette => {
+                self.show_palette = !self.show_palette;
+                if self.show_palette {
+                    self.palette_query.clear();
+                    self.palette_index = 0;
+                    return iced::widget::operation::focus(PALETTE_INPUT_ID);
+                }
+            }
+            Message::ToggleShortcuts => { self.show_shortcuts = !self.show_shortcuts; }
+            Message::PaletteQueryChanged(query) => {
+                self.palette_query = query;
+                self.palette_index = 0;
+            }
+            Message::PaletteRun(index) => {
+                if let Some(command) = self.palette_commands().into_iter().nth(index) {
+                    self.show_palette = false;
+                    return self.update(command.message);
+                }
+            }
+            Message::RepeatLastRequest => {
+                if !self.loading && !self.history.is_empty() {
+                    return Task::done(Message::HistoryEntryClicked(self.history.len() - 1))
+                        .chain(Task::done(Message::Send));
+                }
+            }
+            Message::HistoryEntryClicked(index) => {
+                if let Some(entry) = self.history.get(index) {
+                    self.url = entry.url.clone();
+                    self.method = entry.method;
+                }
+            }
+            Message::CopyAsTable(delimiter) => {
+                if let Some(table) = self.response.as_ref().and_then(|r| json_to_table(&r.body, delimiter)) {
+                    let format = if delimiter == '\t' { "TSV" } else { "CSV" };
+                    match copy_to_clipboard(table) {
+                        Ok(()) => self.toast(ToastKind::Success, format!("Copied as {}", format)),
+                        Err(e) => self.toast(ToastKind::Error, e),
+                    }
+                }
+            }
+            // Feed the response back in: the body goes to the editor, then the URL is up next
+            Message::UseResponseAsBody => {
+                if let Some(ref response) = self.response {
+                    let body = format_json(&self.response_body_text(response));
+                    self.request_body = text_editor::Content::with_text(&body);
+                    self.body_mode = BodyMode::Text;
+                    self.body_file = None;
+                    if self.method == Method::GET {
+                        self.method = Method::POST;
+                    }
+                    self.request_tab = Tab::Body;
+                    return iced::widget::operation::focus(URL_INPUT_ID);
+                }
+            }
+            Message::CopyResponse => {
+                if let Some(ref response) = self.response {
+                    let text = match self.response_tab {
+                        Tab::Body | Tab::Tree | Tab::Params | Tab::Auth | Tab::Vars => self.displayed_body(response),
+                        Tab::Headers => response.headers.iter()
+                            .map(|(k, v)| format!("{}: {}", k, v))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        Tab::Wire => self.wire_text(response),
+                        Tab::Flat => self.response_flat_view.as_ref().map(text_editor::Content::text).unwrap_or_default(),
+                        Tab::Cookies => response_cookies(response).iter()
+                            .map(SetCookie::describe)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        Tab::Compare => format_json(&self.response_body_text(response)),
+                        Tab::Schema => serde_json::from_str::<serde_json::Value>(&response.body)
+                            .map(|value| {
+                                render_schema(&infer_schema(&value))
+                                    .into_iter()
+                                    .map(|(s, _)| s)
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                        Tab::Timing => format!(
+                            "Total Time: {}ms\nResponse Size: {}\nTransfer Speed: {:.1} KB/s",
+                            response.duration.as_millis(),
+                            format_size(response.size),
+                            if response.duration.as_secs_f64() > 0.0 {
+                                (response.size as f64 / response.duration.as_secs_f64()) / 1024.0
+                            } else { 0.0 }
+                        ),
+                    };
+                    match copy_to_clipboard(text) {
+                        Ok(()) => self.toast(ToastKind::Success, "Copied to clipboard"),
+                        Err(e) => self.toast(ToastKind::Error, e),
+                    }
+                }
+            }
+            Message::CopySummary => {
+                if let Some(ref response) = self.response {
+                    match copy_to_clipboard(response_summary(self.method, &self.effective_url(), response)) {
+                        Ok(()) => self.toast(ToastKind::Success, "Copied summary"),
+                        Err(e) => self.toast(ToastKind::Error, e),
+                    }
+                }
+            }
+            Message::CopyRustTypes => {
+                if let Some(ref response) = self.response {
+                    match serde_json::from_str::<serde_json::Value>(&response.body) {
+                        Ok(value) => match copy_to_clipboard(rust_types(&infer_schema(&value))) {
+                            Ok(()) => self.toast(ToastKind::Success, "Copied Rust types"),
+                            Err(e) => self.toast(ToastKind::Error, e),
+                        },
+                        Err(_) => self.toast(ToastKind::Error, "Response body is not JSON"),
+                    }
+                }
+            }
+            Message::JsonValueClicked { value, path } => {
+                self.json_breadcrumb = Some(path);
+                return self.update(Message::CopyJsonValue(value));
+            }
+            Message::CopyJsonValue(value) => {
+                match copy_to_clipboard(value.clone()) {
+                    Ok(()) => self.toast(ToastKind::Success, format!("Copied {}", truncate_str(&value, 40))),
+                    Err(e) => self.toast(ToastKind::Error, e),
+                }
+            }
+            Message::PinResponse => {
+                if let Some(ref response) = self.response {
+                    self.pinned_response = Some(response.clone());
+                    self.toast(ToastKind::Success, "Pinned response for comparison");
+                }
+            }
+            Message::UnpinResponse => {
+                self.pinned_response = None;
+                if self.response_tab == Tab::Compare {
+                    self.response_tab = Tab::Body;
+                }
+            }
+            Message::ToggleRevealCredentials => { self.reveal_credentials = !self.reveal_credentials; }
+            Message::SortResponseKeysToggled(sort) => {
+                self.sort_response_keys = sort;
+                self.refresh_response_view();
+            }
+            Message::ShowValueTypesToggled(show) => { self.show_value_types = show; }
+            Message::MarkdownRawToggled(raw) => { self.markdown_raw = raw; }
+            Message::ShowEnvelopeToggled(show) => { self.show_envelope = show; }
+            Message::ToggleTreeNode(path) => {
+                if !self.tree_expanded.remove(&path) {
+                    self.tree_expanded.insert(path);
+                }
+            }
+            Message::FoldLongStringsToggled(fold) => {
+                self.fold_long_strings = fold;
+            }
+            Message::ToggleLongString(key) => {
+                if !self.expanded_strings.remove(&key) {
+                    self.expanded_strings.insert(key);
+                }
+            }
+            Message::ExpandAll => self.expand_tree_to(TREE_MAX_EXPAND_DEPTH),
+            Message::ExpandToDepth(depth) => self.expand_tree_to(depth),
+            Message::CollapseAll => self.tree_expanded.clear(),
+            Message::OpenInBrowser => {
+                if let Some(ref response) = self.response {
+                    let path = std::env::temp_dir().join("badgateway-response.html");
+                    let html = with_base_href(&response.body, &response.url);
+                    let result = std::fs::write(&path, html)
+                        .map_err(|e| e.to_string())
+                        .and_then(|_| open_in_browser(&path));
+                    match result {
+                        Ok(()) => self.toast(ToastKind::Success, "Opened in browser"),
+                        Err(e) => self.toast(ToastKind::Error, format!("Could not open browser: {}", e)),
+                    }
+                }
+            }
+            Message::StartDrag(target) => {
+                self.dragging = Some(target);
+                self.drag_last_x = None;
+            }
+            Message::Drag(x) => {
+                let delta = self.drag_last_x.map_or(0.0, |last| x - last);
+                self.drag_last_x = Some(x);
+                if let Some(target) = self.dragging {
+                    match target {
+                        DragTarget::Sidebar => {
+                            self.sidebar_width_target =
+                                (self.sidebar_width_target + delta).clamp(120.0, self.max_sidebar_width());
+                            self.sidebar_width = self.sidebar_width_target;
+                        }
+                        DragTarget::RequestPanel => {
+                            let delta_ratio = delta / self.panels_width();
+                            self.request_width_target = (self.request_width_target + delta_ratio).clamp(0.25, 0.75);
+                            self.request_width = self.request_width_target;
+                            self.panel_focus = None;
+                        }
+                    }
+                }
+            }
+            Message::EndDrag => {
+                self.dragging = None;
+                self.drag_last_x = None;
+            }
+            Message::WindowResized(size) => {
+                self.window_width = size.width;
+                self.window_height = size.height;
+                let max = self.max_sidebar_width();
+                self.sidebar_width_target = self.sidebar_width_target.min(max);
+                self.sidebar_width = self.sidebar_width.min(max);
+            }
+            Message::Tick(now) => {
+                for toast in &mut self.toasts {
+                    toast.age = now.saturating_duration_since(toast.created);
+                }
+                self.toasts.retain(|t| t.age < TOAST_DURATION);
+
+                // Smooth animation with easing
+                let ease = 0.15;
+                self.sidebar_width += (self.sidebar_width_target - self.sidebar_width) * ease;
+                self.request_width += (self.request_width_target - self.request_width) * ease;
+            }
+        }
+        Task::none()
+    }
+
+    /// Widest the sidebar may be dragged in the current window.
+    fn max_sidebar_width(&self) -> f32 {
+        (self.window_width * SIDEBAR_MAX_FRACTION).clamp(120.0, 400.0)
+    }
+
+    /// Narrow windows drop the sidebar entirely.
+    fn sidebar_collapsed(&self) -> bool {
+        self.window_width < NARROW_WINDOW_WIDTH
+    }
+
+    /// Width shared by the request and response panels, used to turn drag
+    /// distance into a ratio.
+    fn panels_width(&self) -> f32 {
+        let sidebar = if self.sidebar_collapsed() { 0.0 } else { self.sidebar_width };
+        (self.window_width - sidebar - RESIZE_HANDLES_WIDTH).max(1.0)
+    }
+
+    /// Snapshot the editors into a request ready to send.
+    /// Fails if the body can't be encoded (e.g. malformed hex).
+    fn outgoing_request(&self) -> Result<OutgoingRequest, String> {
+        let vars = self.variables();
+        let resolve = |s: &str| substitute_variables(s, &vars);
+        let url = self.effective_url();
+
+        let mut body_file = None;
+        let (body, default_content_type) = match self.body_mode {
+            BodyMode::Text => (self.resolved_text_body()?.into_bytes(), None),
+            BodyMode::Hex => (
+                parse_hex(&self.request_body.text()).map_err(|e| format!("Invalid hex body: {}", e))?,
+                Some("application/octet-stream"),
+            ),
+            BodyMode::Image => match self.pasted_image {
+                Some(ref image) => (image.png.clone(), Some("image/png")),
+                None => return Err("Paste an image to send as the body".to_string()),
+            },
+            BodyMode::File => {
+                let path = resolve(self.request_body.text().trim());
+                if !std::path::Path::new(&path).is_file() {
+                    return Err(format!("Body file not found: {}", path));
+                }
+                let content_type = file_content_type(&path);
+                body_file = Some(path);
+                (Vec::new(), Some(content_type))
+            }
+        };
+
+        Ok(OutgoingRequest {
+            url,
+            method: self.method,
+            body,
+            body_file,
+            headers: resolve(&merge_default_headers(&self.settings.default_headers, &self.request_headers.text())),
+            auth_type: self.auth_type,
+            auth_token: resolve(&self.auth_token),
+            auth_username: resolve(&self.auth_username),
+            auth_password: resolve(&self.auth_password),
+            auth_domain: resolve(&self.auth_domain),
+            default_content_type,
+            client: ClientOptions::from_settings(&self.settings),
+            body_any_method: self.body_any_method,
+            user_agent: Some(self.settings.user_agent.trim().to_string()).filter(|ua| !ua.is_empty()),
+            chunked: self.chunked_body,
+            gzip: self.gzip_body,
+            pooled: None,
+        })
+    }
+
+    /// The Text mode body with variables substituted and, if asked for,
+    /// canonicalized. Shared by Send and the body preview.
+    fn resolved_text_body(&self) -> Result<String, String> {
+        let resolved = substitute_variables(&self.request_body.text(), &self.variables());
+        if !self.canonical_json_body || resolved.trim().is_empty() {
+            return Ok(resolved);
+        }
+        canonical_json(&resolved).ok_or_else(|| "Canonical JSON is on, but the body isn't valid JSON".to_string())
+    }
+
+    /// `outgoing_request` plus the shared client, rebuilt only when the
+    /// client settings have changed since the last send
+    fn prepare_request(&mut self) -> Result<OutgoingRequest, String> {
+        let mut request = self.outgoing_request()?;
+        // Socket clients are per-path, so they aren't shared
+        if split_unix_socket_url(&request.url).is_none() {
+            let client = match &self.http_client {
+                Some((options, client)) if *options == request.client => client.clone(),
+                _ => {
+                    let client = request.client.clone().client_builder()?.build().map_err(|e| e.to_string())?;
+                    self.http_client = Some((request.client.clone(), client.clone()));
+                    client
+                }
+            };
+            request.pooled = Some(client);
+        }
+        Ok(request)
+    }
+
+    fn active_environment(&self) -> Option<&Environment> {
+        self.active_environment.and_then(|i| self.environments.get(i))
+    }
+
+    fn active_environment_mut(&mut self) -> Option<&mut Environment> {
+        self.active_environment.and_then(|i| self.environments.get_mut(i))
+    }
+
+    /// Commands matching the palette query, in display order. Entries only
+    /// appear while they can do something.
+    fn palette_commands(&self) -> Vec<PaletteCommand> {
+        let command = |label, shortcut, message| PaletteCommand { label, shortcut, message };
+        let has_response = self.response.is_some();
+        let mut commands = vec![
+            command("Send request", "Cmd+Enter", Message::Send),
+            command("Repeat last request", "Cmd+R", Message::RepeatLastRequest),
+            command("New request", "Cmd+N", Message::NewRequest),
+            command("Save request to collection", "", Message::ToggleSaveRequest),
+            command("Snippets", "", Message::ToggleSnippets),
+            command("Timing log", "", Message::ToggleTimingLog),
+            command("Send to a list of URLs", "", Message::ToggleBatch),
+            command("Check CORS preflight", "", Message::CheckCors),
+            command("Beautify body, headers and params", "Cmd+Shift+F", Message::Beautify),
+            command("Find and replace in body", "Cmd+H", Message::ToggleFindReplace),
+            command("Import cURL command", "", Message::ToggleCurlImport),
+            command("Import Postman collection", "", Message::ImportPostman),
+            command("Import .http file", "", Message::ImportHttpFile),
+            command("Export request as .http file", "", Message::ExportHttpFile),
+            command("Save response body to file", "", Message::SaveResponseBody),
+            command("Normalize header name casing", "", Message::CanonicalizeHeaders),
+            command("Switch environment", "Cmd+E", Message::OpenEnvSwitcher),
+            command("Edit environments", "", Message::ToggleEnvironments),
+            command("Focus request panel", "Cmd+J", Message::FocusPanel(PanelFocus::Request)),
+            command("Focus response panel", "Cmd+Shift+J", Message::FocusPanel(PanelFocus::Response)),
+            command(
+                if self.polling { "Stop polling" } else { "Start polling" },
+                "",
+                Message::PollingToggled(!self.polling),
+            ),
+            command(
+                if self.watch.is_some() { "Stop watching" } else { "Retry until status matches" },
+                "",
+                Message::WatchToggled(self.watch.is_none()),
+            ),
+            command(
+                if self.incognito { "Leave incognito mode" } else { "Enter incognito mode" },
+                "",
+                Message::IncognitoToggled(!self.incognito),
+            ),
+            command("Clear history", "", Message::ClearHistory),
+            command("Keyboard shortcuts", "Cmd+/", Message::ToggleShortcuts),
+            command("Settings", "", Message::ToggleSettings),
+        ];
+        if has_response {
+            commands.extend([
+                command("Copy response", "", Message::CopyResponse),
+                command("Send response as request body", "", Message::UseResponseAsBody),
+                command("Copy response summary", "", Message::CopySummary),
+                command("Copy response as Rust types", "", Message::CopyRustTypes),
+                command("Pin response for comparison", "", Message::PinResponse),
+                command("Open response in browser", "", Message::OpenInBrowser),
+            ]);
+        }
+        let token = substitute_variables(self.auth_token.trim(), &self.variables());
+        if self.auth_type == AuthType::Bearer && looks_like_jwt(&token) {
+            commands.push(command("Inspect bearer token as JWT", "", Message::InspectJwt(token)));
+        }
+        if let Some(ref token) = self.response_scan.jwt
+            && self.response.is_some()
+        {
+            commands.push(command("Inspect JWT in response", "", Message::InspectJwt(token.clone())));
+        }
+
+        let query = self.palette_query.trim().to_lowercase();
+        commands.retain(|c| query.split_whitespace().all(|word| c.label.to_lowercase().contains(word)));
+        commands
+    }
+
+    /// Rows of the environment switcher: the active environment, recently used
+    /// ones, the rest in order, then "No environment", filtered by the query
+    /// against names and base URLs
+    fn env_switcher_choices(&self) -> Vec<EnvironmentChoice> {
+        let mut order: Vec<usize> = Vec::new();
+        let candidates = self.active_environment.into_iter()
+            .chain(self.recent_environments.iter().copied())
+            .chain(0..self.environments.len());
+        for i in candidates {
+            if i < self.environments.len() && !order.contains(&i) {
+                order.push(i);
+            }
+        }
+
+        let query = self.env_switcher_query.trim().to_lowercase();
+        let mut choices: Vec<EnvironmentChoice> = order.into_iter()
+            .filter(|&i| {
+                let env = &self.environments[i];
+                query.is_empty()
+                    || env.name.to_lowercase().contains(&query)
+                    || env.base_url.to_lowercase().contains(&query)
+            })
+            .map(|i| EnvironmentChoice { index: Some(i), name: self.environments[i].name.clone() })
+            .collect();
+        if query.is_empty() || "no environment".contains(&query) {
+            let none = EnvironmentChoice { index: None, name: "No environment".to_string() };
+            // With no environment active, it's the current row and goes first
+            if self.active_environment.is_none() {
+                choices.insert(0, none);
+            } else {
+                choices.push(none);
+            }
+        }
+        choices
+    }
+
+    /// Variables available for `{{name}}` substitution, lowest precedence
+    /// first: the active environment, then the request's own overrides.
+    /// `substitute_variables` takes the last match, so later layers win.
+    fn variables(&self) -> Vec<(String, String)> {
+        let mut vars = self.active_environment().map(Environment::variables).unwrap_or_default();
+        vars.extend(parse_variables(&self.request_variables.text()));
+        vars
+    }
+
+    /// `text` with the active environment's secret values masked, for display
+    fn masked(&self, text: &str) -> String {
+        match self.active_environment() {
+            Some(env) => mask_secrets(text, &env.secret_values()),
+            None => text.to_string(),
+        }
+    }
+
+    /// Whether the last connectivity probe failed. Stale results don't count
+    /// while the check is off or skipped in incognito.
+    fn offline(&self) -> bool {
+        !self.online && self.settings.check_connectivity && !self.incognito
+    }
+
+    /// The full URL that will be requested: base URL, typed URL and query params
+    fn effective_url(&self) -> String {
+        self.resolve_url(&self.url)
+    }
+
+    /// `typed` resolved as `effective_url` resolves the URL bar
+    fn resolve_url(&self, typed: &str) -> String {
+        let mut url = self.resolve_base_url(typed);
+        let params = substitute_variables(&self.query_params.text(), &self.variables());
+        let query = build_query(&params, self.settings.query_array_style);
+        if !query.is_empty() {
+            let separator = if url.contains('?') { "&" } else { "?" };
+            url.push_str(separator);
+            url.push_str(&query);
+        }
+        url
+    }
+
+    /// The typed URL after variable substitution, prefixed with the active
+    /// environment's base URL when it has no scheme of its own
+    fn resolve_base_url(&self, typed: &str) -> String {
+        let url = substitute_variables(typed.trim(), &self.variables());
+        match self.active_environment() {
+            Some(env) if !url.contains("://") && !env.base_url.trim().is_empty() => {
+                let base = substitute_variables(env.base_url.trim(), &self.variables());
+                join_url(&base, &url)
+            }
+            _ => url,
+        }
+    }
+
+    /// Send the batch's next URL, if it has one left and isn't stopped
+    fn next_batch_send(&mut self) -> Task<Message> {
+        let Some(batch) = self.batch.as_ref() else { return Task::none() };
+        if batch.stopped || batch.next >= batch.urls.len() {
+            return Task::none();
+        }
+        let index = batch.next;
+        let request = OutgoingRequest { url: self.resolve_url(&batch.urls[index]), ..batch.request.clone() };
+        if let Some(ref mut batch) = self.batch {
+            batch.next += 1;
+        }
+        Task::perform(send_request(request), move |result| Message::BatchResponse(index, result))
+    }
+
+    fn refresh_client_cert_status(&mut self) {
+        let cert = &self.settings.client_cert;
+        self.client_cert_status = cert.is_configured()
+            .then(|| load_client_identity(cert).map(|(_, subject)| subject));
+        self.client_cert_stale = false;
+    }
+
+    fn sync_env_variables_editor(&mut self) {
+        let variables = self.active_environment().map(Environment::masked_variables).unwrap_or_default();
+        self.env_variables_editor = text_editor::Content::with_text(&variables);
+    }
+
+    fn save_environments(&self) {
+        save_environments(&EnvironmentStore {
+            active: self.active_environment,
+            environments: self.environments.clone(),
+        });
+    }
+
+    /// Store the response headers and body values named by the capture rules
+    /// in the active environment, so later requests (e.g. the next step of a
+    /// collection run) can use them as `{{var}}`. Unless `store` is set
+    /// (incognito and off-record sends) they are only shown.
+    fn capture_headers(&mut self, response: &Response, store: bool) {
+        let rules = parse_variables(&self.header_captures.text());
+        let body = rules.iter()
+            .any(|(_, source)| source.starts_with('$'))
+            .then(|| serde_json::from_str::<serde_json::Value>(&response.body).ok())
+            .flatten();
+        self.captured_headers = rules
+            .into_iter()
+            .map(|(var, source)| {
+                let value = match source.strip_prefix('$') {
+                    Some(path) => body.as_ref().and_then(|body| captured_body_value(body, path)),
+                    None => response.header(&source).map(str::to_string),
+                };
+                (var, source, value)
+            })
+            .collect();
+        self.captures_discarded = !store && !self.captured_headers.is_empty();
+        if !store {
+            return;
+        }
+        let Some(env) = self.active_environment.and_then(|i| self.environments.get_mut(i)) else { return };
+        let mut changed = false;
+        for (var, _, value) in &self.captured_headers {
+            if let Some(value) = value {
+                let variables = set_variable_line(&env.variables, var, value);
+                changed |= variables != env.variables;
+                env.variables = variables;
+            }
+        }
+        if changed {
+            self.save_environments();
+            self.sync_env_variables_editor();
+        }
+    }
+
+    fn to_saved_request(&self, name: String) -> SavedRequest {
+        SavedRequest {
+            name,
+            method: self.method,
+            url: self.url.clone(),
+            headers: self.request_headers.text(),
+            body: self.request_body.text(),
+            body_mode: self.body_mode,
+            body_any_method: self.body_any_method,
+            params: self.query_params.text(),
+            auth_type: self.auth_type,
+            auth_token: self.auth_token.clone(),
+            auth_username: self.auth_username.clone(),
+            auth_password: self.auth_password.clone(),
+            auth_domain: self.auth_domain.clone(),
+            variables: self.request_variables.text(),
+            header_captures: self.header_captures.text(),
+        }
+    }
+
+    fn load_saved_request(&mut self, saved: &SavedRequest) {
+        self.method = saved.method;
+        self.url = saved.url.clone();
+        self.request_headers = text_editor::Content::with_text(&saved.headers);
+        self.request_body = text_editor::Content::with_text(&saved.body);
+        self.body_mode = saved.body_mode;
+        self.refresh_body_file();
+        self.body_any_method = saved.body_any_method;
+        self.query_params = text_editor::Content::with_text(&saved.params);
+        self.auth_type = saved.auth_type;
+        self.auth_token = saved.auth_token.clone();
+        self.auth_username = saved.auth_username.clone();
+        self.auth_password = saved.auth_password.clone();
+        self.auth_domain = saved.auth_domain.clone();
+        self.request_variables = text_editor::Content::with_text(&saved.variables);
+        self.header_captures = text_editor::Content::with_text(&saved.header_captures);
+        self.captured_headers.clear();
+        self.captures_discarded = false;
+        self.off_record = false;
+        self.request_snapshot = SavedRequest { name: String::new(), ..saved.clone() };
+    }
+
+    /// Check the File-mode body path on disk, for the size shown in the Body tab
+    fn refresh_body_file(&mut self) {
+        self.body_file = (self.body_mode == BodyMode::File).then(|| {
+            let path = self.request_body.text().trim().to_string();
+            let size = std::fs::metadata(&path).ok().filter(|meta| meta.is_file()).map(|meta| meta.len());
+            (path, size)
+        });
+    }
+
+    /// Whether a send attaches the body, by the same rule as `send_request`
+    fn body_sent(&self) -> bool {
+        self.method.has_body() || self.body_any_method
+    }
+
+    fn has_unsaved_edits(&self) -> bool {
+        self.to_saved_request(String::new()) != self.request_snapshot
+    }
+
+    fn over_latency_budget(&self, duration: Duration) -> bool {
+        self.settings.latency_budget_ms > 0 && duration.as_millis() > u128::from(self.settings.latency_budget_ms)
+    }
+
+    /// `colors::ERROR` for a response over the latency budget, else `normal`
+    fn duration_color(&self, response: &Response, normal: iced::Color) -> iced::Color {
+        if self.over_latency_budget(response.duration) { colors::ERROR } else { normal }
+    }
+
+    /// Count a watch attempt; stop on a matching status or past the limit,
+    /// otherwise queue the next send after the backoff
+    fn check_watch(&mut self, status: Option<u16>) {
+        let Some(ref mut watch) = self.watch else { return };
+        watch.attempts += 1;
+        let elapsed = format_countdown(watch.started.elapsed());
+        let attempts = match watch.attempts {
+            1 => "1 attempt".to_string(),
+            n => format!("{} attempts", n),
+        };
+        if let Some(status) = status.filter(|&status| watch.matches(status)) {
+            self.watch = None;
+            self.toast(ToastKind::Success, format!("Got HTTP {} after {} in {}", status, attempts, elapsed));
+        } else if watch.started.elapsed() + watch.backoff() > watch.limit {
+            self.watch = None;
+            self.toast(ToastKind::Warning, format!("Watch gave up after {} in {}", attempts, elapsed));
+        } else {
+            watch.next_at = Some(Instant::now() + watch.backoff());
+        }
+    }
+
+    /// Reset everything request-scoped; history, collections, environments
+    /// and panel sizes are kept
+    fn reset_request(&mut self) {
+        self.load_saved_request(&SavedRequest::blank());
+        self.request_tab = Tab::Body;
+        self.response_tab = Tab::Body;
+        self.response = None;
+        self.error = None;
+        self.scheduled_send = None;
+        self.watch = None;
+        self.confirm_resend = None;
+        self.show_find_replace = false;
+        self.find_query.clear();
+        self.replace_text.clear();
+    }
+
+    /// Request the page after the current response, or merge what was
+    /// collected once there is no next page (or the page cap is reached)
+    fn fetch_next_page(&mut self) -> Task<Message> {
+        let (Some(response), Some(pagination)) = (&self.response, &self.pagination) else {
+            return Task::none();
+        };
+        let next = if pagination.pages < MAX_PAGES {
+            next_page_url(response, &self.next_page_path)
+        } else {
+            None
+        };
+        match next.map(|url| self.prepare_request().map(|r| OutgoingRequest { url, ..r })) {
+            Some(Ok(request)) => {
+                self.loading = true;
+                Task::perform(send_request(request), Message::PageReceived)
+            }
+            Some(Err(e)) => {
+                if let Some(p) = self.pagination.as_mut() {
+                    p.error = Some(e);
+                }
+                self.finish_pagination();
+                Task::none()
+            }
+            None => {
+                self.finish_pagination();
+                Task::none()
+            }
+        }
+    }
+
+    /// Show the merged items of every page as the response body
+    fn finish_pagination(&mut self) {
+        // A single page is left exactly as received
+        if let (Some(response), Some(pagination)) = (self.response.as_mut(), &self.pagination)
+            && pagination.pages > 1
+        {
+            response.body = serde_json::to_string(&pagination.items).unwrap_or_default();
+            response.size = response.body.len();
+            self.refresh_response_view();
+        }
+    }
+
+    /// Reload the selectable body and headers views from the current response.
+    fn refresh_response_view(&mut self) {
+        self.expanded_strings.clear();
+        self.json_breadcrumb = None;
+        let Some(ref response) = self.response else { return };
+        self.response_scan = ResponseScan::new(response);
+        let headers: String = response.headers.iter()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.response_body_formatted = self.displayed_body(response);
+        self.response_body_shown = self.settings.body_chunk_kb * 1024;
+        self.response_headers_view = text_editor::Content::with_text(&headers);
+        self.response_flat_view = serde_json::from_str::<serde_json::Value>(&self.response_body_text(response))
+            .ok()
+            .map(|value| text_editor::Content::with_text(&flatten_json(&value)));
+        self.show_response_body();
+    }
+
+    /// The body as the Body tab shows it: pretty-printed JSON, unless the
+    /// settings ask for the server's own formatting
+    fn displayed_body(&self, response: &Response) -> String {
+        let body = self.response_body_text(response);
+        if self.settings.preserve_body_formatting { body } else { format_json(&body) }
+    }
+
+    /// Put the first `response_body_shown` bytes of the formatted body in the
+    /// viewer, cut at a line end so nothing is split mid-token
+    fn show_response_body(&mut self) {
+        let body = &self.response_body_formatted;
+        let end = if self.response_body_shown >= body.len() {
+            body.len()
+        } else {
+            let mut end = self.response_body_shown;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            body[..end].rfind('\n').map_or(end, |i| i + 1)
+        };
+        self.response_body_shown = end;
+        self.response_body_view = text_editor::Content::with_text(&body[..end]);
+    }
+
+    /// The message that dismisses the topmost open modal, in the same
+    /// order `view` stacks them
+    fn modal_close_message(&self) -> Option<Message> {
+        if self.confirm_new_request {
+            Some(Message::CancelNewRequest)
+        } else if self.show_curl_import {
+            Some(Message::ToggleCurlImport)
+        } else if self.show_save_request {
+            Some(Message::ToggleSaveRequest)
+        } else if self.show_snippets {
+            Some(Message::ToggleSnippets)
+        } else if self.show_palette {
+            Some(Message::TogglePalette)
+        } else if self.show_shortcuts {
+            Some(Message::ToggleShortcuts)
+        } else if self.inspected_jwt.is_some() {
+            Some(Message::CloseJwt)
+        } else if self.show_timing_log {
+            Some(Message::ToggleTimingLog)
+        } else if self.show_batch {
+            Some(Message::ToggleBatch)
+        } else if self.show_env_switcher {
+            Some(Message::CloseEnvSwitcher)
+        } else if self.show_environments {
+            Some(Message::ToggleEnvironments)
+        } else if self.show_settings {
+            Some(Message::ToggleSettings)
+        } else if self.collection_run.is_some() {
+            Some(Message::CloseCollectionRun)
+        } else if self.variable_completion.is_some() {
+            Some(Message::DismissVariableCompletion)
+        } else if !self.url_suggestions.is_empty() {
+            Some(Message::DismissUrlSuggestions)
+        } else {
+            None
+        }
+    }
+
+    /// Offer variable names if `before_cursor` ends inside an unclosed `{{`
+    fn update_variable_completion(&mut self, target: CompletionTarget, before_cursor: &str) {
+        self.variable_completion = open_variable_prefix(before_cursor).and_then(|prefix| {
+            let names = matching_variable_names(&self.variables(), prefix);
+            (!names.is_empty()).then(|| VariableCompletion { target, prefix: prefix.to_string(), names, selected: 0 })
+        });
+    }
+
+    /// Track `{{` completion in a request editor after it performed an action:
+    /// edits re-check the text before the cursor, moving the cursor dismisses
+    fn editor_changed(&mut self, target: CompletionTarget, edited: bool, moved: bool) {
+        if edited {
+            let Some(content) = self.completion_editor(target) else { return };
+            let before = before_cursor(content);
+            self.update_variable_completion(target, &before);
+        } else if moved && self.variable_completion.as_ref().is_some_and(|c| c.target == target) {
+            self.variable_completion = None;
+        }
+    }
+
+    fn completion_editor(&mut self, target: CompletionTarget) -> Option<&mut text_editor::Content> {
+        match target {
+            CompletionTarget::Url => None,
+            CompletionTarget::Body => Some(&mut self.request_body),
+            CompletionTarget::Headers => Some(&mut self.request_headers),
+            CompletionTarget::Params => Some(&mut self.query_params),
+        }
+    }
+
+    /// Replace the typed `prefix` with `name` and close the braces, unless
+    /// a `}}` already follows
+    fn complete_variable(&mut self, target: CompletionTarget, prefix: &str, name: &str) {
+        let Some(content) = self.completion_editor(target) else {
+            self.url.truncate(self.url.len() - prefix.len());
+            self.url.push_str(name);
+            self.url.push_str("}}");
+            return;
+        };
+        let position = content.cursor().position;
+        let closed = content.line(position.line).is_some_and(|line| {
+            line.text.get(position.column..).is_some_and(|after| after.starts_with("}}"))
+        });
+        for _ in prefix.chars() {
+            content.perform(text_editor::Action::Edit(text_editor::Edit::Backspace));
+        }
+        let insert = if closed { name.to_string() } else { format!("{}}}}}", name) };
+        content.perform(text_editor::Action::Edit(text_editor::Edit::Paste(std::sync::Arc::new(insert))));
+        if closed {
+            content.perform(text_editor::Action::Move(text_editor::Motion::Right));
+            content.perform(text_editor::Action::Move(text_editor::Motion::Right));
+        }
+    }
+
+    /// Variable names for the completion open on `target`, as a row of chips
+    fn view_variable_chips(&self, target: CompletionTarget) -> Option<Element<'_, Message>> {
+        let completion = self.variable_completion.as_ref().filter(|c| c.target == target)?;
+        let chips = completion.names.iter().enumerate().map(|(i, name)| {
+            let highlighted = completion.selected == i;
+            button(text(format!("{{{{{}}}}}", name)).size(10))
+                .padding([3, 8])
+                .style(move |_, status| button::Style {
+                    background: Some(if highlighted || matches!(status, button::Status::Hovered) {
+                        colors::BG_ELEVATED.into()
+                    } else {
+                        colors::BG_DARK.into()
+                    }),
+                    text_color: if highlighted { colors::ACCENT_PURPLE } else { colors::TEXT_SECONDARY },
+                    ..Default::default()
+                })
+                .on_press(Message::VariableCompletionSelected(i))
+                .into()
+        });
+        Some(
+            container(
+                row![text("↑↓ Enter").size(9).color(colors::TEXT_SECONDARY)]
+                    .extend(chips)
+                    .spacing(4)
+                    .align_y(iced::Alignment::Center)
+                    .wrap(),
+            )
+            .padding([4, 12])
+            .into(),
+        )
+    }
+
+    fn dismiss_url_suggestions(&mut self) {
+        self.url_suggestions.clear();
+        self.url_suggestion = None;
+    }
+
+    fn view_palette(&self) -> Element<'_, Message> {
+        let input = text_input("Type a command", &self.palette_query)
+            .id(PALETTE_INPUT_ID)
+            .on_input(Message::PaletteQueryChanged)
+            .on_submit(Message::PaletteRun(self.palette_index))
+            .padding(10)
+            .size(12)
+            .width(Fill)
+            .style(input_style);
+
+        let commands = self.palette_commands();
+        let empty = commands.is_empty();
+        let rows = commands.into_iter().enumerate().map(|(i, command)| {
+            let highlighted = i == self.palette_index;
+            button(
+                row![
+                    text(command.label).size(11).width(Fill),
+                    text(command.shortcut).size(10).color(colors::TEXT_SECONDARY),
+                ]
+                .spacing(12),
+            )
+            .width(Fill)
+            .padding([6, 10])
+            .style(move |_, status| button::Style {
+                background: Some(if highlighted || matches!(status, button::Status::Hovered) {
+                    colors::BG_PANEL.into()
+                } else {
+                    colors::BG_ELEVATED.into()
+                }),
+                text_color: if highlighted { colors::TEXT_PRIMARY } else { colors::TEXT_SECONDARY },
+                ..Default::default()
+            })
+            .on_press(Message::PaletteRun(i))
+            .into()
+        });
+
+        let mut content = column![input].spacing(12).width(Length::Fixed(500.0));
+        content = if empty {
+            content.push(text("No matching commands").size(11).color(colors::TEXT_SECONDARY))
+        } else {
+            content.push(scrollable(Column::from_iter(rows)).height(Length::Fixed(320.0)))
+        };
+        content.into()
+    }
+
+    fn view_env_switcher(&self) -> Element<'_, Message> {
+        let input = text_input("Switch environment", &self.env_switcher_query)
+            .id(ENV_SWITCHER_INPUT_ID)
+            .on_input(Message::EnvSwitcherQueryChanged)
+            .on_submit(Message::EnvSwitcherSubmit)
+            .padding(10)
+            .size(12)
+            .width(Fill)
+            .style(input_style);
+
+        let rows = self.env_switcher_choices().into_iter().enumerate().map(|(i, choice)| {
+            let highlighted = i == self.env_switcher_index;
+            let active = choice.index == self.active_environment;
+            let base_url = choice.index
+                .and_then(|e| self.environments.get(e))
+                .map(|env| env.base_url.trim().to_string())
+                .unwrap_or_default();
+            let label = row![
+                text(choice.name.clone()).size(11).width(Fill),
+                text(truncate_str(&base_url, 40)).size(10).color(colors::TEXT_SECONDARY),
+                text(if active { "active" } else { "" }).size(10).color(colors::ACCENT_PURPLE),
+            ]
+            .spacing(12);
+            button(label)
+                .width(Fill)
+                .padding([6, 10])
+                .style(move |_, status| button::Style {
+                    background: Some(if highlighted || matches!(status, button::Status::Hovered) {
+                        colors::BG_PANEL.into()
+                    } else {
+                        colors::BG_ELEVATED.into()
+                    }),
+                    text_color: if highlighted { colors::TEXT_PRIMARY } else { colors::TEXT_SECONDARY },
+                    ..Default::default()
+                })
+                .on_press(Message::EnvironmentSelected(choice))
+                .into()
+        });
+
+        column![
+            text("ENVIRONMENTS").size(12).color(colors::TEXT_SECONDARY),
+            input,
+            scrollable(Column::from_iter(rows)).height(Length::Shrink),
+            text("Up/Down or Cmd+E to move, Enter to switch").size(10).color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(500.0))
+        .into()
+    }
+
+    /// Dropdown under the URL input; empty when there's nothing to suggest
+    fn view_url_suggestions(&self) -> Element<'_, Message> {
+        let completion = self.variable_completion.as_ref().filter(|c| c.target == CompletionTarget::Url);
+        if self.url_suggestions.is_empty() && completion.is_none() {
+            return column![].into();
+        }
+        // Variable names, with their (masked) values, take over while a `{{` is open
+        let completing = completion.is_some();
+        let (labels, selected) = match completion {
+            Some(completion) => {
+                let vars = self.variables();
+                let labels: Vec<String> = completion.names.iter()
+                    .map(|name| match vars.iter().rev().find(|(k, _)| k == name) {
+                        Some((_, value)) => format!("{{{{{}}}}}  {}", name, truncate_str(&self.masked(value), 50)),
+                        None => format!("{{{{{}}}}}", name),
+                    })
+                    .collect();
+                (labels, Some(completion.selected))
+            }
+            None => (self.url_suggestions.clone(), self.url_suggestion),
+        };
+        let items = labels.into_iter().enumerate().map(|(i, url)| {
+            let highlighted = selected == Some(i);
+            button(text(truncate_str(&url, 70)).size(11))
+                .width(Fill)
+                .padding([6, 10])
+                .style(move |_, status| button::Style {
+                    background: Some(if highlighted || matches!(status, button::Status::Hovered) {
+                        colors::BG_PANEL.into()
+                    } else {
+                        colors::BG_ELEVATED.into()
+                    }),
+                    text_color: if highlighted { colors::TEXT_PRIMARY } else { colors::TEXT_SECONDARY },
+                    ..Default::default()
+                })
+                .on_press(if completing {
+                    Message::VariableCompletionSelected(i)
+                } else {
+                    Message::UrlSuggestionSelected(i)
+                })
+                .into()
+        });
+        let list = container(Column::from_iter(items))
+            .width(Length::Fixed(460.0))
+            .style(|_| container::Style {
+                border: iced::Border { color: colors::BORDER, width: 1.0, radius: 0.0.into() },
+                ..Default::default()
+            });
+        // Lines up with the URL input: bar padding, method picker and spacing
+        container(list)
+            .padding(Padding { top: 50.0, left: 110.0, ..Padding::ZERO })
+            .into()
+    }
+
+    /// Put back the request that was being edited before the collection run,
+    /// once the run has nothing left to send
+    fn restore_run_editor(&mut self) {
+        let Some(run) = self.collection_run.as_mut() else { return };
+        let total = self.collections.get(run.collection).map_or(0, |c| c.requests.len());
+        if run.in_flight() || (!run.stopped && run.next < total) {
+            return;
+        }
+        let off_record = run.off_record;
+        if let Some((editor, snapshot)) = run.editor.take() {
+            self.load_saved_request(&editor);
+            self.request_snapshot = snapshot;
+            self.off_record = off_record;
+        }
+    }
+
+    /// Record the outcome of the in-flight collection step, if any, and
+    /// schedule the next one unless the run is over.
+    fn record_run_step(&mut self, result: &Result<Response, String>) -> Task<Message> {
+        let Some(run) = self.collection_run.as_mut() else { return Task::none() };
+        if !run.in_flight() {
+            return Task::none();
+        }
+        let total = self.collections.get(run.collection).map_or(0, |c| c.requests.len());
+        let step = RunStepResult {
+            status: result.as_ref().ok().map(|r| r.status),
+            error: result.as_ref().err().cloned(),
+            duration: result.as_ref().ok().map(|r| r.duration),
+        };
+        if !step.passed() && self.run_stop_on_failure {
+            run.stopped = true;
+        }
+        run.results.push(step);
+
+        if !run.stopped && run.next < total {
+            Task::done(Message::RunCollectionStep)
+        } else {
+            Task::none()
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let url_bar = self.view_url_bar();
+
+        let sidebar = self.view_sidebar();
+        let sidebar_handle = self.view_resize_handle(DragTarget::Sidebar);
+
+        let request_panel = self.view_request_panel();
+        let panel_handle = self.view_resize_handle(DragTarget::RequestPanel);
+        let response_panel = self.view_response_panel();
+
+        // Split the space between the panels by `request_width`
+        let request_share = (self.request_width * 1000.0).round().clamp(1.0, 999.0) as u16;
+        let main_content = row![
+            container(request_panel).width(Length::FillPortion(request_share)),
+            panel_handle,
+            container(response_panel).width(Length::FillPortion(1000 - request_share)),
+        ]
+        .height(Fill);
+
+        let content = iced::widget::stack![
+            column![url_bar, main_content].spacing(1).width(Fill),
+            self.view_url_suggestions(),
+        ];
+
+        let main_view = if self.sidebar_collapsed() {
+            row![content].height(Fill)
+        } else {
+            row![
+                container(sidebar).width(Length::Fixed(self.sidebar_width)),
+                sidebar_handle,
+                content
+            ]
+            .height(Fill)
+        };
+
+        let status_bar = self.view_status_bar();
+
+        let with_status = column![main_view, status_bar].height(Fill);
+
+        let base: Element<Message> = container(with_status)
+            .width(Fill)
+            .height(Fill)
+            .style(|_| container::Style {
+                background: Some(colors::BG_DARKEST.into()),
+                ..Default::default()
+            })
+            .into();
+
+        // Show a modal on top of the main view if one is open
+        let view = if self.confirm_new_request {
+            modal(base, self.view_confirm_new_request(), Message::CancelNewRequest)
+        } else if self.show_curl_import {
+            modal(base, self.view_curl_import(), Message::ToggleCurlImport)
+        } else if self.show_save_request {
+            modal(base, self.view_save_request(), Message::ToggleSaveRequest)
+        } else if self.show_snippets {
+            modal(base, self.view_snippets(), Message::ToggleSnippets)
+        } else if self.show_palette {
+            modal(base, self.view_palette(), Message::TogglePalette)
+        } else if self.show_shortcuts {
+            modal(base, view_shortcuts(), Message::ToggleShortcuts)
+        } else if let Some(ref token) = self.inspected_jwt {
+            modal(base, view_jwt(token), Message::CloseJwt)
+        } else if self.show_timing_log {
+            modal(base, self.view_timing_log(), Message::ToggleTimingLog)
+        } else if self.show_batch {
+            modal(base, self.view_batch(), Message::ToggleBatch)
+        } else if self.show_env_switcher {
+            modal(base, self.view_env_switcher(), Message::CloseEnvSwitcher)
+        } else if self.show_environments {
+            modal(base, self.view_environments(), Message::ToggleEnvironments)
+        } else if self.show_settings {
+            modal(base, self.view_settings(), Message::ToggleSettings)
+        } else if let Some(ref run) = self.collection_run {
+            modal(base, self.view_collection_run(run), Message::CloseCollectionRun)
+        } else {
+            base
+        };
+
+        if self.toasts.is_empty() {
+            view
+        } else {
+            iced::widget::stack![view, self.view_toasts()].into()
+        }
+    }
+
+    /// Transient notifications stacked in the bottom-right corner
+    fn view_toasts(&self) -> Element<'_, Message> {
+        let toasts = self.toasts.iter().map(|toast| {
+            let alpha = toast.opacity();
+            let accent = match toast.kind {
+                ToastKind::Success => colors::SUCCESS,
+                ToastKind::Warning => colors::WARNING,
+                ToastKind::Error => colors::ERROR,
+            };
+            container(text(toast.message.clone()).size(11).color(colors::TEXT_PRIMARY.scale_alpha(alpha)))
+                .padding([8, 12])
+                .style(move |_| container::Style {
+                    background: Some(colors::BG_ELEVATED.scale_alpha(alpha).into()),
+                    border: iced::Border {
+                        color: accent.scale_alpha(alpha),
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        });
+
+        container(Column::with_children(toasts).spacing(6).align_x(iced::Alignment::End))
+            .width(Fill)
+            .height(Fill)
+            .padding(Padding { top: 0.0, right: 16.0, bottom: 40.0, left: 0.0 })
+            .align_right(Fill)
+            .align_bottom(Fill)
+            .into()
+    }
+
+    /// Highlighting follows the Content-Type header, falling back to the
+    /// body's first character when none is set
+    fn body_syntax(&self) -> BodySyntax {
+        if self.body_mode != BodyMode::Text {
+            return BodySyntax::Plain;
+        }
+        let content_type = header_value(&self.request_headers.text(), "content-type")
+            .map(|v| v.trim().to_ascii_lowercase());
+        match content_type {
+            Some(ct) if ct.contains("json") => BodySyntax::Json,
+            Some(ct) if ct.contains("xml") => BodySyntax::Xml,
+            _ => match self.request_body.text().trim_start().chars().next() {
+                Some('{' | '[') => BodySyntax::Json,
+                Some('<') => BodySyntax::Xml,
+                _ => BodySyntax::Plain,
+            },
+        }
+    }
+
+    /// Replace the expanded set with every container down to `depth`
+    fn expand_tree_to(&mut self, depth: usize) {
+        let Some(value) = self.response.as_ref().and_then(|r| serde_json::from_str::<serde_json::Value>(&r.body).ok())
+        else {
+            return;
+        };
+        let mut paths = Vec::new();
+        let truncated = collect_container_paths(&value, String::new(), depth, &mut paths);
+        self.tree_expanded = paths.into_iter().collect();
+        if truncated {
+            self.toast(ToastKind::Warning, format!("Large document: expanded {} nodes, the rest stay collapsed", TREE_MAX_EXPANDED));
+        } else if depth == TREE_MAX_EXPAND_DEPTH && max_depth(&value) > depth {
+            self.toast(ToastKind::Warning, format!("Expanded to depth {}; deeper nodes stay collapsed", depth));
+        }
+    }
+
+    fn view_json_tree(&self, response: &Response) -> Element<'_, Message> {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&self.response_body_text(response)) else {
+            return container(text("Response body is not JSON").size(11).color(colors::TEXT_SECONDARY))
+                .padding(12)
+                .into();
+        };
+
+        let depth_picker = pick_list(
+            [1, 2, 3, 4, 5].map(TreeDepth),
+            None::<TreeDepth>,
+            |TreeDepth(depth)| Message::ExpandToDepth(depth),
+        )
+        .placeholder("Expand to depth")
+        .text_size(9)
+        .padding([4, 8])
+        .style(pick_list_style);
+
+        let toolbar = row![
+            button(text("EXPAND ALL").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press(Message::ExpandAll),
+            button(text("COLLAPSE ALL").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press(Message::CollapseAll),
+            depth_picker,
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center);
+
+        let mut rows = Vec::new();
+        let folded = self.fold_long_strings.then_some(&self.expanded_strings);
+        tree_rows(&value, None, String::new(), 0, &self.tree_expanded, folded, &mut rows);
+        if rows.len() >= TREE_MAX_ROWS {
+            rows.push(
+                text(format!("... showing the first {} rows", TREE_MAX_ROWS))
+                    .size(10)
+                    .color(colors::WARNING)
+                    .into(),
+            );
+        }
+
+        column![
+            container(toolbar).padding(Padding { top: 8.0, right: 12.0, bottom: 0.0, left: 12.0 }),
+            scrollable(container(Column::from_vec(rows).spacing(1)).padding(12).width(Fill)).height(Fill),
+        ]
+        .into()
+    }
+
+    /// Pinned and current bodies in two aligned columns. Each row holds both
+    /// sides, so a single scrollable keeps them in step.
+    fn view_compare(&self, pinned: &Response, current: &Response) -> Element<'_, Message> {
+        let old = format_json(&self.response_body_text(pinned));
+        let new = format_json(&self.response_body_text(current));
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        let rows = align_lines(&old_lines, &new_lines);
+        let changed = rows.iter().filter(|(a, b)| a != b).count();
+
+        let heading = |label: &str, response: &Response| {
+            container(
+                row![
+                    text(label.to_string()).size(10).color(colors::TEXT_SECONDARY),
+                    text(response.status.to_string()).size(10).color(status_color(response.status)),
+                    text(format!("{}ms", response.duration.as_millis())).size(10).color(colors::TEXT_SECONDARY),
+                    text(format_size(response.size)).size(10).color(colors::TEXT_SECONDARY),
+                ]
+                .spacing(8),
+            )
+            .padding([6, 12])
+            .width(Fill)
+        };
+        let summary = if changed == 0 {
+            text("Bodies are identical").size(10).color(colors::SUCCESS)
+        } else {
+            text(format!("{} line{} differ", changed, if changed == 1 { "" } else { "s" }))
+                .size(10)
+                .color(colors::WARNING)
+        };
+
+        let cell = |line: Option<&str>, tint: Option<iced::Color>| {
+            container(text(line.unwrap_or("").to_string()).size(11).color(colors::TEXT_PRIMARY))
+                .padding([0, 12])
+                .width(Fill)
+                .style(move |_| container::Style {
+                    background: tint.map(|c| c.scale_alpha(0.15).into()),
+                    ..Default::default()
+                })
+        };
+        let body = rows.into_iter().fold(column![], |col, (a, b)| {
+            let (left, right) = match (a, b) {
+                (Some(x), Some(y)) if x == y => (None, None),
+                (Some(_), Some(_)) => (Some(colors::WARNING), Some(colors::WARNING)),
+                (Some(_), None) => (Some(colors::ERROR), None),
+                (None, _) => (None, Some(colors::SUCCESS)),
+            };
+            col.push(row![cell(a, left), cell(b, right)])
+        });
+
+        column![
+            row![heading("PINNED", pinned), heading("CURRENT", current)],
+            container(summary).padding([0, 12]),
+            scrollable(container(body).padding([8, 0]).width(Fill)).height(Fill),
+        ]
+        .spacing(4)
+        .into()
+    }
+
+    /// The response body as displayed; `response.body` itself is never changed
+    fn response_body_text(&self, response: &Response) -> String {
+        if self.sort_response_keys
+            && let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&response.body)
+        {
+            // Arrays keep their order; only object keys move. Reordering loses the
+            // original layout anyway, so it comes out pretty-printed.
+            value.sort_all_objects();
+            return serde_json::to_string_pretty(&value).unwrap_or_else(|_| response.body.clone());
+        }
+        response.body.clone()
+    }
+
+    /// Raw request and response headers, credentials masked unless revealed
+    fn wire_text(&self, response: &Response) -> String {
+        let raw_request = if self.reveal_credentials {
+            response.raw_request.clone()
+        } else {
+            self.masked(&mask_credentials(&response.raw_request))
+        };
+        format!("{}\n\n{}", raw_request.replace("\r\n", "\n"), response.raw_response_headers.replace("\r\n", "\n"))
+    }
+
+    fn toast(&mut self, kind: ToastKind, message: impl Into<String>) {
+        self.toasts.push(Toast {
+            message: message.into(),
+            kind,
+            created: Instant::now(),
+            age: Duration::ZERO,
+        });
+    }
+
+    fn view_curl_import(&self) -> Element<'_, Message> {
+        let curl_input = text_input("Paste cURL command here...", &self.curl_input)
+            .on_input(Message::CurlInputChanged)
+            .on_submit(Message::ImportCurl)
+            .padding(12)
+            .size(12)
+            .width(Fill)
+            .style(|_, _| text_input::Style {
+                background: colors::BG_ELEVATED.into(),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+                icon: colors::TEXT_SECONDARY,
+                placeholder: colors::TEXT_SECONDARY,
+                value: colors::TEXT_PRIMARY,
+                selection: colors::ACCENT_PURPLE,
+            });
+
+        let import_btn = button(text("IMPORT").size(11))
+            .padding([10, 20])
+            .style(|_, status| {
+                let bg = match status {
+                    button::Status::Hovered => colors::ACCENT_CORAL,
+                    _ => colors::ACCENT_PURPLE,
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: colors::BG_DARKEST,
+                    border: iced::Border { radius: 0.0.into(), ..Default::default() },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::ImportCurl);
+
+        let cancel_btn = button(text("CANCEL").size(11))
+            .padding([10, 20])
+            .style(|_, status| {
+                let bg = match status {
+                    button::Status::Hovered => colors::BG_ELEVATED,
+                    _ => colors::BG_DARK,
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: colors::TEXT_PRIMARY,
+                    border: iced::Border {
+                        color: colors::BORDER,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::ToggleCurlImport);
+
+        column![
+            text("IMPORT CURL").size(12).color(colors::TEXT_SECONDARY),
+            curl_input,
+            text("Paste a cURL command and press Enter or click Import")
+                .size(10)
+                .color(colors::TEXT_SECONDARY),
+            row![cancel_btn, import_btn].spacing(8),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(500.0))
+        .into()
+    }
+
+    fn view_confirm_new_request(&self) -> Element<'_, Message> {
+        column![
+            text("NEW REQUEST").size(12).color(colors::TEXT_SECONDARY),
+            text("The current request has unsaved edits. Discard them?")
+                .size(11)
+                .color(colors::TEXT_PRIMARY),
+            row![
+                button(text("CANCEL").size(11))
+                    .padding([10, 20])
+                    .style(secondary_button_style)
+                    .on_press(Message::CancelNewRequest),
+                button(text("DISCARD").size(11))
+                    .padding([10, 20])
+                    .style(primary_button_style)
+                    .on_press(Message::ConfirmNewRequest),
+            ]
+            .spacing(8),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(400.0))
+        .into()
+    }
+
+    fn view_settings(&self) -> Element<'_, Message> {
+        let max_history = text_input("500", &self.max_history_input)
+            .on_input(Message::MaxHistoryChanged)
+            .on_submit(Message::ToggleSettings)
+            .padding(10)
+            .size(12)
+            .width(Length::Fixed(120.0))
+            .style(input_style);
+
+        let cert = &self.settings.client_cert;
+        let field = |placeholder: &str, value: &str, on_input: fn(String) -> Message| {
+            text_input(placeholder, value)
+                .on_input(on_input)
+                .padding(10)
+                .size(12)
+                .width(Fill)
+                .style(input_style)
+        };
+        let cert_status = match &self.client_cert_status {
+            _ if self.client_cert_stale && cert.is_configured() => {
+                text("Press Enter to check the certificate").size(10).color(colors::TEXT_SECONDARY)
+            }
+            None => text("No client certificate").size(10).color(colors::TEXT_SECONDARY),
+            Some(Ok(subject)) => text(format!("Loaded: {}", subject)).size(10).color(colors::SUCCESS),
+            Some(Err(e)) => text(e.clone()).size(10).color(colors::ERROR),
+        };
+
+        let label = |s: &'static str| text(s).size(11).color(colors::TEXT_SECONDARY);
+        let hint = |s: &'static str| text(s).size(10).color(colors::TEXT_SECONDARY);
+
+        let network = column![
+            text("NETWORK").size(10).color(colors::TEXT_SECONDARY),
+            row![
+                column![
+                    label("Connect timeout"),
+                    pick_list(
+                        CONNECT_TIMEOUT_CHOICES,
+                        Some(TimeoutChoice(self.settings.connect_timeout_secs)),
+                        Message::ConnectTimeoutSelected,
+                    )
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                ]
+                .spacing(6)
+                .width(Fill),
+                column![
+                    label("Request timeout"),
+                    pick_list(
+                        TIMEOUT_CHOICES,
+                        Some(TimeoutChoice(self.settings.timeout_secs)),
+                        Message::TimeoutSelected,
+                    )
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                ]
+                .spacing(6)
+                .width(Fill),
+                column![
+                    label("Redirects"),
+                    pick_list(
+                        REDIRECT_CHOICES,
+                        Some(RedirectChoice(self.settings.max_redirects)),
+                        Message::MaxRedirectsSelected,
+                    )
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                ]
+                .spacing(6)
+                .width(Fill),
+            ]
+            .spacing(8),
+            row![
+                column![
+                    label("Keep-alive pool"),
+                    pick_list(
+                        POOL_SIZE_CHOICES,
+                        Some(PoolSizeChoice(self.settings.pool_max_idle_per_host)),
+                        Message::PoolSizeSelected,
+                    )
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                ]
+                .spacing(6)
+                .width(Fill),
+                column![
+                    label("Idle timeout"),
+                    pick_list(
+                        POOL_IDLE_CHOICES,
+                        Some(PoolIdleChoice(self.settings.pool_idle_timeout_secs)),
+                        Message::PoolIdleSelected,
+                    )
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                ]
+                .spacing(6)
+                .width(Fill),
+            ]
+            .spacing(8),
+            label("Proxy"),
+            field("http://127.0.0.1:8080 (blank uses system settings)", &self.settings.proxy, Message::ProxyChanged),
+            iced::widget::checkbox(self.settings.check_connectivity)
+                .label("Disable Send for remote URLs while the network seems to be offline")
+                .text_size(11)
+                .on_toggle(Message::CheckConnectivityToggled),
+            hint("Connects to 1.1.1.1 and 8.8.8.8, or to the proxy if set, every 30s; off in incognito"),
+        ]
+        .spacing(6);
+
+        let default_headers = column![
+            label("Default headers"),
+            text_editor(&self.default_headers_editor)
+                .placeholder("Accept: application/json\nX-Team: api")
+                .on_action(Message::DefaultHeadersChanged)
+                .padding(10)
+                .height(80)
+                .style(|_, _| text_editor::Style {
+                    background: colors::BG_ELEVATED.into(),
+                    border: iced::Border {
+                        color: colors::BORDER,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    placeholder: colors::TEXT_SECONDARY,
+                    value: colors::TEXT_PRIMARY,
+                    selection: colors::ACCENT_PURPLE,
+                }),
+            hint("Sent with every request; the Headers tab overrides the same key"),
+        ]
+        .spacing(6);
+
+        let sections = column![
+            column![
+                text("Max history entries").size(11).color(colors::TEXT_SECONDARY),
+                max_history,
+                match parse_max_history(&self.max_history_input) {
+                    Some(max) if max < self.history.len() => text(format!(
+                        "Keeping the newest {} requests; the oldest {} are deleted when settings close",
+                        max,
+                        self.history.len() - max,
+                    ))
+                    .size(10)
+                    .color(colors::WARNING),
+                    Some(max) => text(format!("Keeping the newest {} requests", max))
+                        .size(10)
+                        .color(colors::TEXT_SECONDARY),
+                    None => text(format!("Enter a number; keeping {} until then", self.settings.max_history))
+                        .size(10)
+                        .color(colors::TEXT_SECONDARY),
+                },
+                iced::widget::checkbox(self.incognito)
+                    .label("Incognito: don't record requests in history")
+                    .text_size(11)
+                    .on_toggle(Message::IncognitoToggled),
+                hint("Applies to this session only; existing history is kept"),
+                label("Archive responses to"),
+                field("/path/to/archive (blank turns archiving off)", &self.settings.archive_dir, Message::ArchiveDirChanged),
+                hint("Each response body is saved with a .meta.json sidecar; skipped in incognito"),
+            ]
+            .spacing(6),
+            column![
+                label("After a response arrives, show"),
+                pick_list(AfterSendTab::ALL, Some(self.settings.after_send_tab), Message::AfterSendTabSelected)
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                hint("Cmd+Enter always shows Body, Cmd+Shift+Enter Headers"),
+                label("Latency budget"),
+                pick_list(
+                    LATENCY_BUDGETS,
+                    Some(LatencyBudget(self.settings.latency_budget_ms)),
+                    Message::LatencyBudgetSelected,
+                )
+                .text_size(11)
+                .padding(10)
+                .width(Fill)
+                .style(pick_list_style),
+                hint("Slower responses show their time in red and are counted in the status bar"),
+            ]
+            .spacing(6),
+            column![
+                label("Large response bodies"),
+                pick_list(
+                    BODY_CHUNK_CHOICES,
+                    Some(BodyChunkChoice(self.settings.body_chunk_kb)),
+                    Message::BodyChunkSelected,
+                )
+                .text_size(11)
+                .padding(10)
+                .width(Fill)
+                .style(pick_list_style),
+                hint("The Body tab shows this much, then SHOW MORE adds the next chunk"),
+                iced::widget::checkbox(self.settings.preserve_body_formatting)
+                    .label("Preserve original formatting")
+                    .text_size(11)
+                    .on_toggle(Message::PreserveBodyFormattingToggled),
+                hint("Show and copy JSON exactly as sent instead of pretty-printing it"),
+            ]
+            .spacing(6),
+            column![
+                label("Repeated query parameters"),
+                pick_list(ArrayStyle::ALL, Some(self.settings.query_array_style), Message::ArrayStyleSelected)
+                    .text_size(11)
+                    .padding(10)
+                    .width(Fill)
+                    .style(pick_list_style),
+                hint("Applies when a key appears on more than one line in Params"),
+            ]
+            .spacing(6),
+            column![
+                text("User-Agent").size(11).color(colors::TEXT_SECONDARY),
+                row![
+                    field("Blank sends no User-Agent", &self.settings.user_agent, Message::UserAgentChanged),
+                    pick_list(
+                        USER_AGENT_PRESETS,
+                        USER_AGENT_PRESETS.iter().find(|p| p.value == self.settings.user_agent).copied(),
+                        Message::UserAgentPresetSelected,
+                    )
+                    .placeholder("Presets")
+                    .text_size(11)
+                    .padding(10)
+                    .width(Length::Fixed(150.0))
+                    .style(pick_list_style),
+                ]
+                .spacing(8),
+                text("A User-Agent line in the Headers tab takes precedence").size(10).color(colors::TEXT_SECONDARY),
+            ]
+            .spacing(6),
+            default_headers,
+            network,
+            column![
+                text("TLS").size(10).color(colors::TEXT_SECONDARY),
+                iced::widget::checkbox(self.settings.accept_invalid_certs)
+                    .label("Accept invalid certificates (self-signed, expired, wrong host)")
+                    .text_size(11)
+                    .on_toggle(Message::AcceptInvalidCertsToggled),
+                text("Client certificate (mTLS)").size(11).color(colors::TEXT_SECONDARY),
+                field("/path/to/client.p12 or client.pem", &cert.cert_path, Message::ClientCertPathChanged)
+                    .on_submit(Message::CheckClientCert),
+                field("/path/to/key.pem (PEM only, if not in the cert file)", &cert.key_path, Message::ClientKeyPathChanged)
+                    .on_submit(Message::CheckClientCert),
+                field("Passphrase (PKCS#12 only)", &cert.passphrase, Message::ClientCertPassphraseChanged)
+                    .secure(true)
+                    .on_submit(Message::CheckClientCert),
+                hint("The passphrase is kept for this session only and never saved"),
+                cert_status,
+            ]
+            .spacing(6),
+        ]
+        .spacing(16)
+        .padding(Padding { top: 0.0, right: 12.0, bottom: 0.0, left: 0.0 });
+
+        column![
+            text("SETTINGS").size(12).color(colors::TEXT_SECONDARY),
+            scrollable(sections).height(Length::Fixed(480.0)),
+            button(text("DONE").size(11))
+                .padding([10, 20])
+                .style(primary_button_style)
+                .on_press(Message::ToggleSettings),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(460.0))
+        .into()
+    }
+
+    fn view_environments(&self) -> Element<'_, Message> {
+        let list: Vec<Element<Message>> = self.environments.iter()
+            .enumerate()
+            .map(|(i, env)| {
+                let active = self.active_environment == Some(i);
+                button(text(env.name.clone()).size(11))
+                    .width(Fill)
+                    .padding([6, 10])
+                    .style(move |_, status| {
+                        let bg = match status {
+                            _ if active => colors::BG_ELEVATED,
+                            button::Status::Hovered => colors::BG_ELEVATED,
+                            _ => colors::BG_DARK,
+                        };
+                        button::Style {
+                            background: Some(bg.into()),
+                            text_color: if active { colors::ACCENT_PURPLE } else { colors::TEXT_PRIMARY },
+                            border: iced::Border::default(),
+                            ..Default::default()
+                        }
+                    })
+                    .on_press(Message::EnvironmentSelected(EnvironmentChoice {
+                        index: Some(i),
+                        name: env.name.clone(),
+                    }))
+                    .into()
+            })
+            .collect();
+
+        let sidebar = column![
+            scrollable(Column::from_vec(list).spacing(2).width(Fill)).height(Fill),
+            button(text("+ NEW").size(10))
+                .width(Fill)
+                .padding([6, 10])
+                .style(secondary_button_style)
+                .on_press(Message::AddEnvironment),
+        ]
+        .spacing(8)
+        .width(Length::Fixed(160.0));
+
+        let details: Element<Message> = match self.active_environment() {
+            Some(env) => column![
+                column![
+                    text("Name").size(11).color(colors::TEXT_SECONDARY),
+                    text_input("Name", &env.name)
+                        .on_input(Message::EnvironmentNameChanged)
+                        .padding(8)
+                        .size(12)
+                        .style(input_style),
+                ].spacing(4),
+                column![
+                    text("Base URL").size(11).color(colors::TEXT_SECONDARY),
+                    text_input("https://staging.example.com", &env.base_url)
+                        .on_input(Message::EnvironmentBaseUrlChanged)
+                        .padding(8)
+                        .size(12)
+                        .style(input_style),
+                    text("Prefixed to URLs typed without a scheme, e.g. /api/users")
+                        .size(10)
+                        .color(colors::TEXT_SECONDARY),
+                ].spacing(4),
+                column![
+                    text("Variables").size(11).color(colors::TEXT_SECONDARY),
+                    text_editor(&self.env_variables_editor)
+                        .placeholder("token=abc123\nuser_id=42")
+                        .on_action(Message::EnvironmentVariablesChanged)
+                        .padding(8)
+                        .height(Fill)
+                        .style(|_, _| text_editor::Style {
+                            background: colors::BG_ELEVATED.into(),
+                            border: iced::Border {
+                                color: colors::BORDER,
+                                width: 1.0,
+                                radius: 0.0.into(),
+                            },
+                            placeholder: colors::TEXT_SECONDARY,
+                            value: colors::TEXT_PRIMARY,
+                            selection: colors::ACCENT_PURPLE,
+                        }),
+                    text("One key=value per line, used as {{key}} in the URL, params, headers, body and auth")
+                        .size(10)
+                        .color(colors::TEXT_SECONDARY),
+                    secret_toggles(env),
+                ].spacing(4).height(Fill),
+                button(text("DELETE ENVIRONMENT").size(10))
+                    .padding([6, 10])
+                    .style(secondary_button_style)
+                    .on_press(Message::DeleteEnvironment),
+            ]
+            .spacing(12)
+            .into(),
+            None => container(
+                text("Select or create an environment")
+                    .size(11)
+                    .color(colors::TEXT_SECONDARY),
+            )
+            .center_x(Fill)
+            .center_y(Fill)
+            .into(),
+        };
+
+        column![
+            row![
+                text("ENVIRONMENTS").size(12).color(colors::TEXT_SECONDARY).width(Fill),
+                button(text("CLOSE").size(11))
+                    .padding([6, 12])
+                    .style(secondary_button_style)
+                    .on_press(Message::ToggleEnvironments),
+            ]
+            .align_y(iced::Alignment::Center),
+            row![sidebar, details].spacing(16).height(Fill),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(640.0))
+        .height(Length::Fixed(420.0))
+        .into()
+    }
+
+    fn view_save_request(&self) -> Element<'_, Message> {
+        let name_input = text_input("Request name (defaults to method + URL)", &self.save_name)
+            .on_input(Message::SaveNameChanged)
+            .on_submit(Message::SaveRequest)
+            .padding(10)
+            .size(12)
+            .width(Fill)
+            .style(input_style);
+
+        let collection_input = text_input("Collection", &self.save_collection)
+            .on_input(Message::SaveCollectionChanged)
+            .on_submit(Message::SaveRequest)
+            .padding(10)
+            .size(12)
+            .width(Fill)
+            .style(input_style);
+
+        let existing = self.collections.iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let cancel_btn = button(text("CANCEL").size(11))
+            .padding([10, 20])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleSaveRequest);
+
+        let save_btn = button(text("SAVE").size(11))
+            .padding([10, 20])
+            .style(primary_button_style)
+            .on_press(Message::SaveRequest);
+
+        let mut content = column![
+            text("SAVE TO COLLECTION").size(12).color(colors::TEXT_SECONDARY),
+            column![
+                text("Name").size(11).color(colors::TEXT_SECONDARY),
+                name_input,
+            ].spacing(4),
+            column![
+                text("Collection").size(11).color(colors::TEXT_SECONDARY),
+                collection_input,
+            ].spacing(4),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(500.0));
+
+        if !existing.is_empty() {
+            content = content.push(
+                text(format!("Existing: {}", existing))
+                    .size(10)
+                    .color(colors::TEXT_SECONDARY),
+            );
+        }
+
+        content.push(row![cancel_btn, save_btn].spacing(8)).into()
+    }
+
+    fn view_snippets(&self) -> Element<'_, Message> {
+        let mut list = column![].spacing(4).width(Fill);
+        for (i, snippet) in self.snippets.iter().enumerate() {
+            let entry = button(
+                row![
+                    text(snippet.method.to_string()).size(10).color(snippet.method.color()),
+                    text(snippet.name.clone()).size(11).color(colors::TEXT_PRIMARY).width(Fill),
+                    text(truncate_str(&snippet.url, 36)).size(10).color(colors::TEXT_SECONDARY),
+                ]
+                .spacing(8),
+            )
+            .width(Fill)
+            .padding([6, 10])
+            .style(|_, status| button::Style {
+                background: Some(if matches!(status, button::Status::Hovered) {
+                    colors::BG_PANEL.into()
+                } else {
+                    colors::BG_ELEVATED.into()
+                }),
+                text_color: colors::TEXT_PRIMARY,
+                ..Default::default()
+            })
+            .on_press(Message::SnippetClicked(i));
+
+            let delete = button(text("x").size(9))
+                .padding([6, 8])
+                .style(secondary_button_style)
+                .on_press(Message::DeleteSnippet(i));
+
+            list = list.push(row![entry, delete].spacing(2));
+        }
+
+        let name_input = text_input("Snippet name", &self.snippet_name)
+            .on_input(Message::SnippetNameChanged)
+            .on_submit(Message::SaveSnippet)
+            .padding(10)
+            .size(12)
+            .width(Fill)
+            .style(input_style);
+
+        let save_btn = button(text("SAVE CURRENT").size(11))
+            .padding([10, 20])
+            .style(primary_button_style)
+            .on_press_maybe((!self.snippet_name.trim().is_empty()).then_some(Message::SaveSnippet));
+
+        let close_btn = button(text("CLOSE").size(11))
+            .padding([10, 20])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleSnippets);
+
+        let mut content = column![
+            text("SNIPPETS").size(12).color(colors::TEXT_SECONDARY),
+            text("Loading a snippet replaces the current request. Leave the {{placeholders}} in and fill \
+                  them from an environment or the Vars tab.")
+                .size(10)
+                .color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(12)
+        .width(Length::Fixed(500.0));
+
+        content = if self.snippets.is_empty() {
+            content.push(text("No snippets").size(11).color(colors::TEXT_SECONDARY))
+        } else {
+            content.push(scrollable(list).height(Length::Shrink))
+        };
+
+        content
+            .push(row![name_input, save_btn].spacing(8).align_y(iced::Alignment::Center))
+            .push(close_btn)
+            .into()
+    }
+
+    /// Pasted URLs, progress and one row per URL; a finished row loads its response
+    fn view_batch(&self) -> Element<'_, Message> {
+        let editor = text_editor(&self.batch_urls)
+            .placeholder("https://api.example.com/health\n{{base}}/v1/status\n/relative/to/the/base/url")
+            .on_action(Message::BatchUrlsAction)
+            .padding(10)
+            .height(120)
+            .style(|_, _| text_editor::Style {
+                background: colors::BG_ELEVATED.into(),
+                border: iced::Border { color: colors::BORDER, width: 1.0, radius: 0.0.into() },
+                placeholder: colors::TEXT_SECONDARY,
+                value: colors::TEXT_PRIMARY,
+                selection: colors::ACCENT_PURPLE,
+            });
+
+        let running = self.batch.as_ref().is_some_and(|b| !b.finished());
+        let action = if running {
+            button(text("STOP").size(11))
+                .padding([10, 20])
+                .style(secondary_button_style)
+                .on_press_maybe(self.batch.as_ref().is_some_and(|b| !b.stopped).then_some(Message::StopBatch))
+        } else {
+            button(text("RUN").size(11))
+                .padding([10, 20])
+                .style(primary_button_style)
+                .on_press_maybe((!self.batch_urls.text().trim().is_empty()).then_some(Message::RunBatch))
+        };
+        let close = button(text("CLOSE").size(11))
+            .padding([10, 20])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleBatch);
+
+        let mut content = column![
+            text("BATCH").size(12).color(colors::TEXT_SECONDARY),
+            text(format!(
+                "Sends this {} request, headers, body and auth included, to every URL below, {} at a time. \
+                 Batch sends aren't added to history.",
+                self.method, BATCH_CONCURRENCY,
+            ))
+            .size(10)
+            .color(colors::TEXT_SECONDARY),
+            editor,
+        ]
+        .spacing(12)
+        .width(Length::Fixed(640.0));
+
+        if let Some(ref batch) = self.batch {
+            let responses = batch.results.iter().flatten();
+            let ok = responses.clone().filter(|r| r.as_ref().is_ok_and(|r| !r.is_http_error())).count();
+            let failed = batch.done() - ok;
+            let state = if batch.finished() {
+                if batch.stopped { "Stopped" } else { "Done" }
+            } else if batch.stopped {
+                "Stopping..."
+            } else {
+                "Running..."
+            };
+            content = content.push(
+                row![
+                    text(format!("{} / {}", batch.done(), batch.urls.len())).size(12).color(colors::TEXT_PRIMARY),
+                    text(format!("{} ok", ok)).size(12).color(colors::SUCCESS),
+                    text(format!("{} failed", failed))
+                        .size(12)
+                        .color(if failed > 0 { colors::ERROR } else { colors::TEXT_SECONDARY }),
+                    text(state).size(12).color(if batch.finished() { colors::TEXT_SECONDARY } else { colors::WARNING }),
+                ]
+                .spacing(16),
+            );
+
+            let mut rows = column![].spacing(2).width(Fill);
+            for (i, (url, result)) in batch.urls.iter().zip(&batch.results).enumerate() {
+                let (status, status_color, timing) = match result {
+                    Some(Ok(response)) => (
+                        response.status.to_string(),
+                        status_color(response.status),
+                        format!("{}ms", response.duration.as_millis()),
+                    ),
+                    Some(Err(e)) => (truncate_str(e, 24), colors::ERROR, String::new()),
+                    None if i < batch.next => ("...".to_string(), colors::WARNING, String::new()),
+                    None => ("-".to_string(), colors::TEXT_SECONDARY, String::new()),
+                };
+                rows = rows.push(
+                    button(
+                        row![
+                            text(truncate_str(url, 60)).size(11).color(colors::TEXT_PRIMARY).width(Fill),
+                            container(text(status).size(11).color(status_color)).width(Length::Fixed(160.0)),
+                            container(text(timing).size(11).color(colors::TEXT_SECONDARY)).width(Length::Fixed(64.0)),
+                        ]
+                        .spacing(8),
+                    )
+                    .width(Fill)
+                    .padding([4, 8])
+                    .style(|_, status| button::Style {
+                        background: Some(if matches!(status, button::Status::Hovered) {
+                            colors::BG_PANEL.into()
+                        } else {
+                            colors::BG_ELEVATED.into()
+                        }),
+                        text_color: colors::TEXT_PRIMARY,
+                        ..Default::default()
+                    })
+                    .on_press_maybe(result.is_some().then_some(Message::BatchResultClicked(i))),
+                );
+            }
+            content = content.push(scrollable(rows).height(Length::Fixed(240.0)));
+        }
+
+        content.push(row![action, close].spacing(8)).into()
+    }
+
+    /// This session's responses by duration; clicking one loads its request
+    fn view_timing_log(&self) -> Element<'_, Message> {
+        let mut order: Vec<usize> = (0..self.timing_log.len()).rev().collect();
+        if self.timing_log_slowest_first {
+            order.sort_by_key(|&i| std::cmp::Reverse(self.timing_log[i].duration));
+        }
+        let slowest = self.timing_log.iter().map(|e| e.duration).max().unwrap_or_default().as_secs_f32().max(0.001);
+
+        let mut list = column![].spacing(2).width(Fill);
+        for i in order {
+            let entry = &self.timing_log[i];
+            let request = &entry.request;
+            let over = self.over_latency_budget(entry.duration);
+            let bar = container(text(""))
+                .width(Length::Fixed(80.0 * entry.duration.as_secs_f32() / slowest))
+                .height(4)
+                .style(move |_| container::Style {
+                    background: Some(if over { colors::ERROR } else { colors::ACCENT_PURPLE }.into()),
+                    ..Default::default()
+                });
+            list = list.push(
+                button(
+                    row![
+                        text(request.method.to_string()).size(10).color(request.method.color()).width(52),
+                        text(truncate_str(&request.url, 56)).size(10).color(colors::TEXT_PRIMARY).width(Fill),
+                        text(entry.status.to_string()).size(10).color(status_color(entry.status)),
+                        container(bar).width(80),
+                        text(format!("{}ms", entry.duration.as_millis()))
+                            .size(10)
+                            .color(if over { colors::ERROR } else { colors::TEXT_SECONDARY })
+                            .width(64)
+                            .align_x(iced::alignment::Horizontal::Right),
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                )
+                .width(Fill)
+                .padding([4, 8])
+                .style(|_, status| button::Style {
+                    background: Some(if matches!(status, button::Status::Hovered) {
+                        colors::BG_PANEL.into()
+                    } else {
+                        colors::BG_ELEVATED.into()
+                    }),
+                    text_color: colors::TEXT_PRIMARY,
+                    ..Default::default()
+                })
+                .on_press(Message::TimingLogEntryClicked(i)),
+            );
+        }
+
+        let summary = if self.timing_log.is_empty() {
+            "No responses yet this session".to_string()
+        } else {
+            let total: Duration = self.timing_log.iter().map(|e| e.duration).sum();
+            format!(
+                "{} responses · average {}ms · slowest {}ms",
+                self.timing_log.len(),
+                (total / self.timing_log.len() as u32).as_millis(),
+                (slowest * 1000.0).round(),
+            )
+        };
+
+        let controls = row![
+            iced::widget::checkbox(self.timing_log_slowest_first)
+                .label("Slowest first")
+                .text_size(10)
+                .on_toggle(Message::TimingLogSortToggled),
+            container(text("")).width(Fill),
+            button(text("CLEAR").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press_maybe((!self.timing_log.is_empty()).then_some(Message::ClearTimingLog)),
+            button(text("CLOSE").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press(Message::ToggleTimingLog),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        column![
+            text("TIMING LOG").size(12).color(colors::TEXT_SECONDARY),
+            text(summary).size(10).color(colors::TEXT_SECONDARY),
+            scrollable(list).height(Length::Fixed(360.0)),
+            controls,
+        ]
+        .spacing(12)
+        .width(Length::Fixed(640.0))
+        .into()
+    }
+
+    fn view_collection_run<'a>(&'a self, run: &'a CollectionRun) -> Element<'a, Message> {
+        let collection = self.collections.get(run.collection);
+        let name = collection.map_or("", |c| c.name.as_str());
+        let total = collection.map_or(0, |c| c.requests.len());
+
+        let passed = run.results.iter().filter(|r| r.passed()).count();
+        let failed = run.results.len() - passed;
+        let elapsed: std::time::Duration = run.results.iter().filter_map(|r| r.duration).sum();
+        let finished = !run.in_flight() && (run.stopped || run.results.len() == total);
+
+        let state = if run.in_flight() && run.stopped {
+            "Stopping..."
+        } else if run.in_flight() {
+            "Running..."
+        } else if run.results.len() == total {
+            "Finished"
+        } else {
+            "Stopped"
+        };
+
+        let progress = row![
+            text(format!("{} / {}", run.results.len(), total)).size(12).color(colors::TEXT_PRIMARY),
+            text(format!("{} passed", passed)).size(12).color(colors::SUCCESS),
+            text(format!("{} failed", failed))
+                .size(12)
+                .color(if failed > 0 { colors::ERROR } else { colors::TEXT_SECONDARY }),
+            text(format!("{}ms", elapsed.as_millis())).size(12).color(colors::TEXT_SECONDARY),
+            text(state).size(12).color(if finished { colors::TEXT_SECONDARY } else { colors::WARNING }),
+        ]
+        .spacing(16);
+
+        let steps: Vec<Element<Message>> = collection
+            .map(|c| c.requests.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(i, saved)| {
+                let (verdict, verdict_color, status, timing) = match run.results.get(i) {
+                    Some(result) => (
+                        if result.passed() { "PASS" } else { "FAIL" },
+                        if result.passed() { colors::SUCCESS } else { colors::ERROR },
+                        match (result.status, &result.error) {
+                            (Some(status), _) => status.to_string(),
+                            (None, Some(error)) => truncate_str(error, 24),
+                            (None, None) => String::new(),
+                        },
+                        result.duration.map(|d| format!("{}ms", d.as_millis())).unwrap_or_default(),
+                    ),
+                    None if i + 1 == run.next => ("...", colors::WARNING, String::new(), String::new()),
+                    None => ("-", colors::TEXT_SECONDARY, String::new(), String::new()),
+                };
+                let code_color = run.results.get(i)
+                    .and_then(|r| r.status)
+                    .map_or(colors::ERROR, status_color);
+
+                row![
+                    container(text(verdict).size(11).color(verdict_color)).width(Length::Fixed(40.0)),
+                    container(text(saved.method.to_string()).size(11).color(saved.method.color()))
+                        .width(Length::Fixed(64.0)),
+                    container(text(truncate_str(&saved.name, 36)).size(11).color(colors::TEXT_PRIMARY))
+                        .width(Fill),
+                    container(text(status).size(11).color(code_color)).width(Length::Fixed(160.0)),
+                    container(text(timing).size(11).color(colors::TEXT_SECONDARY)).width(Length::Fixed(64.0)),
+                ]
+                .spacing(8)
+                .into()
+            })
+            .collect();
+
+        let stop_on_failure = iced::widget::checkbox(self.run_stop_on_failure)
+            .label("Stop on first failure")
+            .text_size(11)
+            .on_toggle(Message::StopOnFailureToggled);
+
+        let mut buttons = row![].spacing(8);
+        if finished {
+            buttons = buttons
+                .push(
+                    button(text("CLOSE").size(11))
+                        .padding([10, 20])
+                        .style(secondary_button_style)
+                        .on_press(Message::CloseCollectionRun),
+                )
+                .push(
+                    button(text("RUN AGAIN").size(11))
+                        .padding([10, 20])
+                        .style(primary_button_style)
+                        .on_press(Message::RunCollection(run.collection)),
+                );
+        } else {
+            buttons = buttons.push(
+                button(text("STOP").size(11))
+                    .padding([10, 20])
+                    .style(secondary_button_style)
+                    .on_press_maybe((!run.stopped).then_some(Message::StopCollectionRun)),
+            );
+        }
+
+        column![
+            text(format!("RUN COLLECTION: {}", name)).size(12).color(colors::TEXT_SECONDARY),
+            progress,
+            scrollable(Column::from_vec(steps).spacing(6).width(Fill)).height(Length::Fixed(280.0)),
+            stop_on_failure,
+            buttons,
+        ]
+        .spacing(12)
+        .width(Length::Fixed(640.0))
+        .into()
+    }
+
+    fn view_resize_handle(&self, target: DragTarget) -> Element<'_, Message> {
+        let is_dragging = self.dragging == Some(target);
+        let handle_color = if is_dragging { colors::ACCENT_PURPLE } else { colors::BORDER };
+
+        mouse_area(
+            container(column![])
+                .width(4)
+                .height(Fill)
+                .style(move |_| container::Style {
+                    background: Some(handle_color.into()),
+                    ..Default::default()
+                })
+        )
+        .on_press(Message::StartDrag(target))
+        .on_release(Message::EndDrag)
+        .into()
+    }
+
+    fn view_status_bar(&self) -> Element<'_, Message> {
+        let method_color = self.method.color();
+
+        let env_indicator = button(
+            text(self.active_environment().map_or("No environment", |env| env.name.as_str()).to_string())
+                .size(10)
+                .color(if self.active_environment.is_some() { colors::ACCENT_PURPLE } else { colors::TEXT_SECONDARY }),
+        )
+        .padding(0)
+        .style(|_, _| button::Style::default())
+        .on_press(Message::OpenEnvSwitcher);
+
+        let left_items = row![
+            env_indicator,
+            text(self.method.to_string()).size(10).color(method_color),
+            text(truncate_str(&self.url, 50)).size(10).color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(8);
+
+        let network_indicator = if !self.offline() {
+            text("").size(10)
+        } else {
+            text("Offline").size(10).color(colors::ERROR)
+        };
+
+        let auth_indicator = match self.auth_type {
+            AuthType::None => text("").size(10),
+            AuthType::Bearer => text("Bearer").size(10).color(colors::SUCCESS),
+            AuthType::Basic => text("Basic").size(10).color(colors::SUCCESS),
+            AuthType::Ntlm => text("NTLM").size(10).color(colors::SUCCESS),
+        };
+
+        let socket_indicator = match split_unix_socket_url(&self.effective_url()) {
+            Some((socket, _)) => text(format!("socket: {}", socket)).size(10).color(colors::ACCENT_CORAL),
+            None => text("").size(10),
+        };
+
+        let status_indicator = if self.loading {
+            text("Sending...").size(10).color(colors::WARNING)
+        } else if let Some(ref response) = self.response {
+            if response.is_http_error() {
+                text(format!("HTTP {}", response.status)).size(10).color(status_color(response.status))
+            } else {
+                text("Ready").size(10).color(colors::SUCCESS)
+            }
+        } else if self.error.is_some() {
+            text("Connection failed").size(10).color(colors::ERROR)
+        } else {
+            text("Ready").size(10).color(colors::TEXT_SECONDARY)
+        };
+
+        let incognito_indicator: Element<Message> = if self.incognito {
+            button(text("Incognito").size(10).color(colors::ACCENT_PURPLE))
+                .padding(0)
+                .style(|_, _| button::Style::default())
+                .on_press(Message::IncognitoToggled(false))
+                .into()
+        } else {
+            text("").size(10).into()
+        };
+
+        let poll_indicator = if self.polling {
+            text(format!("Polling every {}s", self.poll_interval.0)).size(10).color(colors::TEXT_SECONDARY)
+        } else {
+            text("").size(10)
+        };
+
+        let schedule_indicator: Element<Message> = match self.scheduled_send {
+            Some(ref scheduled) => button(
+                text(format!(
+                    "Sending in {} (cancel)",
+                    format_countdown(scheduled.at.saturating_duration_since(Instant::now())),
+                ))
+                .size(10)
+                .color(colors::WARNING),
+            )
+            .padding(0)
+            .style(|_, _| button::Style::default())
+            .on_press(Message::CancelScheduledSend)
+            .into(),
+            None => text("").size(10).into(),
+        };
+
+        let watch_indicator: Element<Message> = match self.watch {
+            Some(ref watch) => button(
+                text(format!(
+                    "Watching: attempt {}, {} (stop)",
+                    watch.attempts + u32::from(watch.next_at.is_none()),
+                    format_countdown(watch.started.elapsed()),
+                ))
+                .size(10)
+                .color(colors::WARNING),
+            )
+            .padding(0)
+            .style(|_, _| button::Style::default())
+            .on_press(Message::WatchToggled(false))
+            .into(),
+            None => text("").size(10).into(),
+        };
+
+        let budget_indicator = if self.over_budget > 0 {
+            text(format!("{} over {}ms", self.over_budget, self.settings.latency_budget_ms))
+                .size(10)
+                .color(colors::ERROR)
+        } else {
+            text("").size(10)
+        };
+
+        let breadcrumb: Element<'_, Message> = match self.json_breadcrumb {
+            Some(ref path) => iced::widget::tooltip(
+                button(text(truncate_str(path, 60)).size(10).color(colors::ACCENT_PURPLE))
+                    .padding(0)
+                    .style(|_, _| button::Style::default())
+                    .on_press(Message::CopyJsonValue(path.clone())),
+                tooltip_box("Copy this path"),
+                iced::widget::tooltip::Position::Top,
+            )
+            .into(),
+            None => text("").size(10).into(),
+        };
+
+        let history_count = text(format!("{} requests", self.history.len()))
+            .size(10)
+            .color(colors::TEXT_SECONDARY);
+
+        let timing_log_button = button(text("Timing log").size(10).color(colors::TEXT_SECONDARY))
+            .padding(0)
+            .style(|_, _| button::Style::default())
+            .on_press(Message::ToggleTimingLog);
+
+        let shortcut_hint = text("Cmd+Enter to send, Cmd+/ for shortcuts")
+            .size(10)
+            .color(colors::TEXT_SECONDARY);
+
+        let right_items = row![
+            network_indicator,
+            socket_indicator,
+            auth_indicator,
+            status_indicator,
+            incognito_indicator,
+            poll_indicator,
+            schedule_indicator,
+            watch_indicator,
+            budget_indicator,
+            breadcrumb,
+            timing_log_button,
+            history_count,
+            shortcut_hint,
+        ]
+        .spacing(16);
+
+        container(
+            row![left_items, right_items]
+                .width(Fill)
+                .align_y(iced::Alignment::Center)
+        )
+        .padding(Padding { top: 6.0, right: 16.0, bottom: 6.0, left: 16.0 })
+        .width(Fill)
+        .style(|_| container::Style {
+            background: Some(colors::BG_DARK.into()),
+            border: iced::Border {
+                color: colors::BORDER,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn view_sidebar(&self) -> Element<'_, Message> {
+        let title = text("HISTORY").size(10).color(colors::TEXT_SECONDARY);
+
+        let history_content: Element<Message> = if self.history.is_empty() {
+            container(
+                text("No requests yet").size(11).color(colors::TEXT_SECONDARY)
+            )
+            .padding(8)
+            .into()
+        } else {
+            let history_item = |idx: usize| -> Element<Message> {
+                let entry = &self.history[idx];
+                let status_color = match entry.status {
+                    200..=299 => colors::SUCCESS,
+                    400..=499 => colors::WARNING,
+                    500..=599 => colors::ERROR,
+                    _ => colors::TEXT_PRIMARY,
+                };
+
+                let url_display = if entry.url.len() > 18 {
+                    format!("{}...", &entry.url[..15])
+                } else {
+                    entry.url.clone()
+                };
+
+                button(
+                    column![
+                        row![
+                            text(entry.method.to_string())
+                                .size(10)
+                                .color(entry.method.color()),
+                            text(entry.status.to_string())
+                                .size(10)
+                                .color(status_color),
+                        ].spacing(8),
+                        text(url_display).size(10).color(colors::TEXT_SECONDARY),
+                    ].spacing(2),
+                )
+                .width(Fill)
+                .padding(8)
+                .style(|_, status| {
+                    let bg = match status {
+                        button::Status::Hovered => colors::BG_ELEVATED,
+                        _ => colors::BG_PANEL,
+                    };
+                    button::Style {
+                        background: Some(bg.into()),
+                        text_color: colors::TEXT_PRIMARY,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    }
+                })
+                .on_press(Message::HistoryEntryClicked(idx))
+                .into()
+            };
+
+            // Newest first, same window for both views
+            let recent = (0..self.history.len()).rev().take(50);
+            let items: Vec<Element<Message>> = if self.group_history_by_host {
+                let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+                for idx in recent {
+                    let host = url_host(&self.history[idx].url);
+                    match groups.iter_mut().find(|(h, _)| *h == host) {
+                        Some((_, entries)) => entries.push(idx),
+                        None => groups.push((host, vec![idx])),
+                    }
+                }
+
+                let mut items = Vec::new();
+                for (host, entries) in groups {
+                    let expanded = self.expanded_hosts.contains(&host);
+                    let marker = if expanded { "▾" } else { "▸" };
+                    items.push(
+                        button(
+                            row![
+                                text(marker).size(10).color(colors::TEXT_SECONDARY),
+                                text(truncate_str(&host, 22)).size(10).color(colors::TEXT_PRIMARY).width(Fill),
+                                text(entries.len().to_string()).size(10).color(colors::TEXT_SECONDARY),
+                            ]
+                            .spacing(6),
+                        )
+                        .width(Fill)
+                        .padding([4, 8])
+                        .style(|_, status| button::Style {
+                            background: matches!(status, button::Status::Hovered).then(|| colors::BG_ELEVATED.into()),
+                            text_color: colors::TEXT_PRIMARY,
+                            ..Default::default()
+                        })
+                        .on_press(Message::ToggleHostGroup(host.clone()))
+                        .into(),
+                    );
+                    if expanded {
+                        items.extend(entries.into_iter().map(history_item));
+                    }
+                }
+                items
+            } else {
+                recent.map(history_item).collect()
+            };
+
+            scrollable(Column::from_vec(items).spacing(4).width(Fill))
+                .height(Length::FillPortion(2))
+                .into()
+        };
+
+        let import_btn = iced::widget::tooltip(
+            button(text("IMPORT").size(9))
+                .padding([2, 6])
+                .style(secondary_button_style)
+                .on_press(Message::ImportPostman),
+            tooltip_box("Import a Postman v2.1 collection"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+        let import_http_btn = iced::widget::tooltip(
+            button(text(".HTTP").size(9))
+                .padding([2, 6])
+                .style(secondary_button_style)
+                .on_press(Message::ImportHttpFile),
+            tooltip_box("Import requests and @variables from a REST Client .http file"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+        let mut sidebar = column![
+            row![
+                container(text("COLLECTIONS").size(10).color(colors::TEXT_SECONDARY)).width(Fill),
+                row![import_btn, import_http_btn].spacing(4),
+            ]
+            .align_y(iced::Alignment::Center),
+        ]
+        .spacing(12)
+        .width(Fill);
+        if !self.collections.is_empty() {
+            sidebar = sidebar.push(self.view_collections());
+        }
+        let settings_btn = button(text("SETTINGS").size(9))
+            .padding([2, 6])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleSettings);
+        let group_toggle = iced::widget::checkbox(self.group_history_by_host)
+            .label("By host")
+            .text_size(9)
+            .on_toggle(Message::GroupHistoryByHostToggled);
+        let header = row![container(title).width(Fill), group_toggle, settings_btn]
+            .spacing(6)
+            .align_y(iced::Alignment::Center);
+        sidebar = sidebar.push(header).push(history_content);
+
+        container(sidebar)
+        .padding(12)
+        .width(Fill)
+        .height(Fill)
+        .style(|_| container::Style {
+            background: Some(colors::BG_DARK.into()),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn view_collections(&self) -> Element<'_, Message> {
+        let running = self.collection_run.is_some();
+        let mut list = column![].spacing(4).width(Fill);
+
+        for (ci, collection) in self.collections.iter().enumerate() {
+            let run_btn = button(text("RUN").size(9))
+                .padding([2, 6])
+                .style(secondary_button_style)
+                .on_press_maybe((!running && !self.loading).then_some(Message::RunCollection(ci)));
+            let export_btn = iced::widget::tooltip(
+                button(text("EXPORT").size(9))
+                    .padding([2, 6])
+                    .style(secondary_button_style)
+                    .on_press(Message::ExportCollection(ci)),
+                tooltip_box("Save as a Postman v2.1 collection"),
+                iced::widget::tooltip::Position::Bottom,
+            );
+
+            list = list.push(
+                row![
+                    text(format!("{} ({})", collection.name, collection.requests.len()))
+                        .size(10)
+                        .color(colors::TEXT_PRIMARY)
+                        .width(Fill),
+                    export_btn,
+                    run_btn,
+                ]
+                .spacing(4)
+                .align_y(iced::Alignment::Center),
+            );
+
+            for (ri, saved) in collection.requests.iter().enumerate() {
+                let entry = button(
+                    row![
+                        text(saved.method.to_string()).size(10).color(saved.method.color()),
+                        text(truncate_str(&saved.name, 18)).size(10).color(colors::TEXT_SECONDARY),
+                    ]
+                    .spacing(6),
+                )
+                .width(Fill)
+                .padding([4, 8])
+                .style(|_, status| {
+                    let bg = match status {
+                        button::Status::Hovered => colors::BG_ELEVATED,
+                        _ => colors::BG_PANEL,
+                    };
+                    button::Style {
+                        background: Some(bg.into()),
+                        text_color: colors::TEXT_PRIMARY,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    }
+                })
+                .on_press(Message::SavedRequestClicked(ci, ri));
+
+                let delete = button(text("x").size(9))
+                    .padding([4, 6])
+                    .style(secondary_button_style)
+                    .on_press_maybe((!running).then_some(Message::DeleteSavedRequest(ci, ri)));
+
+                list = list.push(row![entry, delete].spacing(2));
+            }
+        }
+
+        scrollable(list).height(Length::FillPortion(1)).into()
+    }
+
+    fn view_url_bar(&self) -> Element<'_, Message> {
+        let method_picker = pick_list(Method::ALL, Some(self.method), Message::MethodSelected)
+            .text_size(12)
+            .padding(10)
+            .width(90)
+            .style(|_, _| pick_list::Style {
+                text_color: self.method.color(),
+                placeholder_color: colors::TEXT_SECONDARY,
+                handle_color: colors::TEXT_SECONDARY,
+                background: colors::BG_ELEVATED.into(),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+            });
+
+        let url_input = text_input("https://api.example.com/endpoint", &self.url)
+            .id(URL_INPUT_ID)
+            .on_input(Message::UrlChanged)
+            .on_submit(Message::UrlSubmitted)
+            .padding(10)
+            .size(12)
+            .width(Fill)
+            .style(|_, _| text_input::Style {
+                background: colors::BG_ELEVATED.into(),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+                icon: colors::TEXT_SECONDARY,
+                placeholder: colors::TEXT_SECONDARY,
+                value: colors::TEXT_PRIMARY,
+                selection: colors::ACCENT_PURPLE,
+            });
+
+        // Local targets don't need the internet, so only block remote ones
+        let offline = self.offline() && !is_local_url(&self.effective_url());
+        let send_text = if self.loading { "..." } else { "SEND" };
+        let send_button = button(text(send_text).size(11))
+            .padding([10, 20])
+            .style(move |_, status| {
+                let bg = match status {
+                    button::Status::Hovered => colors::ACCENT_CORAL,
+                    button::Status::Pressed => colors::WARNING,
+                    button::Status::Disabled if !offline => colors::ACCENT_PURPLE,
+                    button::Status::Disabled => colors::BG_ELEVATED,
+                    _ => colors::ACCENT_PURPLE,
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: colors::BG_DARKEST,
+                    border: iced::Border {
+                        radius: 0.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            })
+            .on_press_maybe(if self.loading || offline { None } else { Some(Message::Send) });
+
+        let send_button: Element<Message> = if offline {
+            iced::widget::tooltip(
+                send_button,
+                tooltip_box("You appear to be offline. Turn off the network check in Settings to send anyway"),
+                iced::widget::tooltip::Position::Bottom,
+            )
+            .into()
+        } else {
+            send_button.into()
+        };
+
+        let import_button = button(text("cURL").size(10))
+            .padding([10, 12])
+            .style(|_, status| {
+                let bg = match status {
+                    button::Status::Hovered => colors::BG_ELEVATED,
+                    _ => colors::BG_DARK,
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: colors::TEXT_SECONDARY,
+                    border: iced::Border {
+                        color: colors::BORDER,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::ToggleCurlImport);
+
+        let save_button = button(text("SAVE").size(10))
+            .padding([10, 12])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleSaveRequest);
+
+        let mut choices = vec![EnvironmentChoice { index: None, name: "No environment".to_string() }];
+        choices.extend(self.environments.iter().enumerate().map(|(i, env)| EnvironmentChoice {
+            index: Some(i),
+            name: env.name.clone(),
+        }));
+        let selected = choices.iter().find(|c| c.index == self.active_environment).cloned();
+
+        let env_picker = pick_list(choices, selected, Message::EnvironmentSelected)
+            .text_size(10)
+            .padding(10)
+            .width(140)
+            .style(|_, _| pick_list::Style {
+                text_color: colors::TEXT_PRIMARY,
+                placeholder_color: colors::TEXT_SECONDARY,
+                handle_color: colors::TEXT_SECONDARY,
+                background: colors::BG_DARK.into(),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+            });
+
+        let env_button = button(text("ENV").size(10))
+            .padding([10, 12])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleEnvironments);
+
+        let parts_button = iced::widget::tooltip(
+            button(text("PARTS").size(10))
+                .padding([10, 12])
+                .style(if self.show_url_parts { primary_button_style } else { secondary_button_style })
+                .on_press(Message::ToggleUrlParts),
+            tooltip_box("Edit the URL by scheme, host, port, path and query"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let off_record = iced::widget::tooltip(
+            iced::widget::checkbox(self.off_record)
+                .label("Off record")
+                .text_size(10)
+                .on_toggle(Message::OffRecordToggled),
+            tooltip_box("Keep this request's sends out of history and the response archive"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let bar = row![
+            method_picker, url_input, parts_button, env_picker, env_button, import_button, save_button, off_record, send_button,
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let mut bar = column![bar].spacing(6).padding(12);
+
+        if self.show_url_parts {
+            let parts = UrlParts::split(&self.url);
+            let field = |label: &'static str, placeholder: &'static str, part: UrlPart, width: Length| {
+                column![
+                    text(label).size(9).color(colors::TEXT_SECONDARY),
+                    text_input(placeholder, parts.get(part))
+                        .on_input(move |value| Message::UrlPartChanged(part, value))
+                        .padding(4)
+                        .size(10)
+                        .style(input_style),
+                ]
+                .spacing(2)
+                .width(width)
+            };
+            bar = bar.push(
+                row![
+                    field("Scheme", "https", UrlPart::Scheme, Length::Fixed(70.0)),
+                    field("Host", "api.example.com", UrlPart::Host, Length::FillPortion(3)),
+                    field("Port", "443", UrlPart::Port, Length::Fixed(60.0)),
+                    field("Path", "/v1/users", UrlPart::Path, Length::FillPortion(4)),
+                    field("Query", "page=1&limit=10", UrlPart::Query, Length::FillPortion(3)),
+                    field("Fragment", "", UrlPart::Fragment, Length::Fixed(90.0)),
+                ]
+                .spacing(8),
+            );
+        }
+
+        if let Some(problem) = url_problem(&self.effective_url()) {
+            bar = bar.push(text(problem).size(10).color(colors::WARNING));
+        }
+
+        if let Some(credential) = cleartext_credential(
+            &self.effective_url(),
+            self.auth_type,
+            &merge_default_headers(&self.settings.default_headers, &self.request_headers.text()),
+        ) {
+            bar = bar.push(
+                text(format!("{} will be sent unencrypted over http://; use https:// to protect it", credential))
+                    .size(10)
+                    .color(colors::WARNING),
+            );
+        }
+
+        match url_paste_hint(&self.url) {
+            Some(UrlPasteHint::Curl) => {
+                let open_import = button(text("OPEN IN cURL IMPORT").size(10))
+                    .padding([4, 8])
+                    .style(secondary_button_style)
+                    .on_press(Message::ImportCurlFromUrl);
+                bar = bar.push(
+                    row![
+                        text("This looks like a cURL command, not a URL").size(10).color(colors::WARNING),
+                        open_import,
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                );
+            }
+            Some(UrlPasteHint::Json) => {
+                bar = bar.push(
+                    text("This looks like JSON, not a URL. Did you mean to paste it into the Body tab?")
+                        .size(10)
+                        .color(colors::WARNING),
+                );
+            }
+            None => {}
+        }
+
+        let mut pages_row = row![
+            iced::widget::checkbox(self.fetch_all_pages)
+                .label("Fetch all pages")
+                .text_size(10)
+                .on_toggle(Message::FetchAllPagesToggled),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+        if self.fetch_all_pages {
+            pages_row = pages_row.push(
+                text_input("Next page field, e.g. .next (blank uses the Link header)", &self.next_page_path)
+                    .on_input(Message::NextPagePathChanged)
+                    .padding(4)
+                    .size(10)
+                    .width(Length::Fixed(360.0))
+                    .style(input_style),
+            );
+        }
+        bar = bar.push(pages_row);
+
+        let mut poll_row = row![
+            iced::widget::checkbox(self.polling)
+                .label("Poll")
+                .text_size(10)
+                .on_toggle(Message::PollingToggled),
+            pick_list(POLL_INTERVALS, Some(self.poll_interval), Message::PollIntervalSelected)
+                .text_size(10)
+                .padding([2, 6])
+                .style(pick_list_style),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+        if self.polling {
+            poll_row = poll_row.push(
+                iced::widget::checkbox(self.poll_stop_on_error)
+                    .label("Stop on error")
+                    .text_size(10)
+                    .on_toggle(Message::PollStopOnErrorToggled),
+            );
+        }
+        poll_row = poll_row.push(
+            text_input("Send in 30s / at 14:05", &self.schedule_input)
+                .on_input(Message::ScheduleInputChanged)
+                .on_submit(Message::ScheduleSend)
+                .padding(4)
+                .size(10)
+                .width(Length::Fixed(150.0))
+                .style(input_style),
+        );
+        poll_row = poll_row.push(if self.scheduled_send.is_some() {
+            button(text("CANCEL").size(9))
+                .padding([2, 6])
+                .style(secondary_button_style)
+                .on_press(Message::CancelScheduledSend)
+        } else {
+            button(text("SCHEDULE").size(9))
+                .padding([2, 6])
+                .style(secondary_button_style)
+                .on_press_maybe((!self.schedule_input.trim().is_empty()).then_some(Message::ScheduleSend))
+        });
+        poll_row = poll_row.push(
+            iced::widget::checkbox(self.watch.is_some())
+                .label("Retry until")
+                .text_size(10)
+                .on_toggle(Message::WatchToggled),
+        );
+        poll_row = poll_row.push(
+            text_input("2xx", &self.watch_status)
+                .on_input(Message::WatchStatusChanged)
+                .on_submit(Message::WatchToggled(true))
+                .padding(4)
+                .size(10)
+                .width(Length::Fixed(90.0))
+                .style(input_style),
+        );
+        poll_row = poll_row.push(
+            pick_list(WATCH_LIMITS, Some(self.watch_limit), Message::WatchLimitSelected)
+                .text_size(10)
+                .padding([2, 6])
+                .style(pick_list_style),
+        );
+        bar = bar.push(poll_row);
+
+        // Show where the request will actually go when it differs from what was typed
+        let effective_url = self.effective_url();
+        if effective_url != self.url {
+            bar = bar.push(
+                text(format!("-> {}", self.masked(&effective_url)))
+                    .size(10)
+                    .color(colors::TEXT_SECONDARY),
+            );
+        }
+
+        container(bar)
+            .width(Fill)
+            .style(|_| container::Style {
+                background: Some(colors::BG_PANEL.into()),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 0.0,
+                    radius: 0.0.into(),
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Per-request variable overrides, with a note on what each one shadows
+    fn view_request_variables(&self) -> Element<'_, Message> {
+        let env = self.active_environment();
+        let env_vars = env.map(Environment::variables).unwrap_or_default();
+        let overrides = parse_variables(&self.request_variables.text());
+
+        let mut notes = column![
+            text(match env {
+                Some(env) => format!("Used instead of {} for this request only", env.name),
+                None => "Used for this request only".to_string(),
+            })
+            .size(10)
+            .color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(2);
+        for (name, value) in &overrides {
+            let note = match env_vars.iter().rev().find(|(k, _)| k == name) {
+                Some((_, env_value)) if env_value == value => {
+                    text(format!("{}: same as the environment", name)).color(colors::TEXT_SECONDARY)
+                }
+                Some((_, env_value)) => {
+                    let shown = if env.is_some_and(|e| e.secrets.contains(name)) {
+                        SECRET_MASK.to_string()
+                    } else {
+                        truncate_str(env_value, 40)
+                    };
+                    text(format!("{}: overrides {}", name, shown)).color(colors::WARNING)
+                }
+                None => text(format!("{}: not in the environment", name)).color(colors::TEXT_SECONDARY),
+            };
+            notes = notes.push(note.size(10));
+        }
+
+        let mut captures = column![
+            text(match env {
+                Some(env) => format!("Capture into {}: var=Header-Name or var=$.json.path", env.name),
+                None => "Capture: var=Header-Name or var=$.json.path (needs an active environment)".to_string(),
+            })
+            .size(10)
+            .color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(2);
+        if self.captures_discarded {
+            captures = captures.push(
+                text("Not stored: the last send was incognito or off record").size(10).color(colors::WARNING),
+            );
+        }
+        for (var, header, value) in &self.captured_headers {
+            let note = match value {
+                Some(_) if env.is_some_and(|e| e.secrets.contains(var)) => {
+                    text(format!("{} = {}", var, SECRET_MASK)).color(colors::SUCCESS)
+                }
+                Some(value) => text(format!("{} = {}", var, truncate_str(value, 60))).color(colors::SUCCESS),
+                None if header.starts_with('$') => {
+                    text(format!("{}: nothing at {} in the last response", var, header)).color(colors::WARNING)
+                }
+                None => text(format!("{}: no {} header in the last response", var, header)).color(colors::WARNING),
+            };
+            captures = captures.push(note.size(10));
+        }
+
+        let editor_style = |_: &iced::Theme, _| text_editor::Style {
+            background: colors::BG_PANEL.into(),
+            border: iced::Border::default(),
+            placeholder: colors::TEXT_SECONDARY,
+            value: colors::TEXT_PRIMARY,
+            selection: colors::ACCENT_PURPLE,
+        };
+
+        column![
+            container(notes).padding([6, 12]),
+            text_editor(&self.request_variables)
+                .placeholder("key=value\ntoken=staging-token")
+                .on_action(Message::RequestVariablesChanged)
+                .padding(12)
+                .height(Fill)
+                .style(editor_style),
+            container(captures).padding([6, 12]),
+            text_editor(&self.header_captures)
+                .placeholder("next_url=Location\ntoken=$.data.access_token")
+                .on_action(Message::HeaderCapturesChanged)
+                .padding(12)
+                .height(Length::Fixed(90.0))
+                .style(editor_style),
+        ]
+        .into()
+    }
+
+    fn view_request_panel(&self) -> Element<'_, Message> {
+        let body_active = self.request_tab == Tab::Body;
+        let headers_active = self.request_tab == Tab::Headers;
+        let params_active = self.request_tab == Tab::Params;
+        let auth_active = self.request_tab == Tab::Auth;
+        let body_ignored = !self.body_sent();
+
+        let body_tab = button(text("Body").size(11))
+            .padding([10, 16])
+            .style(move |_, _| {
+                let (bg, mut txt, border) = if body_active {
+                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
+                } else {
+                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
+                };
+                // Dimmed while the method drops the body
+                if body_ignored {
+                    txt = txt.scale_alpha(0.4);
+                }
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: txt,
+                    border: iced::Border { color: border, width: if body_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::RequestTabSelected(Tab::Body));
+
+        let headers_tab = button(text("Headers").size(11))
+            .padding([10, 16])
+            .style(move |_, _| {
+                let (bg, txt, border) = if headers_active {
+                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
+                } else {
+                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: txt,
+                    border: iced::Border { color: border, width: if headers_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::RequestTabSelected(Tab::Headers));
+
+        let params_tab = button(text("Params").size(11))
+            .padding([10, 16])
+            .style(move |_, _| {
+                let (bg, txt, border) = if params_active {
+                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
+                } else {
+                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: txt,
+                    border: iced::Border { color: border, width: if params_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::RequestTabSelected(Tab::Params));
+
+        let auth_tab = button(text("Auth").size(11))
+            .padding([10, 16])
+            .style(move |_, _| {
+                let (bg, txt, border) = if auth_active {
+                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_PURPLE)
+                } else {
+                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: txt,
+                    border: iced::Border { color: border, width: if auth_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
+                    ..Default::default()
+                }
+            })
+            .on_press(Message::RequestTabSelected(Tab::Auth));
+
+        let vars_tab = tab_button("Vars", self.request_tab == Tab::Vars, colors::ACCENT_PURPLE)
+            .on_press(Message::RequestTabSelected(Tab::Vars));
+
+        let tabs = row![body_tab, headers_tab, params_tab, auth_tab, vars_tab].spacing(0);
+
+        let content: Element<Message> = match self.request_tab {
+            Tab::Body | Tab::Tree | Tab::Flat | Tab::Cookies | Tab::Timing | Tab::Schema | Tab::Wire | Tab::Compare => {
+                self.view_body_editor()
+            }
+            Tab::Headers => {
+                let presets = pick_list(HEADER_PRESETS, None::<&str>, Message::HeaderPresetSelected)
+                    .placeholder("+ Header")
+                    .text_size(10)
+                    .padding([4, 8])
+                    .style(|_, _| pick_list::Style {
+                        text_color: colors::TEXT_PRIMARY,
+                        placeholder_color: colors::TEXT_SECONDARY,
+                        handle_color: colors::TEXT_SECONDARY,
+                        background: colors::BG_ELEVATED.into(),
+                        border: iced::Border {
+                            color: colors::BORDER,
+                            width: 1.0,
+                            radius: 0.0.into(),
+                        },
+                    });
+
+                let headers_text = self.request_headers.text();
+                let quick_headers = QUICK_HEADERS.iter().map(|&(name, placeholder)| {
+                    column![
+                        text(name).size(9).color(colors::TEXT_SECONDARY),
+                        text_input(placeholder, header_value(&headers_text, name).unwrap_or(""))
+                            .on_input(move |value| Message::QuickHeaderChanged(name, value))
+                            .padding(4)
+                            .size(10)
+                            .style(input_style),
+                    ]
+                    .spacing(2)
+                    .width(Fill)
+                    .into()
+                });
+                let quick_headers = iced::widget::Row::from_iter(quick_headers).spacing(8);
+
+                let toggle_btn = iced::widget::tooltip(
+                    button(text("# ON/OFF").size(9))
+                        .padding([4, 8])
+                        .style(secondary_button_style)
+                        .on_press(Message::ToggleHeaderLines),
+                    tooltip_box("Comment out the selected header lines so they aren't sent, or bring them back"),
+                    iced::widget::tooltip::Position::Bottom,
+                );
+
+                let case_btn = iced::widget::tooltip(
+                    button(text("Aa").size(9))
+                        .padding([4, 8])
+                        .style(secondary_button_style)
+                        .on_press(Message::CanonicalizeHeaders),
+                    tooltip_box("Write header names in canonical casing, e.g. Content-Type; values are kept"),
+                    iced::widget::tooltip::Position::Bottom,
+                );
+
+                column![
+                    container(row![presets, toggle_btn, case_btn].spacing(8).align_y(iced::Alignment::Center))
+                        .padding([6, 12]),
+                    container(quick_headers).padding([0, 12]),
+                ]
+                .push(self.view_variable_chips(CompletionTarget::Headers))
+                .push(view_line_warnings(parse_header_lines(&headers_text).1))
+                .push(
+                    text_editor(&self.request_headers)
+                        .highlight_with::<BodyHighlighter>(BodySyntax::Headers, |kind, _theme| {
+                            iced::advanced::text::highlighter::Format {
+                                color: Some(kind.color().scale_alpha(0.6)),
+                                font: None,
+                            }
+                        })
+                        .placeholder("Content-Type: application/json\nAuthorization: Bearer token\n# X-Disabled: not sent")
+                        .on_action(Message::RequestHeadersChanged)
+                        .key_binding(self.completion_keys(CompletionTarget::Headers))
+                        .padding(12)
+                        .height(Fill)
+                        .style(|_, _| text_editor::Style {
+                            background: colors::BG_PANEL.into(),
+                            border: iced::Border::default(),
+                            placeholder: colors::TEXT_SECONDARY,
+                            value: colors::TEXT_PRIMARY,
+                            selection: colors::ACCENT_PURPLE,
+                        }),
+                )
+                .into()
+            }
+            Tab::Params => column![]
+                .push(self.view_variable_chips(CompletionTarget::Params))
+                .push(view_line_warnings(parse_param_lines(&self.query_params.text()).1))
+                .push(
+                    text_editor(&self.query_params)
+                        .placeholder("key=value\npage=1\nlimit=10")
+                        .on_action(Message::QueryParamsChanged)
+                        .key_binding(self.completion_keys(CompletionTarget::Params))
+                        .padding(12)
+                        .height(Fill)
+                        .style(|_, _| text_editor::Style {
+                            background: colors::BG_PANEL.into(),
+                            border: iced::Border::default(),
+                            placeholder: colors::TEXT_SECONDARY,
+                            value: colors::TEXT_PRIMARY,
+                            selection: colors::ACCENT_PURPLE,
+                        }),
+                )
+                .into(),
+            Tab::Auth => self.view_auth_panel(),
+            Tab::Vars => self.view_request_variables(),
+        };
+
+        let beautify_btn = button(text("BEAUTIFY").size(9))
+            .padding([4, 8])
+            .style(secondary_button_style)
+            .on_press(Message::Beautify);
+
+        let new_btn = button(text("NEW").size(9))
+            .padding([4, 8])
+            .style(secondary_button_style)
+            .on_press(Message::NewRequest);
+
+        let snippets_btn = button(text("SNIPPETS").size(9))
+            .padding([4, 8])
+            .style(secondary_button_style)
+            .on_press(Message::ToggleSnippets);
+
+        let batch_btn = iced::widget::tooltip(
+            button(text("BATCH").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press(Message::ToggleBatch),
+            tooltip_box("Send this request to each URL in a pasted list"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let cors_btn = iced::widget::tooltip(
+            button(text(if self.cors_pending.is_some() { "CHECKING…" } else { "CHECK CORS" }).size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press_maybe(self.cors_pending.is_none().then_some(Message::CheckCors)),
+            tooltip_box("Send the OPTIONS preflight a browser would send for this request"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let export_btn = iced::widget::tooltip(
+            button(text(".HTTP").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press(Message::ExportHttpFile),
+            tooltip_box("Save this request, and the response as comments, as a .http file"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let header = row![
+            text("REQUEST").size(10).color(colors::TEXT_SECONDARY),
+            row![new_btn, snippets_btn, batch_btn, cors_btn, export_btn, beautify_btn].spacing(4),
+        ]
+        .spacing(16);
+
+        let panel = column![
+            container(header).padding(Padding { top: 12.0, right: 16.0, bottom: 8.0, left: 16.0 }),
+            container(tabs).style(|_| container::Style {
+                background: Some(colors::BG_DARK.into()),
+                ..Default::default()
+            }),
+            container(content).padding(0).height(Fill),
+        ].spacing(0);
+
+        container(panel)
+            .width(Fill)
+            .height(Fill)
+            .style(|_| container::Style {
+                background: Some(colors::BG_PANEL.into()),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    fn view_body_editor(&self) -> Element<'_, Message> {
+        let placeholder = match self.body_mode {
+            BodyMode::Text => "{\n  \"key\": \"value\"\n}",
+            BodyMode::Hex => "DE AD BE EF",
+            BodyMode::File => "/path/to/upload.bin",
+            BodyMode::Image => "",
+        };
+
+        let mode_picker = pick_list(BodyMode::ALL, Some(self.body_mode), Message::BodyModeSelected)
+            .text_size(10)
+            .padding([4, 8])
+            .style(|_, _| pick_list::Style {
+                text_color: colors::TEXT_PRIMARY,
+                placeholder_color: colors::TEXT_SECONDARY,
+                handle_color: colors::TEXT_SECONDARY,
+                background: colors::BG_ELEVATED.into(),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+            });
+
+        // Read back from the header every time, so hand edits show up here too
+        let headers = self.request_headers.text();
+        let content_type = header_value(&headers, "content-type").map(str::trim).filter(|v| !v.is_empty());
+        let content_type_picker = pick_list(
+            ContentTypeChoice::all(),
+            content_type.map(ContentTypeChoice::from_header),
+            Message::ContentTypeSelected,
+        )
+        .placeholder("Content-Type")
+        .text_size(10)
+        .padding([4, 8])
+        .style(pick_list_style);
+
+        let mut toolbar = row![
+            text("MODE").size(10).color(colors::TEXT_SECONDARY),
+            mode_picker,
+            content_type_picker,
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        // Validate against the declared type, after variables are filled in
+        let body = self.request_body.text();
+        if self.body_mode == BodyMode::Text
+            && content_type.is_some_and(|ct| ct.to_ascii_lowercase().contains("json"))
+            && !body.trim().is_empty()
+            && let Err(e) = serde_json::from_str::<serde_json::Value>(&substitute_variables(&body, &self.variables()))
+        {
+            toolbar = toolbar.push(text(format!("Invalid JSON: {}", e)).size(10).color(colors::ERROR));
+        }
+
+        let previewing = self.show_body_preview && self.body_mode == BodyMode::Text;
+        if self.body_mode == BodyMode::Text {
+            toolbar = toolbar.push(
+                iced::widget::checkbox(self.show_body_preview)
+                    .label("Preview")
+                    .text_size(10)
+                    .on_toggle(Message::BodyPreviewToggled),
+            );
+            toolbar = toolbar.push(
+                iced::widget::checkbox(self.settings.beautify_on_paste)
+                    .label("Beautify paste")
+                    .text_size(10)
+                    .on_toggle(Message::BeautifyOnPasteToggled),
+            );
+            toolbar = toolbar.push(iced::widget::tooltip(
+                iced::widget::checkbox(self.canonical_json_body)
+                    .label("Canonical JSON")
+                    .text_size(10)
+                    .on_toggle(Message::CanonicalJsonToggled),
+                tooltip_box("Send with sorted keys and no whitespace, e.g. for signing"),
+                iced::widget::tooltip::Position::Bottom,
+            ));
+        }
+
+        if !self.method.has_body() {
+            toolbar = toolbar.push(
+                iced::widget::checkbox(self.body_any_method)
+                    .label(format!("Send body with {}", self.method))
+                    .text_size(10)
+                    .on_toggle(Message::BodyAnyMethodToggled),
+            );
+            if !self.body_sent() {
+                // Louder once there's something that will be dropped
+                let color = if self.request_body.text().trim().is_empty() {
+                    colors::TEXT_SECONDARY
+                } else {
+                    colors::WARNING
+                };
+                toolbar = toolbar.push(text(format!("Body not sent for {}", self.method)).size(10).color(color));
+            }
+        }
+
+        toolbar = toolbar.push(
+            iced::widget::checkbox(self.chunked_body)
+                .label("Chunked")
+                .text_size(10)
+                .on_toggle(Message::ChunkedBodyToggled),
+        );
+        toolbar = toolbar.push(
+            iced::widget::checkbox(self.gzip_body)
+                .label("Gzip")
+                .text_size(10)
+                .on_toggle(Message::GzipBodyToggled),
+        );
+
+        if let Some((ref path, size)) = self.body_file {
+            toolbar = toolbar.push(match size {
+                Some(size) => text(format!(
+                    "{}, streamed from disk as {} unless overridden",
+                    format_size(size as usize),
+                    file_content_type(path),
+                ))
+                .size(10)
+                .color(colors::TEXT_SECONDARY),
+                None if path.is_empty() => text("Enter a file path").size(10).color(colors::TEXT_SECONDARY),
+                None => text("File not found").size(10).color(colors::ERROR),
+            });
+        } else {
+            // Editor bodies are held in memory (and re-rendered) as a whole
+            let len = self.request_body.text().len();
+            if len > LARGE_INLINE_BODY {
+                toolbar = toolbar.push(
+                    text(format!("Large body ({}); File mode streams it from disk instead", format_size(len)))
+                        .size(10)
+                        .color(colors::WARNING),
+                );
+            }
+        }
+
+        if self.body_mode == BodyMode::Image {
+            toolbar = toolbar.push(
+                button(text("PASTE IMAGE").size(9))
+                    .padding([4, 8])
+                    .style(secondary_button_style)
+                    .on_press(Message::PasteImage),
+            );
+            let content: Element<Message> = match self.pasted_image {
+                Some(ref image) => column![
+                    text(format!(
+                        "{}×{}, {} PNG, sent as image/png unless overridden",
+                        image.width,
+                        image.height,
+                        format_size(image.png.len()),
+                    ))
+                    .size(10)
+                    .color(colors::TEXT_SECONDARY),
+                    iced::widget::image(image.thumbnail.clone())
+                        .width(Length::Fixed(image.width.min(320) as f32))
+                        .content_fit(iced::ContentFit::ScaleDown),
+                ]
+                .spacing(8)
+                .padding(12)
+                .into(),
+                None => container(
+                    text("Copy an image, then PASTE IMAGE to send it as the body")
+                        .size(11)
+                        .color(colors::TEXT_SECONDARY),
+                )
+                .padding(12)
+                .into(),
+            };
+            return column![container(toolbar).padding([6, 12]), scrollable(content).height(Fill)].into();
+        }
+
+        if self.body_mode == BodyMode::Hex {
+            toolbar = toolbar.push(match parse_hex(&self.request_body.text()) {
+                Ok(bytes) => text(format!("{} bytes, sent as application/octet-stream unless overridden", bytes.len()))
+                    .size(10)
+                    .color(colors::TEXT_SECONDARY),
+                Err(e) => text(e).size(10).color(colors::ERROR),
+            });
+        }
+
+        if previewing {
+            // Resolve exactly as Send does
+            let resolved = match self.resolved_text_body() {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    toolbar = toolbar.push(text(e).size(10).color(colors::ERROR));
+                    substitute_variables(&self.request_body.text(), &self.variables())
+                }
+            };
+            let sent_len = if self.gzip_body && !resolved.is_empty() {
+                gzip(resolved.as_bytes()).map_or(resolved.len(), |compressed| compressed.len())
+            } else {
+                resolved.len()
+            };
+            let mut framing = if self.chunked_body {
+                "Transfer-Encoding: chunked".to_string()
+            } else {
+                format!("Content-Length: {}", sent_len)
+            };
+            if self.gzip_body && !resolved.is_empty() {
+                framing.push_str(&format!(
+                    ", Content-Encoding: gzip ({} → {})",
+                    format_size(resolved.len()),
+                    format_size(sent_len),
+                ));
+            }
+            toolbar = toolbar.push(text(framing).size(10).color(colors::TEXT_SECONDARY));
+            let unresolved = unresolved_variables(&resolved);
+            if !unresolved.is_empty() {
+                toolbar = toolbar.push(
+                    text(format!("Unresolved: {}", unresolved.join(", ")))
+                        .size(10)
+                        .color(colors::WARNING),
+                );
+            }
+            let preview = scrollable(
+                container(rich_text(json_to_spans(&self.masked(&resolved), false, None)).size(11))
+                    .padding(12)
+                    .width(Fill),
+            )
+            .height(Fill);
+            return column![container(toolbar).padding([6, 12]), preview].into();
+        }
+
+        let editor = text_editor(&self.request_body)
+            .highlight_with::<BodyHighlighter>(self.body_syntax(), |kind, _theme| {
+                iced::advanced::text::highlighter::Format {
+                    color: Some(kind.color()),
+                    font: None,
+                }
+            })
+            .placeholder(placeholder)
+            .on_action(Message::RequestBodyChanged)
+            .key_binding(self.completion_keys(CompletionTarget::Body))
+            .padding(12)
+            .height(Fill)
+            .style(|_, _| text_editor::Style {
+                background: colors::BG_PANEL.into(),
+                border: iced::Border::default(),
+                placeholder: colors::TEXT_SECONDARY,
+                value: colors::TEXT_PRIMARY,
+                selection: colors::ACCENT_PURPLE,
+            });
+
+        let mut content = column![];
+        if self.show_find_replace {
+            content = content.push(self.view_find_replace());
+        }
+        let body = self.request_body.text();
+        content
+            .push(container(toolbar).padding([6, 12]))
+            .push(self.view_variable_chips(CompletionTarget::Body))
+            .push(editor)
+            .push(stats_footer(text_stats(&body, body.len())))
+            .into()
+    }
+
+    /// Editor key bindings that, while `target` has completions open, walk
+    /// them with the arrows and accept with Enter or Tab
+    fn completion_keys(
+        &self,
+        target: CompletionTarget,
+    ) -> impl Fn(text_editor::KeyPress) -> Option<text_editor::Binding<Message>> + 'static {
+        let completing = self.variable_completion.as_ref().is_some_and(|c| c.target == target);
+        move |key_press| {
+            if completing && !key_press.modifiers.command() {
+                match key_press.key {
+                    Key::Named(key::Named::ArrowDown) => {
+                        return Some(text_editor::Binding::Custom(Message::UrlSuggestionMoved(1)));
+                    }
+                    Key::Named(key::Named::ArrowUp) => {
+                        return Some(text_editor::Binding::Custom(Message::UrlSuggestionMoved(-1)));
+                    }
+                    Key::Named(key::Named::Enter | key::Named::Tab) => {
+                        return Some(text_editor::Binding::Custom(Message::AcceptVariableCompletion));
+                    }
+                    _ => {}
+                }
+            }
+            text_editor::Binding::from_key_press(key_press)
+        }
+    }
+
+    fn view_find_replace(&self) -> Element<'_, Message> {
+        let matches = if self.find_query.is_empty() {
+            0
+        } else {
+            self.request_body.text().matches(&self.find_query).count()
+        };
+        let has_matches = matches > 0;
+
+        let find_input = text_input("Find", &self.find_query)
+            .id(FIND_INPUT_ID)
+            .on_input(Message::FindQueryChanged)
+            .on_submit(Message::FindNext)
+            .padding(6)
+            .size(11)
+            .width(Fill)
+            .style(input_style);
+
+        let replace_input = text_input("Replace with", &self.replace_text)
+            .on_input(Message::ReplaceTextChanged)
+            .on_submit(Message::ReplaceNext)
+            .padding(6)
+            .size(11)
+            .width(Fill)
+            .style(input_style);
+
+        let match_label = match matches {
+            _ if self.find_query.is_empty() => text("").size(10),
+            0 => text("No matches").size(10).color(colors::WARNING),
+            1 => text("1 match").size(10).color(colors::TEXT_SECONDARY),
+            n => text(format!("{} matches", n)).size(10).color(colors::TEXT_SECONDARY),
+        };
+
+        let small_button = |label: &'static str, message: Message, enabled: bool| {
+            button(text(label).size(9))
+                .padding([6, 8])
+                .style(secondary_button_style)
+                .on_press_maybe(enabled.then_some(message))
+        };
+
+        container(
+            column![
+                row![
+                    find_input,
+                    container(match_label).width(Length::Fixed(80.0)),
+                    small_button("NEXT", Message::FindNext, has_matches),
+                    small_button("CLOSE", Message::ToggleFindReplace, true),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center),
+                row![
+                    replace_input,
+                    small_button("REPLACE", Message::ReplaceNext, has_matches),
+                    small_button("ALL", Message::ReplaceAll, has_matches),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center),
+            ]
+            .spacing(6)
+        )
+        .padding(8)
+        .width(Fill)
+        .style(|_| container::Style {
+            background: Some(colors::BG_DARK.into()),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn view_auth_panel(&self) -> Element<'_, Message> {
+        let auth_picker = pick_list(AuthType::ALL, Some(self.auth_type), Message::AuthTypeSelected)
+            .text_size(12)
+            .padding(10)
+            .width(200)
+            .style(|_, _| pick_list::Style {
+                text_color: colors::TEXT_PRIMARY,
+                placeholder_color: colors::TEXT_SECONDARY,
+                handle_color: colors::TEXT_SECONDARY,
+                background: colors::BG_ELEVATED.into(),
+                border: iced::Border {
+                    color: colors::BORDER,
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+            });
+
+        let auth_fields: Element<Message> = match self.auth_type {
+            AuthType::None => {
+                container(
+                    text("No authentication will be sent with the request")
+                        .size(11)
+                        .color(colors::TEXT_SECONDARY)
+                )
+                .padding(16)
+                .into()
+            }
+            AuthType::Bearer => {
+                let token_input = text_input("Enter token", &self.auth_token)
+                    .on_input(Message::AuthTokenChanged)
+                    .padding(10)
+                    .size(12)
+                    .width(Fill)
+                    .style(|_, _| text_input::Style {
+                        background: colors::BG_ELEVATED.into(),
+                        border: iced::Border {
+                            color: colors::BORDER,
+                            width: 1.0,
+                            radius: 0.0.into(),
+                        },
+                        icon: colors::TEXT_SECONDARY,
+                        placeholder: colors::TEXT_SECONDARY,
+                        value: colors::TEXT_PRIMARY,
+                        selection: colors::ACCENT_PURPLE,
+                    });
+
+                let token = substitute_variables(self.auth_token.trim(), &self.variables());
+                let inspect_btn = button(text("INSPECT JWT").size(9))
+                    .padding([4, 8])
+                    .style(secondary_button_style)
+                    .on_press_maybe(looks_like_jwt(&token).then(|| Message::InspectJwt(token.clone())));
+
+                column![
+                    text("Token").size(11).color(colors::TEXT_SECONDARY),
+                    row![token_input, inspect_btn].spacing(8).align_y(iced::Alignment::Center),
+                    text("Will send: Authorization: Bearer <token>")
+                        .size(10)
+                        .color(colors::TEXT_SECONDARY),
+                ]
+                .spacing(8)
+                .padding(16)
+                .into()
+            }
+            AuthType::Basic | AuthType::Ntlm => {
+                let ntlm = self.auth_type == AuthType::Ntlm;
+                let username_input = text_input("Username", &self.auth_username)
+                    .on_input(Message::AuthUsernameChanged)
+                    .padding(10)
+                    .size(12)
+                    .width(Fill)
+                    .style(|_, _| text_input::Style {
+                        background: colors::BG_ELEVATED.into(),
+                        border: iced::Border {
+                            color: colors::BORDER,
+                            width: 1.0,
+                            radius: 0.0.into(),
+                        },
+                        icon: colors::TEXT_SECONDARY,
+                        placeholder: colors::TEXT_SECONDARY,
+                        value: colors::TEXT_PRIMARY,
+                        selection: colors::ACCENT_PURPLE,
+                    });
+
+                let password_input = text_input("Password", &self.auth_password)
+                    .on_input(Message::AuthPasswordChanged)
+                    .padding(10)
+                    .size(12)
+                    .width(Fill)
+                    .secure(true)
+                    .style(|_, _| text_input::Style {
+                        background: colors::BG_ELEVATED.into(),
+                        border: iced::Border {
+                            color: colors::BORDER,
+                            width: 1.0,
+                            radius: 0.0.into(),
+                        },
+                        icon: colors::TEXT_SECONDARY,
+                        placeholder: colors::TEXT_SECONDARY,
+                        value: colors::TEXT_PRIMARY,
+                        selection: colors::ACCENT_PURPLE,
+                    });
+
+                let mut fields = column![
+                    column![
+                        text("Username").size(11).color(colors::TEXT_SECONDARY),
+                        username_input,
+                    ].spacing(4),
+                    column![
+                        text("Password").size(11).color(colors::TEXT_SECONDARY),
+                        password_input,
+                    ].spacing(4),
+                ]
+                .spacing(12)
+                .padding(16);
+                if ntlm {
+                    let domain_input = text_input("Blank uses DOMAIN\\user from the username", &self.auth_domain)
+                        .on_input(Message::AuthDomainChanged)
+                        .padding(10)
+                        .size(12)
+                        .width(Fill)
+                        .style(input_style);
+                    fields = fields.push(
+                        column![
+                            text("Domain").size(11).color(colors::TEXT_SECONDARY),
+                            domain_input,
+                        ].spacing(4),
+                    );
+                }
+                fields
+                    .push(
+                        text(if ntlm {
+                            "Will send: a Negotiate/Challenge/Authenticate exchange over one connection"
+                        } else {
+                            "Will send: Authorization: Basic <base64>"
+                        })
+                        .size(10)
+                        .color(colors::TEXT_SECONDARY),
+                    )
+                    .into()
+            }
+        };
+
+        let content = column![
+            container(
+                column![
+                    text("AUTH TYPE").size(10).color(colors::TEXT_SECONDARY),
+                    auth_picker,
+                ]
+                .spacing(8)
+            )
+            .padding(16),
+            auth_fields,
+        ]
+        .spacing(0);
+
+        scrollable(content).height(Fill).into()
+    }
+
+    fn view_response_panel(&self) -> Element<'_, Message> {
+        let tab = |label: &'static str, tab: Tab| {
+            tab_button(label, self.response_tab == tab, colors::ACCENT_CORAL)
+                .on_press(Message::ResponseTabSelected(tab))
+        };
+
+        let mut tabs = row![
+            tab("Body", Tab::Body),
+            tab("Tree", Tab::Tree),
+            tab("Flat", Tab::Flat),
+            tab("Headers", Tab::Headers),
+            tab("Cookies", Tab::Cookies),
+            tab("Timing", Tab::Timing),
+            tab("Schema", Tab::Schema),
+            tab("Wire", Tab::Wire),
+        ]
+        .spacing(0);
+        if self.pinned_response.is_some() {
+            tabs = tabs.push(tab("Compare", Tab::Compare));
+        }
+
+        let status_bar: Element<Message> = if let Some(ref response) = self.response {
+            let mut items = row![
+                iced::widget::tooltip(
+                    text(format!("{}", response.status))
+                        .size(11)
+                        .color(status_color(response.status)),
+                    tooltip_box(status_explanation(response.status)),
+                    iced::widget::tooltip::Position::Bottom,
+                ),
+                text(response.status_text.clone())
+                    .size(11)
+                    .color(colors::TEXT_SECONDARY),
+                text(format!("{}ms", response.duration.as_millis()))
+                    .size(10)
+                    .color(self.duration_color(response, colors::TEXT_SECONDARY)),
+                text(format_size(response.size))
+                    .size(10)
+                    .color(colors::TEXT_SECONDARY),
+            ]
+            .spacing(12);
+
+            if let Some(ref pagination) = self.pagination {
+                let summary = format!("{} pages, {} items", pagination.pages, pagination.items.len());
+                items = items.push(text(summary).size(10).color(colors::ACCENT_CORAL));
+                if let Some(ref e) = pagination.error {
+                    items = items.push(text(truncate_str(e, 40)).size(10).color(colors::ERROR));
+                }
+            }
+
+            if let Some(target) = response.redirect_target() {
+                items = items.push(iced::widget::tooltip(
+                    button(text(format!("→ {}", truncate_str(&target, 48))).size(10))
+                        .padding(0)
+                        .style(|_, status| button::Style {
+                            text_color: if matches!(status, button::Status::Hovered) {
+                                colors::TEXT_PRIMARY
+                            } else {
+                                colors::ACCENT_CORAL
+                            },
+                            ..Default::default()
+                        })
+                        .on_press_maybe((!self.loading).then(|| Message::FollowRedirect(target.clone()))),
+                    tooltip_box("Follow the redirect"),
+                    iced::widget::tooltip::Position::Bottom,
+                ));
+            }
+
+            // The server answered with an error status; surface its reason next to the status
+            if response.is_http_error()
+                && let Some(reason) = error_summary(&response.body)
+            {
+                items = items.push(
+                    text(truncate_str(&reason, 40))
+                        .size(10)
+                        .color(status_color(response.status)),
+                );
+            }
+
+            items.into()
+        } else if let Some(ref error) = self.error {
+            text(format!("Connection failed: {}", truncate_str(error, 40)))
+                .size(11)
+                .color(colors::ERROR)
+                .into()
+        } else {
+            text("Ready")
+                .size(11)
+                .color(colors::TEXT_SECONDARY)
+                .into()
+        };
+
+        let content: Element<Message> = if let Some(ref response) = self.response {
+            match self.response_tab {
+                Tab::Body | Tab::Params | Tab::Auth | Tab::Vars if !self.markdown_raw && response.is_markdown() => {
+                    column![
+                        scrollable(container(render_markdown(&response.body)).padding(12).width(Fill))
+                            .height(Fill),
+                        stats_footer(self.response_scan.stats.clone()),
+                    ]
+                    .into()
+                }
+                Tab::Body | Tab::Params | Tab::Auth | Tab::Vars if self.show_value_types || self.fold_long_strings => {
+                    // Type labels and folds are display-only, so this view isn't selectable
+                    let folded = self.fold_long_strings.then_some(&self.expanded_strings);
+                    let spans = json_to_spans(&self.response_body_text(response), self.show_value_types, folded);
+                    let mut body = column![];
+                    if let Some(ref problem) = self.response_scan.problem {
+                        body = body.push(view_problem(problem.clone()));
+                    }
+                    if self.show_envelope
+                        && let Some(ref envelope) = self.response_scan.envelope
+                    {
+                        body = body.push(view_envelope(envelope.clone()));
+                    }
+                    column![
+                        scrollable(
+                            body.push(
+                                container(rich_text(spans).size(11).on_link_click(SpanAction::message))
+                                    .padding(12)
+                                    .width(Fill),
+                            ),
+                        )
+                        .height(Fill),
+                        stats_footer(self.response_scan.stats.clone()),
+                    ]
+                    .into()
+                }
+                Tab::Body | Tab::Params | Tab::Auth | Tab::Vars => {
+                    let mut body = column![];
+                    if let Some(ref problem) = self.response_scan.problem {
+                        body = body.push(view_problem(problem.clone()));
+                    }
+                    if self.show_envelope
+                        && let Some(ref envelope) = self.response_scan.envelope
+                    {
+                        body = body.push(view_envelope(envelope.clone()));
+                    }
+                    body = body.push(
+                        response_viewer(&self.response_body_view, response_syntax(response))
+                            .on_action(Message::ResponseBodyViewAction),
+                    );
+                    let total = self.response_body_formatted.len();
+                    if self.response_body_shown < total {
+                        body = body.push(
+                            container(
+                                row![
+                                    text(format!(
+                                        "Showing {} of {}; copy and save use the whole body",
+                                        format_size(self.response_body_shown),
+                                        format_size(total),
+                                    ))
+                                    .size(10)
+                                    .color(colors::TEXT_SECONDARY)
+                                    .width(Fill),
+                                    button(text("SHOW MORE").size(9))
+                                        .padding([4, 8])
+                                        .style(secondary_button_style)
+                                        .on_press(Message::ShowMoreBody),
+                                    button(text("SHOW ALL").size(9))
+                                        .padding([4, 8])
+                                        .style(secondary_button_style)
+                                        .on_press(Message::ShowWholeBody),
+                                ]
+                                .spacing(4)
+                                .align_y(iced::Alignment::Center),
+                            )
+                            .padding([4, 12]),
+                        );
+                    }
+                    let goto = text_input("Go to line[:col] or @offset", &self.body_goto)
+                        .size(10)
+                        .padding([3, 6])
+                        .width(180)
+                        .on_input(Message::BodyGotoChanged)
+                        .on_submit(Message::GoToBodyPosition);
+                    body.push(
+                        row![
+                            container(goto).padding(Padding { top: 2.0, right: 0.0, bottom: 2.0, left: 12.0 }),
+                            stats_footer(self.response_scan.stats.clone()),
+                        ]
+                        .align_y(iced::Alignment::Center),
+                    )
+                    .into()
+                }
+                Tab::Headers => {
+                    let framing = container(text(response.framing()).size(11).color(colors::ACCENT_CORAL))
+                        .padding([8, 12])
+                        .width(Fill)
+                        .style(|_| container::Style {
+                            background: Some(colors::BG_ELEVATED.into()),
+                            ..Default::default()
+                        });
+                    column![
+                        framing,
+                        response_viewer(&self.response_headers_view, BodySyntax::Plain)
+                            .on_action(Message::ResponseHeadersViewAction),
+                    ]
+                    .into()
+                }
+                Tab::Timing => {
+                    self.view_timing_details(response)
+                }
+                Tab::Cookies => view_cookies(response),
+                Tab::Tree => self.view_json_tree(response),
+                Tab::Flat => match self.response_flat_view {
+                    Some(ref view) => response_viewer(view, BodySyntax::Plain)
+                        .on_action(Message::ResponseFlatViewAction)
+                        .into(),
+                    None => container(text("Response body is not JSON").size(11).color(colors::TEXT_SECONDARY))
+                        .padding(12)
+                        .into(),
+                },
+                Tab::Compare => match self.pinned_response {
+                    Some(ref pinned) => self.view_compare(pinned, response),
+                    None => text("Pin a response to compare against").size(11).color(colors::TEXT_SECONDARY).into(),
+                },
+                Tab::Wire => {
+                    let reveal = button(text(if self.reveal_credentials { "HIDE CREDENTIALS" } else { "REVEAL CREDENTIALS" }).size(9))
+                        .padding([4, 8])
+                        .style(secondary_button_style)
+                        .on_press(Message::ToggleRevealCredentials);
+                    column![
+                        container(reveal).padding(Padding { top: 8.0, right: 12.0, bottom: 0.0, left: 12.0 }),
+                        scrollable(
+                            container(text(self.wire_text(response)).size(11).color(colors::TEXT_PRIMARY))
+                                .padding(12)
+                                .width(Fill),
+                        )
+                        .height(Fill),
+                    ]
+                    .into()
+                }
+                Tab::Schema => {
+                    let parsed = serde_json::from_str::<serde_json::Value>(&response.body);
+                    let is_json = parsed.is_ok();
+                    let spans: Vec<iced::widget::text::Span<'_, iced::Font>> = match parsed {
+                        Ok(value) => render_schema(&infer_schema(&value))
+                            .into_iter()
+                            .map(|(s, color)| span(s).color(color))
+                            .collect(),
+                        Err(_) => vec![span("Response body is not JSON").color(colors::TEXT_SECONDARY)],
+                    };
+                    let rust_btn = iced::widget::tooltip(
+                        button(text("COPY RUST TYPES").size(9))
+                            .padding([4, 8])
+                            .style(secondary_button_style)
+                            .on_press_maybe(is_json.then_some(Message::CopyRustTypes)),
+                        tooltip_box("serde structs for this response"),
+                        iced::widget::tooltip::Position::Bottom,
+                    );
+                    column![
+                        container(rust_btn).padding(Padding { top: 8.0, right: 12.0, bottom: 0.0, left: 12.0 }),
+                        scrollable(
+                            container(rich_text(spans).size(11))
+                                .padding(12)
+                                .width(Fill),
+                        )
+                        .height(Fill),
+                    ]
+                    .into()
+                }
+            }
+        } else {
+            container(
+                text("Send a request to see the response")
+                    .size(12)
+                    .color(colors::TEXT_SECONDARY),
+            )
+            .padding(16)
+            .center_x(Fill)
+            .center_y(Fill)
+            .into()
+        };
+
+        let copy_btn = button(text("COPY").size(9))
+            .padding([4, 8])
+            .style(|_, status| {
+                let bg = match status {
+                    button::Status::Hovered => colors::BG_ELEVATED,
+                    _ => colors::BG_DARK,
+                };
+                button::Style {
+                    background: Some(bg.into()),
+                    text_color: colors::TEXT_SECONDARY,
+                    border: iced::Border {
+                        color: colors::BORDER,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .on_press_maybe(if self.response.is_some() { Some(Message::CopyResponse) } else { None });
+
+        // Only offered when the body is a JSON array of objects
+        let is_table = self.response.is_some() && self.response_scan.is_table;
+        let table_btn = |label: &'static str, delimiter: char| {
+            button(text(label).size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press_maybe(is_table.then_some(Message::CopyAsTable(delimiter)))
+        };
+
+        let summary_btn = button(text("SUMMARY").size(9))
+            .padding([4, 8])
+            .style(secondary_button_style)
+            .on_press_maybe(self.response.is_some().then_some(Message::CopySummary));
+
+        let pin_btn = if self.pinned_response.is_some() {
+            button(text("UNPIN").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press(Message::UnpinResponse)
+        } else {
+            button(text("PIN").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press_maybe(self.response.is_some().then_some(Message::PinResponse))
+        };
+
+        let reuse_btn = iced::widget::tooltip(
+            button(text("AS BODY").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press_maybe(self.response.is_some().then_some(Message::UseResponseAsBody)),
+            tooltip_box("Send this response as the request body"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let save_btn = iced::widget::tooltip(
+            button(text("SAVE").size(9))
+                .padding([4, 8])
+                .style(secondary_button_style)
+                .on_press_maybe(self.response.is_some().then_some(Message::SaveResponseBody)),
+            tooltip_box("Save the body to a file, named as the server suggests"),
+            iced::widget::tooltip::Position::Bottom,
+        );
+
+        let mut actions = row![
+            copy_btn,
+            save_btn,
+            summary_btn,
+            pin_btn,
+            reuse_btn,
+            table_btn("CSV", ','),
+            table_btn("TSV", '\t'),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center);
+        if let Some(ref token) = self.response_scan.jwt
+            && self.response.is_some()
+        {
+            actions = actions.push(iced::widget::tooltip(
+                button(text("JWT").size(9))
+                    .padding([4, 8])
+                    .style(secondary_button_style)
+                    .on_press(Message::InspectJwt(token.clone())),
+                tooltip_box("Decode the first token-shaped value in the body"),
+                iced::widget::tooltip::Position::Bottom,
+            ));
+        }
+        if matches!(self.response_tab, Tab::Body | Tab::Tree) {
+            actions = actions.push(
+                iced::widget::checkbox(self.fold_long_strings)
+                    .label("Fold strings")
+                    .text_size(10)
+                    .on_toggle(Message::FoldLongStringsToggled),
+            );
+        }
+        if matches!(self.response_tab, Tab::Body | Tab::Flat) {
+            actions = actions.push(
+                iced::widget::checkbox(self.sort_response_keys)
+                    .label("Sort keys")
+                    .text_size(10)
+                    .on_toggle(Message::SortResponseKeysToggled),
+            );
+        }
+        if self.response_tab == Tab::Body {
+            actions = actions.push(
+                iced::widget::checkbox(self.show_value_types)
+                    .label("Types")
+                    .text_size(10)
+                    .on_toggle(Message::ShowValueTypesToggled),
+            );
+            if self.response.is_some() && self.response_scan.envelope.is_some() {
+                actions = actions.push(
+                    iced::widget::checkbox(self.show_envelope)
+                        .label("Summary")
+                        .text_size(10)
+                        .on_toggle(Message::ShowEnvelopeToggled),
+                );
+            }
+            if self.response.as_ref().is_some_and(Response::is_markdown) {
+                actions = actions.push(
+                    iced::widget::checkbox(self.markdown_raw)
+                        .label("Raw")
+                        .text_size(10)
+                        .on_toggle(Message::MarkdownRawToggled),
+                );
+            }
+        }
+        if self.response.as_ref().is_some_and(Response::is_html) {
+            actions = actions.push(
+                button(text("OPEN IN BROWSER").size(9))
+                    .padding([4, 8])
+                    .style(secondary_button_style)
+                    .on_press(Message::OpenInBrowser),
+            );
+        }
+
+        let header = row![
+            text("RESPONSE").size(10).color(colors::TEXT_SECONDARY),
+            status_bar,
+            actions,
+        ].spacing(16);
+
+        let mut panel = column![
+            container(header).padding(Padding { top: 12.0, right: 16.0, bottom: 8.0, left: 16.0 }),
         ].spacing(0);
+        if self.response.is_some() {
+            panel = panel.push(self.view_resend_as());
+        }
+        if let Some(ref verdict) = self.cors_verdict {
+            panel = panel.push(view_cors_verdict(verdict));
+        }
+        let panel = panel
+            .push(container(tabs).style(|_| container::Style {
+                background: Some(colors::BG_DARK.into()),
+                ..Default::default()
+            }))
+            .push(container(content).padding(0).height(Fill));
+
+        container(panel)
+            .width(Fill)
+            .height(Fill)
+            .style(|_| container::Style {
+                background: Some(colors::BG_PANEL.into()),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// One button per other method, re-sending the current request with it
+    fn view_resend_as(&self) -> Element<'_, Message> {
+        let mut bar = row![text("RESEND AS").size(9).color(colors::TEXT_SECONDARY)]
+            .spacing(4)
+            .align_y(iced::Alignment::Center);
+        if let Some(method) = self.confirm_resend {
+            bar = bar
+                .push(text(format!("{} {}?", method, truncate_str(&self.url, 60))).size(10).color(colors::WARNING))
+                .push(
+                    button(text(method.to_string()).size(9).color(method.color()))
+                        .padding([2, 6])
+                        .style(secondary_button_style)
+                        .on_press_maybe((!self.loading).then_some(Message::ConfirmResend)),
+                )
+                .push(
+                    button(text("CANCEL").size(9))
+                        .padding([2, 6])
+                        .style(secondary_button_style)
+                        .on_press(Message::CancelResend),
+                );
+        } else {
+            for &method in Method::ALL.iter().filter(|&&m| m != self.method) {
+                bar = bar.push(
+                    button(text(method.to_string()).size(9).color(method.color()))
+                        .padding([2, 6])
+                        .style(secondary_button_style)
+                        .on_press_maybe((!self.loading).then_some(Message::ResendAs(method))),
+                );
+            }
+        }
+        container(bar).padding(Padding { top: 0.0, right: 16.0, bottom: 8.0, left: 16.0 }).into()
+    }
+
+    fn view_timing_details(&self, response: &Response) -> Element<'_, Message> {
+        let total_ms = response.duration.as_millis() as f32;
+        let bar_width = 300.0;
+
+        // Summary stats
+        let speed = if response.duration.as_secs_f64() > 0.0 {
+            (response.size as f64 / response.duration.as_secs_f64()) / 1024.0
+        } else {
+            0.0
+        };
+
+        let summary_items = column![
+            row![
+                text("Total Time").size(12).color(colors::TEXT_SECONDARY),
+                text(format!("{}ms", response.duration.as_millis()))
+                    .size(14)
+                    .color(self.duration_color(response, colors::ACCENT_CORAL)),
+            ].spacing(12),
+            row![
+                text("Response Size").size(12).color(colors::TEXT_SECONDARY),
+                text(format_size(response.size)).size(14).color(colors::SUCCESS),
+            ].spacing(12),
+            row![
+                text("Transfer Speed").size(12).color(colors::TEXT_SECONDARY),
+                text(format!("{:.1} KB/s", speed)).size(14).color(colors::ACCENT_PURPLE),
+            ].spacing(12),
+        ]
+        .spacing(8);
+
+        // Visual breakdown bar
+        let timing_note = text("Breakdown (total request time)")
+            .size(10)
+            .color(colors::TEXT_SECONDARY);
+
+        let bar = container(column![])
+            .width(Length::Fixed(bar_width))
+            .height(16)
+            .style(|_| container::Style {
+                background: Some(colors::ACCENT_CORAL.into()),
+                ..Default::default()
+            });
+
+        let bar_bg = container(bar)
+            .width(Length::Fixed(bar_width))
+            .style(|_| container::Style {
+                background: Some(colors::BG_DARK.into()),
+                ..Default::default()
+            });
+
+        let timing_bar_row = row![
+            container(text("Total").size(11).color(colors::TEXT_SECONDARY))
+                .width(Length::Fixed(120.0)),
+            bar_bg,
+            container(text(format!("{:.0}ms", total_ms)).size(11).color(colors::TEXT_PRIMARY))
+                .width(Length::Fixed(80.0))
+                .padding(Padding { top: 0.0, right: 0.0, bottom: 0.0, left: 12.0 }),
+        ]
+        .spacing(8);
+
+        let samples = self.latency_history.get(&latency_key(&response.url));
+        let trend: Element<Message> = match samples {
+            Some(samples) if samples.len() > 1 => column![
+                text(format!("Last {} requests to this URL", samples.len()))
+                    .size(10)
+                    .color(colors::TEXT_SECONDARY),
+                iced::widget::canvas(Sparkline::new(samples))
+                    .width(Length::Fixed(bar_width))
+                    .height(48),
+            ]
+            .spacing(12)
+            .into(),
+            _ => text("Send again to see a latency trend for this URL")
+                .size(10)
+                .color(colors::TEXT_SECONDARY)
+                .into(),
+        };
+
+        let content = column![
+            container(
+                column![
+                    text("TIMING SUMMARY").size(10).color(colors::TEXT_SECONDARY),
+                    summary_items,
+                ]
+                .spacing(12)
+            )
+            .padding(16)
+            .width(Fill)
+            .style(|_| container::Style {
+                background: Some(colors::BG_ELEVATED.into()),
+                ..Default::default()
+            }),
+            container(
+                column![
+                    timing_note,
+                    timing_bar_row,
+                ]
+                .spacing(12)
+            )
+            .padding(16)
+            .width(Fill),
+            container(trend).padding(16).width(Fill),
+        ]
+        .spacing(16);
+
+        scrollable(content).height(Fill).into()
+    }
+}
+
+fn truncate_str(s: &str, max: usize) -> String {
+    if s.chars().count() > max {
+        let kept: String = s.chars().take(max.saturating_sub(3)).collect();
+        format!("{}...", kept)
+    } else {
+        s.to_string()
+    }
+}
+
+const FIND_INPUT_ID: &str = "find-input";
+const URL_INPUT_ID: &str = "url-input";
+const ENV_SWITCHER_INPUT_ID: &str = "env-switcher-input";
+const PALETTE_INPUT_ID: &str = "palette-input";
+
+/// Key pressed together with Cmd/Ctrl to trigger a shortcut
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ShortcutKey {
+    Char(char),
+    Enter,
+    /// Handled by widgets or outside `command_shortcut`; listed for reference only
+    Other,
+}
+
+/// One row of the shortcut reference. `command_shortcut` dispatches from
+/// this same list, so what's shown is what's bound.
+struct Shortcut {
+    keys: &'static str,
+    action: &'static str,
+    key: ShortcutKey,
+    shift: bool,
+    message: Option<fn() -> Message>,
+}
+
+impl Shortcut {
+    const fn bound(keys: &'static str, action: &'static str, key: ShortcutKey, shift: bool, message: fn() -> Message) -> Self {
+        Self { keys, action, key, shift, message: Some(message) }
+    }
+
+    const fn listed(keys: &'static str, action: &'static str) -> Self {
+        Self { keys, action, key: ShortcutKey::Other, shift: false, message: None }
+    }
+}
+
+const SHORTCUTS: &[Shortcut] = &[
+    Shortcut::bound("Cmd+Enter", "Send and show the body", ShortcutKey::Enter, false, || Message::SendAndShow(Tab::Body)),
+    Shortcut::bound("Cmd+Shift+Enter", "Send and show the headers", ShortcutKey::Enter, true, || {
+        Message::SendAndShow(Tab::Headers)
+    }),
+    Shortcut::bound("Cmd+R", "Repeat the last request", ShortcutKey::Char('r'), false, || Message::RepeatLastRequest),
+    Shortcut::bound("Cmd+N", "New request", ShortcutKey::Char('n'), false, || Message::NewRequest),
+    Shortcut::bound("Cmd+Shift+F", "Beautify body, headers and params", ShortcutKey::Char('f'), true, || Message::Beautify),
+    Shortcut::bound("Cmd+H", "Find and replace in the body", ShortcutKey::Char('h'), false, || Message::ToggleFindReplace),
+    Shortcut::bound("Cmd+K", "Command palette", ShortcutKey::Char('k'), false, || Message::TogglePalette),
+    Shortcut::bound("Cmd+E", "Switch environment", ShortcutKey::Char('e'), false, || Message::OpenEnvSwitcher),
+    Shortcut::bound("Cmd+J", "Focus the request panel", ShortcutKey::Char('j'), false, || {
+        Message::FocusPanel(PanelFocus::Request)
+    }),
+    Shortcut::bound("Cmd+Shift+J", "Focus the response panel", ShortcutKey::Char('j'), true, || {
+        Message::FocusPanel(PanelFocus::Response)
+    }),
+    Shortcut::bound("Cmd+/ or ?", "This list", ShortcutKey::Char('/'), false, || Message::ToggleShortcuts),
+    Shortcut::listed("Tab / Shift+Tab", "Move focus between fields"),
+    Shortcut::listed("Up / Down", "Walk URL suggestions and picker rows"),
+    Shortcut::listed("Escape", "Close the topmost dialog"),
+];
+
+/// The message bound to Cmd/Ctrl + `key`, if any
+fn command_shortcut(key: Key<&str>, shift: bool) -> Option<Message> {
+    SHORTCUTS.iter()
+        .filter(|s| s.shift == shift)
+        .find(|s| match (s.key, &key) {
+            (ShortcutKey::Enter, Key::Named(key::Named::Enter)) => true,
+            // Shift can turn the character upper case
+            (ShortcutKey::Char(c), Key::Character(pressed)) => pressed.to_lowercase() == c.to_string(),
+            _ => false,
+        })
+        .and_then(|s| s.message)
+        .map(|message| message())
+}
+
+/// Three dot-separated base64url segments, the first a JSON object
+fn looks_like_jwt(token: &str) -> bool {
+    let token = token.trim();
+    let parts: Vec<&str> = token.split('.').collect();
+    let base64url = |p: &str| p.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'=');
+    parts.len() == 3
+        && token.starts_with("eyJ")
+        && !parts[1].is_empty()
+        && parts.iter().all(|p| base64url(p))
+}
+
+/// The first JWT-shaped token anywhere in `text`
+fn find_jwt(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '=')))
+        .find(|token| looks_like_jwt(token))
+        .map(str::to_string)
+}
+
+/// Header and claims of a JWT. The signature isn't checked.
+fn decode_jwt(token: &str) -> Result<(serde_json::Value, serde_json::Value), String> {
+    use base64::Engine;
+    let token = token.trim();
+    let token = token.strip_prefix("Bearer ").unwrap_or(token).trim();
+    let mut parts = token.split('.');
+    let mut segment = |name: &str| -> Result<serde_json::Value, String> {
+        let part = parts.next().ok_or_else(|| format!("No {} segment", name))?;
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(part.trim_end_matches('='))
+            .map_err(|e| format!("{} isn't base64url: {}", name, e))?;
+        serde_json::from_slice(&bytes).map_err(|e| format!("{} isn't JSON: {}", name, e))
+    };
+    Ok((segment("Header")?, segment("Payload")?))
+}
+
+/// A NumericDate claim as local time, relative to `now` (both Unix seconds)
+fn jwt_time(secs: i64, now: i64) -> String {
+    let when = chrono::DateTime::from_timestamp(secs, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| secs.to_string());
+    let delta = secs.abs_diff(now);
+    let span = match delta {
+        0..60 => format!("{}s", delta),
+        60..3600 => format!("{}m", delta / 60),
+        3600..86_400 => format!("{}h {}m", delta / 3600, delta % 3600 / 60),
+        _ => format!("{}d {}h", delta / 86_400, delta % 86_400 / 3600),
+    };
+    if secs >= now {
+        format!("{} (in {})", when, span)
+    } else {
+        format!("{} ({} ago)", when, span)
+    }
+}
+
+fn view_jwt(token: &str) -> Element<'_, Message> {
+    let close_btn = button(text("CLOSE").size(11))
+        .padding([10, 20])
+        .style(secondary_button_style)
+        .on_press(Message::CloseJwt);
+    let content = column![text("JWT").size(12).color(colors::TEXT_SECONDARY)]
+        .spacing(12)
+        .width(Length::Fixed(560.0));
+
+    let (header, payload) = match decode_jwt(token) {
+        Ok(decoded) => decoded,
+        Err(e) => return content.push(text(e).size(11).color(colors::ERROR)).push(close_btn).into(),
+    };
+    let now = chrono::Utc::now().timestamp();
+    let mut times = column![].spacing(4);
+    for (claim, label) in [("iat", "Issued"), ("nbf", "Not before"), ("exp", "Expires")] {
+        let Some(secs) = payload.get(claim).and_then(serde_json::Value::as_i64) else { continue };
+        let color = match claim {
+            "exp" if secs <= now => colors::ERROR,
+            "nbf" if secs > now => colors::WARNING,
+            _ => colors::TEXT_PRIMARY,
+        };
+        times = times.push(
+            row![
+                container(text(label).size(11).color(colors::TEXT_SECONDARY)).width(Length::Fixed(90.0)),
+                text(jwt_time(secs, now)).size(11).color(color),
+            ]
+            .spacing(8),
+        );
+    }
+    let exp = payload.get("exp").and_then(serde_json::Value::as_i64);
+    if exp.is_some_and(|exp| exp <= now) {
+        times = times.push(text("This token has expired").size(11).color(colors::ERROR));
+    }
+
+    let section = |title: &'static str, value: &serde_json::Value| {
+        let pretty = serde_json::to_string_pretty(value).unwrap_or_default();
+        column![
+            text(title).size(10).color(colors::TEXT_SECONDARY),
+            container(rich_text(json_to_spans(&pretty, false, None)).size(11).on_link_click(SpanAction::message))
+                .padding(8)
+                .width(Fill)
+                .style(|_| container::Style {
+                    background: Some(colors::BG_DARK.into()),
+                    ..Default::default()
+                }),
+        ]
+        .spacing(4)
+    };
+    let details = column![section("HEADER", &header), section("PAYLOAD", &payload), times].spacing(12);
+    content
+        .push(scrollable(details).height(Length::Shrink))
+        .push(text("Decoded only; the signature is not verified").size(10).color(colors::TEXT_SECONDARY))
+        .push(close_btn)
+        .into()
+}
+
+fn view_shortcuts<'a>() -> Element<'a, Message> {
+    let rows = SHORTCUTS.iter().fold(column![].spacing(6), |col, shortcut| {
+        col.push(
+            row![
+                container(text(shortcut.keys).size(11).color(colors::ACCENT_PURPLE)).width(Length::Fixed(140.0)),
+                text(shortcut.action).size(11).color(colors::TEXT_PRIMARY),
+            ]
+            .spacing(12),
+        )
+    });
+    column![
+        text("KEYBOARD SHORTCUTS").size(12).color(colors::TEXT_SECONDARY),
+        rows,
+        text("Ctrl replaces Cmd on Linux and Windows").size(10).color(colors::TEXT_SECONDARY),
+    ]
+    .spacing(12)
+    .width(Length::Fixed(440.0))
+    .into()
+}
+
+/// An action offered by the command palette
+struct PaletteCommand {
+    label: &'static str,
+    shortcut: &'static str,
+    message: Message,
+}
+
+/// Something other than a URL that commonly ends up pasted into the URL bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UrlPasteHint {
+    Curl,
+    Json,
+}
+
+fn url_paste_hint(url: &str) -> Option<UrlPasteHint> {
+    let trimmed = url.trim_start();
+    let first_word = trimmed.split_whitespace().next().unwrap_or("");
+    if first_word == "curl" {
+        return Some(UrlPasteHint::Curl);
+    }
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some(UrlPasteHint::Json);
+    }
+    None
+}
+
+/// Headers with their own inputs above the headers editor, with placeholders
+const QUICK_HEADERS: &[(&str, &str)] = &[
+    ("Accept", "application/json"),
+    ("Accept-Language", "en-US,en;q=0.9"),
+    ("Accept-Encoding", "gzip, deflate, br"),
+];
+
+/// Content types offered by the Body tab's picker
+const BODY_CONTENT_TYPES: &[&str] = &[
+    "application/json",
+    "application/x-www-form-urlencoded",
+    "text/plain",
+    "application/xml",
+    "multipart/form-data",
+];
+
+/// Entry in the Body tab's content-type picker. `Custom` stands for any other
+/// value, which is edited in the Headers tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentTypeChoice {
+    Preset(&'static str),
+    Custom,
+}
+
+impl ContentTypeChoice {
+    fn all() -> Vec<ContentTypeChoice> {
+        BODY_CONTENT_TYPES.iter()
+            .map(|&ct| ContentTypeChoice::Preset(ct))
+            .chain([ContentTypeChoice::Custom])
+            .collect()
+    }
+
+    /// The choice matching a Content-Type header value; parameters such as
+    /// `charset` are ignored
+    fn from_header(value: &str) -> ContentTypeChoice {
+        let media_type = value.split(';').next().unwrap_or("").trim();
+        BODY_CONTENT_TYPES.iter()
+            .find(|ct| ct.eq_ignore_ascii_case(media_type))
+            .map_or(ContentTypeChoice::Custom, |&ct| ContentTypeChoice::Preset(ct))
+    }
+}
+
+impl std::fmt::Display for ContentTypeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContentTypeChoice::Preset(ct) => write!(f, "{}", ct),
+            ContentTypeChoice::Custom => write!(f, "Custom..."),
+        }
+    }
+}
+
+/// Quick-insert templates for the Headers tab; `<...>` marks a value to fill in
+const HEADER_PRESETS: &[&str] = &[
+    "Content-Type: application/json",
+    "Content-Type: application/x-www-form-urlencoded",
+    "Content-Type: multipart/form-data",
+    "Content-Type: text/plain",
+    "Content-Type: application/xml",
+    "Accept: application/json",
+    "Accept: text/html",
+    "Accept: */*",
+    "Accept-Encoding: gzip, deflate, br",
+    "Accept-Language: en-US,en;q=0.9",
+    "Authorization: Bearer <token>",
+    "Cache-Control: no-cache",
+    "If-None-Match: <etag>",
+    "User-Agent: badgateway/0.1",
+    "X-Request-Id: <id>",
+];
+
+/// Byte offset of the first literal match of `query` at or after `from`, wrapping around
+fn find_from(haystack: &str, query: &str, from: usize) -> Option<usize> {
+    if query.is_empty() {
+        return None;
+    }
+    let from = from.min(haystack.len());
+    haystack.get(from..)
+        .and_then(|rest| rest.find(query))
+        .map(|i| from + i)
+        .or_else(|| haystack.find(query))
+}
+
+/// Byte offset in `text` of an editor position (line, byte column)
+fn position_to_offset(text: &str, position: text_editor::Position) -> usize {
+    let mut offset = 0;
+    for (i, line) in text.split('\n').enumerate() {
+        if i == position.line {
+            return offset + position.column.min(line.len());
+        }
+        offset += line.len() + 1;
+    }
+    text.len()
+}
+
+/// The byte range to select for a go-to target: `12` or `12:5` (1-based,
+/// column in characters) selects that line, `@1234` the character at that
+/// byte offset. Positions count the body as shown, which is the body as
+/// received whenever it isn't valid JSON.
+fn body_goto_target(body: &str, input: &str) -> Result<(usize, usize), String> {
+    let char_end = |start: usize| body[start..].chars().next().map_or(start, |c| start + c.len_utf8());
+    if let Some(offset) = input.strip_prefix('@') {
+        let offset: usize = offset.trim().parse().map_err(|_| format!("Not a byte offset: {}", offset))?;
+        if offset >= body.len() {
+            return Err(format!("Offset {} is past the end of the body ({} bytes)", offset, body.len()));
+        }
+        let mut start = offset;
+        while !body.is_char_boundary(start) {
+            start -= 1;
+        }
+        return Ok((start, char_end(start)));
+    }
+    let (line, column) = input.split_once(':').unwrap_or((input, ""));
+    let line: usize = line.trim().parse().map_err(|_| format!("Not a line number: {}", input))?;
+    let column: usize = match column.trim() {
+        "" => 0,
+        c => c.parse().map_err(|_| format!("Not a column: {}", c))?,
+    };
+    let lines = body.split('\n').count();
+    if line == 0 || line > lines {
+        return Err(format!("Line {} is out of range; the body has {} lines", line, lines));
+    }
+    let start: usize = body.split('\n').take(line - 1).map(|l| l.len() + 1).sum();
+    let end = body[start..].find('\n').map_or(body.len(), |i| start + i);
+    if column == 0 {
+        return Ok((start, end));
+    }
+    let at = body[start..end].char_indices().nth(column - 1).map_or(end, |(i, _)| start + i);
+    Ok((at, char_end(at).min(end)))
+}
+
+fn offset_to_position(text: &str, offset: usize) -> text_editor::Position {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count();
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1);
+    text_editor::Position { line, column }
+}
+
+/// Replace an editor's text, keeping the cursor where it was as far as the new text allows
+fn set_editor_text(content: &mut text_editor::Content, text: &str) {
+    let position = content.cursor().position;
+    *content = text_editor::Content::with_text(text);
+    let offset = position_to_offset(text, position);
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    content.move_to(text_editor::Cursor {
+        position: offset_to_position(text, offset),
+        selection: None,
+    });
+}
+
+/// Point relative links in `html` back at `url` so stylesheets and images
+/// still resolve from a local file. Pages that set their own `<base>` are left alone.
+fn with_base_href(html: &str, url: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    if lower.contains("<base") {
+        return html.to_string();
+    }
+    let base = format!("<base href=\"{}\">", url.replace('"', "%22"));
+    let insert_at = lower
+        .find("<head")
+        .and_then(|start| lower[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(0);
+    format!("{}{}{}", &html[..insert_at], base, &html[insert_at..])
+}
+
+/// Open a file with the system's default handler
+fn open_in_browser(path: &std::path::Path) -> Result<(), String> {
+    use std::process::Command;
+
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(path).spawn().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// A clipboard image, kept both as the PNG that's sent and as pixels to show
+#[derive(Debug, Clone)]
+struct PastedImage {
+    png: Vec<u8>,
+    width: u32,
+    height: u32,
+    thumbnail: iced::widget::image::Handle,
+}
+
+/// Read an image from the clipboard and encode it as PNG. Clipboards hand
+/// over decoded pixels, so the original format isn't known
+fn paste_image() -> Result<PastedImage, String> {
+    let image = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_image())
+        .map_err(|e| format!("No image on the clipboard: {}", e))?;
+    let (width, height) = (image.width as u32, image.height as u32);
+    let rgba = image.bytes.into_owned();
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer| writer.write_image_data(&rgba))
+        .map_err(|e| format!("Could not encode the image: {}", e))?;
+
+    Ok(PastedImage {
+        png,
+        width,
+        height,
+        thumbnail: iced::widget::image::Handle::from_rgba(width, height, rgba),
+    })
+}
+
+fn copy_to_clipboard(text: String) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("Copy failed: {}", e))
+}
+
+/// Items of one page: the body itself when it's an array, otherwise the
+/// first array under a common envelope key (or any top-level array)
+fn page_items(body: &str) -> Vec<serde_json::Value> {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Array(items)) => items,
+        Ok(serde_json::Value::Object(map)) => ["data", "results", "items", "records"]
+            .iter()
+            .filter_map(|key| map.get(*key))
+            .chain(map.values())
+            .find_map(|v| v.as_array().cloned())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// One `Set-Cookie` header, attributes as sent
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SetCookie {
+    name: String,
+    value: String,
+    domain: Option<String>,
+    path: Option<String>,
+    expires: Option<String>,
+    max_age: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<String>,
+}
+
+impl SetCookie {
+    /// Lacks Secure or HttpOnly, so it can leak over http or to scripts
+    fn is_weak(&self) -> bool {
+        !self.secure || !self.http_only
+    }
+
+    /// When the cookie goes away; Max-Age wins over Expires, as in browsers
+    fn expiry(&self) -> String {
+        match (&self.max_age, &self.expires) {
+            (Some(max_age), _) => format!("Max-Age {}", max_age),
+            (None, Some(expires)) => expires.clone(),
+            (None, None) => "Session".to_string(),
+        }
+    }
+
+    fn flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.secure {
+            flags.push("Secure".to_string());
+        }
+        if self.http_only {
+            flags.push("HttpOnly".to_string());
+        }
+        if let Some(ref same_site) = self.same_site {
+            flags.push(format!("SameSite={}", same_site));
+        }
+        flags.join(" ")
+    }
+
+    /// A one-line summary for copying
+    fn describe(&self) -> String {
+        format!(
+            "{}={}\tDomain={}\tPath={}\t{}\t{}",
+            self.name,
+            self.value,
+            self.domain.as_deref().unwrap_or("-"),
+            self.path.as_deref().unwrap_or("-"),
+            self.expiry(),
+            self.flags(),
+        )
+    }
+}
+
+/// Parse a `Set-Cookie` value (RFC 6265 §5.2); None without a `name=value` pair
+fn parse_set_cookie(header: &str) -> Option<SetCookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut cookie = SetCookie {
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        ..Default::default()
+    };
+    for attribute in parts {
+        let (key, value) = match attribute.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+            None => (attribute.trim(), None),
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "domain" => cookie.domain = value.map(|d| d.trim_start_matches('.').to_string()),
+            "path" => cookie.path = value,
+            "expires" => cookie.expires = value,
+            "max-age" => cookie.max_age = value,
+            "secure" => cookie.secure = true,
+            "httponly" => cookie.http_only = true,
+            "samesite" => cookie.same_site = value,
+            _ => {}
+        }
+    }
+    Some(cookie)
+}
+
+/// Every cookie the response sets, in header order
+fn response_cookies(response: &Response) -> Vec<SetCookie> {
+    response.headers.iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("set-cookie"))
+        .filter_map(|(_, v)| parse_set_cookie(v))
+        .collect()
+}
+
+/// Set-Cookie headers as a table, cookies without Secure or HttpOnly in warning colour
+fn view_cookies<'a>(response: &Response) -> Element<'a, Message> {
+    let cookies = response_cookies(response);
+    if cookies.is_empty() {
+        return container(text("No Set-Cookie headers").size(11).color(colors::TEXT_SECONDARY))
+            .padding(12)
+            .into();
+    }
+
+    let cell = |value: String, width: u16, color: iced::Color| {
+        container(text(value).size(11).color(color)).width(Length::FillPortion(width))
+    };
+    let header = ["Name", "Value", "Domain", "Path", "Expires", "Flags"]
+        .into_iter()
+        .zip([2, 3, 2, 1, 2, 3])
+        .fold(row![].spacing(8), |row, (label, width)| {
+            row.push(cell(label.to_string(), width, colors::TEXT_SECONDARY))
+        });
+    let weak = cookies.iter().filter(|c| c.is_weak()).count();
+
+    let rows = cookies.into_iter().fold(column![].spacing(6), |col, cookie| {
+        let color = if cookie.is_weak() { colors::WARNING } else { colors::TEXT_PRIMARY };
+        col.push(
+            row![
+                cell(cookie.name.clone(), 2, color),
+                cell(truncate_str(&cookie.value, 40), 3, colors::TEXT_PRIMARY),
+                cell(cookie.domain.clone().unwrap_or_else(|| "-".to_string()), 2, colors::TEXT_SECONDARY),
+                cell(cookie.path.clone().unwrap_or_else(|| "-".to_string()), 1, colors::TEXT_SECONDARY),
+                cell(cookie.expiry(), 2, colors::TEXT_SECONDARY),
+                cell(cookie.flags(), 3, color),
+            ]
+            .spacing(8),
+        )
+    });
+
+    let mut content = column![header, rows].spacing(8).padding(12);
+    if weak > 0 {
+        content = content.push(
+            text(format!("{} cookie{} without Secure or HttpOnly", weak, if weak == 1 { "" } else { "s" }))
+                .size(10)
+                .color(colors::WARNING),
+        );
+    }
+    scrollable(content).height(Fill).into()
+}
+
+/// Origin sent in a CORS preflight when the request doesn't set one
+const DEFAULT_CORS_ORIGIN: &str = "http://localhost:3000";
+
+/// What a browser would ask in the preflight for a request
+#[derive(Debug, Clone)]
+struct CorsPreflight {
+    url: String,
+    origin: String,
+    method: Method,
+    /// Lowercase names of the non-safelisted headers the request sets
+    headers: Vec<String>,
+    /// Cookies go along, so the answer must name the origin and allow credentials
+    credentials: bool,
+}
+
+impl CorsPreflight {
+    /// Only methods and headers outside the CORS safelist trigger a preflight
+    fn needed(&self) -> bool {
+        !matches!(self.method, Method::GET | Method::HEAD | Method::POST) || !self.headers.is_empty()
+    }
+
+    /// The OPTIONS request itself, sent with the client settings of `request`
+    fn request(&self, request: OutgoingRequest) -> OutgoingRequest {
+        let mut headers = format!("Origin: {}\nAccess-Control-Request-Method: {}", self.origin, self.method);
+        if !self.headers.is_empty() {
+            headers.push_str(&format!("\nAccess-Control-Request-Headers: {}", self.headers.join(",")));
+        }
+        OutgoingRequest {
+            method: Method::OPTIONS,
+            body: Vec::new(),
+            body_file: None,
+            headers,
+            auth_type: AuthType::None,
+            default_content_type: None,
+            body_any_method: false,
+            chunked: false,
+            gzip: false,
+            ..request
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CorsVerdict {
+    preflight: CorsPreflight,
+    status: u16,
+    /// Each rule checked, and whether the response passes it
+    checks: Vec<(bool, String)>,
+    notes: Vec<String>,
+}
+
+impl CorsVerdict {
+    fn allowed(&self) -> bool {
+        self.checks.iter().all(|(pass, _)| *pass)
+    }
+}
+
+fn cors_preflight(request: &OutgoingRequest) -> CorsPreflight {
+    // Set by the browser itself, so never listed in the preflight
+    const BROWSER_HEADERS: &[&str] = &[
+        "origin", "host", "content-length", "user-agent", "cookie", "connection",
+        "accept-encoding", "referer", "transfer-encoding",
+    ];
+    let safelisted = |name: &str, value: &str| match name {
+        "accept" | "accept-language" | "content-language" => true,
+        "content-type" => {
+            let mime = value.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+            matches!(mime.as_str(), "application/x-www-form-urlencoded" | "multipart/form-data" | "text/plain")
+        }
+        _ => false,
+    };
+    let mut headers: Vec<String> = Vec::new();
+    let mut add = |name: String| {
+        if !headers.contains(&name) {
+            headers.push(name);
+        }
+    };
+    for (name, value) in parse_header_lines(&request.headers).0 {
+        let name = name.to_ascii_lowercase();
+        if !BROWSER_HEADERS.contains(&name.as_str()) && !safelisted(&name, &value) {
+            add(name);
+        }
+    }
+    let sends_body = request.method.has_body() || request.body_any_method;
+    if let Some(content_type) = request.default_content_type
+        && sends_body
+        && header_value(&request.headers, "content-type").is_none()
+        && !safelisted("content-type", content_type)
+    {
+        add("content-type".to_string());
+    }
+    if request.gzip && sends_body && header_value(&request.headers, "content-encoding").is_none() {
+        add("content-encoding".to_string());
+    }
+    if request.auth_type != AuthType::None {
+        add("authorization".to_string());
+    }
+    headers.sort();
+    CorsPreflight {
+        url: request.url.clone(),
+        origin: header_value(&request.headers, "origin")
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .unwrap_or(DEFAULT_CORS_ORIGIN)
+            .to_string(),
+        method: request.method,
+        headers,
+        credentials: header_value(&request.headers, "cookie").is_some(),
+    }
+}
+
+/// Read the `Access-Control-Allow-*` answer to a preflight the way a browser would
+fn cors_verdict(preflight: &CorsPreflight, response: &Response) -> CorsVerdict {
+    let list = |name: &str| -> Vec<String> {
+        response.header(name)
+            .map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default()
+    };
+    let wildcard = |values: &[String]| !preflight.credentials && values.iter().any(|v| v == "*");
+    let mut checks = Vec::new();
+    let mut notes = Vec::new();
+
+    if !preflight.needed() {
+        notes.push("A browser sends this request without a preflight; only the origin check applies".to_string());
+    } else if !(200..300).contains(&response.status) {
+        checks.push((false, format!("Preflight answered {}; browsers need a 2xx", response.status)));
+    }
+
+    match response.header("access-control-allow-origin").map(str::trim) {
+        None => checks.push((false, "No Access-Control-Allow-Origin header".to_string())),
+        Some("*") if preflight.credentials => {
+            checks.push((false, "Allow-Origin is *, which browsers reject when cookies are sent".to_string()));
+        }
+        Some("*") => checks.push((true, "Any origin allowed (*)".to_string())),
+        Some(origin) if origin == preflight.origin => checks.push((true, format!("Origin {} allowed", origin))),
+        Some(origin) => checks.push((false, format!("Allows origin {}, not {}", origin, preflight.origin))),
+    }
+
+    if preflight.credentials {
+        let allowed = response.header("access-control-allow-credentials").is_some_and(|v| v.trim() == "true");
+        checks.push(if allowed {
+            (true, "Credentials allowed".to_string())
+        } else {
+            (false, "Cookies need Access-Control-Allow-Credentials: true".to_string())
+        });
+    }
+
+    if !matches!(preflight.method, Method::GET | Method::HEAD | Method::POST) {
+        let methods = list("access-control-allow-methods");
+        let method = preflight.method.to_string();
+        checks.push(if methods.contains(&method) || wildcard(&methods) {
+            (true, format!("Method {} allowed", method))
+        } else if methods.is_empty() {
+            (false, format!("No Access-Control-Allow-Methods, so {} is blocked", method))
+        } else {
+            (false, format!("{} isn't in Access-Control-Allow-Methods ({})", method, methods.join(", ")))
+        });
+    }
+
+    if !preflight.headers.is_empty() {
+        let allowed = list("access-control-allow-headers");
+        // A * never covers Authorization
+        let missing: Vec<&str> = preflight.headers.iter()
+            .filter(|h| {
+                !allowed.iter().any(|a| a.eq_ignore_ascii_case(h)) && (*h == "authorization" || !wildcard(&allowed))
+            })
+            .map(String::as_str)
+            .collect();
+        checks.push(if missing.is_empty() {
+            (true, format!("Headers allowed: {}", preflight.headers.join(", ")))
+        } else {
+            (false, format!("Not in Access-Control-Allow-Headers: {}", missing.join(", ")))
+        });
+    }
+
+    if let Some(max_age) = response.header("access-control-max-age") {
+        notes.push(format!("Browsers may cache this preflight for {}s", max_age.trim()));
+    }
+    CorsVerdict { preflight: preflight.clone(), status: response.status, checks, notes }
+}
+
+/// Banner above the response tabs with the last preflight's verdict
+fn view_cors_verdict(verdict: &Result<CorsVerdict, String>) -> Element<'_, Message> {
+    let verdict = match verdict {
+        Ok(verdict) => verdict,
+        Err(e) => {
+            let details = column![
+                text("CORS PREFLIGHT FAILED").size(12).color(colors::ERROR),
+                text(e.clone()).size(10).color(colors::TEXT_SECONDARY),
+            ];
+            return cors_banner(details, colors::ERROR);
+        }
+    };
+    let (title, color) = if verdict.allowed() {
+        ("CORS ALLOWED", colors::SUCCESS)
+    } else {
+        ("CORS BLOCKED", colors::ERROR)
+    };
+    let preflight = &verdict.preflight;
+    let mut details = column![
+        row![
+            text(title).size(12).color(color),
+            text(format!(
+                "{} {} from {} (preflight {})",
+                preflight.method,
+                truncate_str(&preflight.url, 60),
+                preflight.origin,
+                verdict.status,
+            ))
+            .size(10)
+            .color(colors::TEXT_SECONDARY),
+        ]
+        .spacing(12)
+        .align_y(iced::Alignment::Center),
+    ];
+    for (pass, check) in &verdict.checks {
+        let (mark, color) = if *pass { ("✓", colors::SUCCESS) } else { ("✗", colors::ERROR) };
+        details = details.push(text(format!("{} {}", mark, check)).size(10).color(color));
+    }
+    for note in &verdict.notes {
+        details = details.push(text(note.clone()).size(10).color(colors::TEXT_SECONDARY));
+    }
+    cors_banner(details, color)
+}
+
+fn cors_banner<'a>(details: Column<'a, Message>, accent: iced::Color) -> Element<'a, Message> {
+    let dismiss = button(text("×").size(12))
+        .padding([0, 6])
+        .style(secondary_button_style)
+        .on_press(Message::DismissCors);
+    container(row![details.spacing(3).width(Fill), dismiss].spacing(8))
+        .padding([8, 16])
+        .width(Fill)
+        .style(move |_| container::Style {
+            background: Some(colors::BG_ELEVATED.into()),
+            border: iced::Border { color: accent, width: 1.0, radius: 0.0.into() },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Find the next page's URL in the body at `path` (e.g. `.links.next`),
+/// or in an RFC 5988 `Link: <...>; rel="next"` header when `path` is blank
+fn next_page_url(response: &Response, path: &str) -> Option<String> {
+    let next = if path.trim().is_empty() {
+        response.header("link").and_then(link_next)?
+    } else {
+        let value = serde_json::from_str::<serde_json::Value>(&response.body).ok()?;
+        json_path(&value, path)?.as_str()?.to_string()
+    };
+    if next.is_empty() {
+        return None;
+    }
+    // Relative links are resolved against the page they came from
+    let base = reqwest::Url::parse(&response.url).ok()?;
+    let next = base.join(&next).ok()?.to_string();
+    (next != response.url).then_some(next)
+}
+
+fn link_next(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .filter_map(|p| p.trim().strip_prefix("rel="))
+            .any(|rel| rel.trim_matches('"').split_whitespace().any(|r| r == "next"));
+        is_next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+/// The text of the body value at a capture rule's path, written `.data.token`
+/// or `data.items[0].id`: strings as they are, anything else as compact JSON
+fn captured_body_value(body: &serde_json::Value, path: &str) -> Option<String> {
+    let path = path.replace('[', ".").replace(']', "");
+    match json_path(body, &path)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Look up a dotted path like `.meta.next` or `links.0.href`
+fn json_path<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            serde_json::Value::Object(map) => map.get(segment),
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Dim `base` and show `content` in a centered panel on top of it.
+/// Clicking the dimmed area sends `on_close`.
+fn modal<'a>(base: Element<'a, Message>, content: Element<'a, Message>, on_close: Message) -> Element<'a, Message> {
+    use iced::widget::{opaque, stack};
+
+    let modal_overlay = mouse_area(
+        container(column![])
+            .width(Fill)
+            .height(Fill)
+            .style(|_| container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.5).into()),
+                ..Default::default()
+            }),
+    )
+    .on_press(on_close);
+
+    let modal_content = container(content)
+        .padding(20)
+        .style(|_| container::Style {
+            background: Some(colors::BG_PANEL.into()),
+            border: iced::Border {
+                color: colors::BORDER,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        });
+
+    let modal_centered = container(opaque(modal_content))
+        .width(Fill)
+        .height(Fill)
+        .center_x(Fill)
+        .center_y(Fill);
+
+    stack![base, modal_overlay, modal_centered].into()
+}
+
+/// Small bordered panel used as tooltip content
+fn tooltip_box<'a>(label: impl text::IntoFragment<'a>) -> iced::widget::Container<'a, Message> {
+    container(text(label).size(10).color(colors::TEXT_PRIMARY))
+        .padding([4, 8])
+        .max_width(320)
+        .style(|_| container::Style {
+            background: Some(colors::BG_ELEVATED.into()),
+            border: iced::Border {
+                color: colors::BORDER,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        })
+}
+
+/// One-line explanation of a status code for the status tooltip
+fn status_explanation(status: u16) -> String {
+    let explanation = match status {
+        100 => "Continue — the server got the headers; send the body",
+        101 => "Switching Protocols — the server is upgrading the connection (e.g. WebSocket)",
+        200 => "OK — the request succeeded",
+        201 => "Created — the request succeeded and a new resource was created",
+        202 => "Accepted — the request was accepted but hasn't been processed yet",
+        204 => "No Content — the request succeeded and there is no body",
+        206 => "Partial Content — only the requested range of the body was sent",
+        301 => "Moved Permanently — the resource has a new permanent URL",
+        302 => "Found — the resource is temporarily at another URL",
+        303 => "See Other — fetch the result from another URL with GET",
+        304 => "Not Modified — the cached copy is still valid",
+        307 => "Temporary Redirect — repeat the same request at another URL",
+        308 => "Permanent Redirect — repeat the same request at the new URL from now on",
+        400 => "Bad Request — the server couldn't understand the request (often malformed syntax)",
+        401 => "Unauthorized — authentication is missing or invalid",
+        403 => "Forbidden — authenticated, but not allowed to access this resource",
+        404 => "Not Found — nothing exists at this URL",
+        405 => "Method Not Allowed — this URL doesn't support the request method",
+        406 => "Not Acceptable — no representation matches the Accept headers",
+        408 => "Request Timeout — the server gave up waiting for the request",
+        409 => "Conflict — the request conflicts with the resource's current state",
+        410 => "Gone — the resource was removed and won't come back",
+        411 => "Length Required — the server needs a Content-Length header",
+        412 => "Precondition Failed — an If-* header condition wasn't met",
+        413 => "Content Too Large — the request body is bigger than the server accepts",
+        414 => "URI Too Long — the URL is longer than the server accepts",
+        415 => "Unsupported Media Type — the server doesn't accept this Content-Type",
+        416 => "Range Not Satisfiable — the requested range is outside the resource",
+        418 => "I'm a teapot — a joke status some servers use to refuse requests",
+        422 => "Unprocessable Entity — the request was well-formed but had semantic errors",
+        425 => "Too Early — the server won't risk processing a possibly replayed request",
+        428 => "Precondition Required — the server requires a conditional request",
+        429 => "Too Many Requests — you're being rate limited; check Retry-After",
+        431 => "Request Header Fields Too Large — the headers are too big",
+        451 => "Unavailable For Legal Reasons — access is blocked for legal reasons",
+        500 => "Internal Server Error — the server hit an unexpected error",
+        501 => "Not Implemented — the server doesn't support this functionality",
+        502 => "Bad Gateway — an upstream server returned an invalid response",
+        503 => "Service Unavailable — the server is overloaded or down for maintenance",
+        504 => "Gateway Timeout — an upstream server didn't respond in time",
+        505 => "HTTP Version Not Supported — the server doesn't support this HTTP version",
+        _ => {
+            let class = match status {
+                100..=199 => "Informational",
+                200..=299 => "Success",
+                300..=399 => "Redirection",
+                400..=499 => "Client error",
+                500..=599 => "Server error",
+                _ => "Non-standard status",
+            };
+            return format!("{} — {}", status, class);
+        }
+    };
+    format!("{} {}", status, explanation)
+}
+
+fn status_color(status: u16) -> iced::Color {
+    match status {
+        200..=299 => colors::SUCCESS,
+        300..=399 => colors::ACCENT_PURPLE,
+        400..=499 => colors::WARNING,
+        500..=599 => colors::ERROR,
+        _ => colors::TEXT_PRIMARY,
+    }
+}
+
+/// Short human-readable reason from an error response body.
+/// Looks for the usual JSON error fields first, then falls back to the first line of text.
+fn error_summary(body: &str) -> Option<String> {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+        for key in ["message", "error", "detail", "title", "error_description"] {
+            match value.get(key) {
+                Some(serde_json::Value::String(s)) if !s.trim().is_empty() => {
+                    return Some(s.trim().to_string());
+                }
+                // e.g. {"error": {"message": "..."}}
+                Some(nested @ serde_json::Value::Object(_)) => {
+                    if let Some(serde_json::Value::String(s)) = nested.get("message") {
+                        return Some(s.trim().to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        return None;
+    }
+    // HTML error pages usually carry the reason in <title>
+    let lower = body.to_ascii_lowercase();
+    if let Some(start) = lower.find("<title>")
+        && let Some(len) = lower[start + 7..].find("</title>")
+    {
+        let title = body[start + 7..start + 7 + len].trim();
+        return (!title.is_empty()).then(|| title.to_string());
+    }
+    body.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// A field of the URL component editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UrlPart {
+    Scheme,
+    Host,
+    Port,
+    Path,
+    Query,
+    Fragment,
+}
+
+/// The typed URL cut into its components without resolving anything, so
+/// `{{variables}}` survive a round trip. `join` puts it back together
+/// exactly as it was when nothing has changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct UrlParts {
+    scheme: String,
+    userinfo: String,
+    host: String,
+    port: String,
+    path: String,
+    query: String,
+    fragment: String,
+}
+
+impl UrlParts {
+    fn split(url: &str) -> Self {
+        let mut parts = UrlParts::default();
+        let mut rest = url.trim();
+        if let Some((fragment_start, fragment)) = rest.split_once('#') {
+            parts.fragment = fragment.to_string();
+            rest = fragment_start;
+        }
+        if let Some((before, query)) = rest.split_once('?') {
+            parts.query = query.to_string();
+            rest = before;
+        }
+        if let Some((scheme, after)) = rest.split_once("://") {
+            parts.scheme = scheme.to_string();
+            rest = after;
+        } else if rest.starts_with('/') {
+            // Relative to the environment's base URL
+            parts.path = rest.to_string();
+            return parts;
+        }
+        let (authority, path) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+        parts.path = path.to_string();
+        let hostport = match authority.rsplit_once('@') {
+            Some((userinfo, hostport)) => {
+                parts.userinfo = userinfo.to_string();
+                hostport
+            }
+            None => authority,
+        };
+        // The port is whatever follows the last colon, unless that colon is inside an IPv6 literal
+        match hostport.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => {
+                parts.host = host.to_string();
+                parts.port = port.to_string();
+            }
+            _ => parts.host = hostport.to_string(),
+        }
+        parts
+    }
+
+    fn join(&self) -> String {
+        let mut url = String::new();
+        if !self.scheme.is_empty() {
+            url.push_str(&self.scheme);
+            url.push_str("://");
+        }
+        if !self.userinfo.is_empty() {
+            url.push_str(&self.userinfo);
+            url.push('@');
+        }
+        url.push_str(&self.host);
+        if !self.port.is_empty() {
+            url.push(':');
+            url.push_str(&self.port);
+        }
+        if !self.path.is_empty() && !self.path.starts_with('/') && !url.is_empty() {
+            url.push('/');
+        }
+        url.push_str(&self.path);
+        if !self.query.is_empty() {
+            url.push('?');
+            url.push_str(&self.query);
+        }
+        if !self.fragment.is_empty() {
+            url.push('#');
+            url.push_str(&self.fragment);
+        }
+        url
+    }
+
+    fn get(&self, part: UrlPart) -> &str {
+        match part {
+            UrlPart::Scheme => &self.scheme,
+            UrlPart::Host => &self.host,
+            UrlPart::Port => &self.port,
+            UrlPart::Path => &self.path,
+            UrlPart::Query => &self.query,
+            UrlPart::Fragment => &self.fragment,
+        }
+    }
+
+    fn set(&mut self, part: UrlPart, value: String) {
+        *match part {
+            UrlPart::Scheme => &mut self.scheme,
+            UrlPart::Host => &mut self.host,
+            UrlPart::Port => &mut self.port,
+            UrlPart::Path => &mut self.path,
+            UrlPart::Query => &mut self.query,
+            UrlPart::Fragment => &mut self.fragment,
+        } = value;
+    }
+}
+
+/// Split a leading method off a `POST https://...` line as pasted from logs or
+/// docs, dropping a trailing `HTTP/1.1`. Only known methods followed by
+/// whitespace and something else count, so ordinary URLs are left alone.
+fn split_method_prefix(input: &str) -> Option<(Method, String)> {
+    let (verb, rest) = input.trim_start().split_once(char::is_whitespace)?;
+    let method = Method::ALL.iter().copied().find(|m| m.to_string().eq_ignore_ascii_case(verb))?;
+    let mut rest = rest.trim_start();
+    if let Some((url, version)) = rest.rsplit_once(char::is_whitespace)
+        && version.to_ascii_uppercase().starts_with("HTTP/")
+    {
+        rest = url.trim_end();
+    }
+    (!rest.is_empty()).then(|| (method, rest.to_string()))
+}
+
+/// Why the URL as it will be sent can't be parsed, once every variable in it
+/// is resolved
+fn url_problem(effective_url: &str) -> Option<String> {
+    let url = effective_url.trim();
+    if url.is_empty() || split_unix_socket_url(url).is_some() || !unresolved_variables(url).is_empty() {
+        return None;
+    }
+    if !url.contains("://") {
+        return Some("No scheme; start with http:// or https://, or set a base URL on the environment".to_string());
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if !matches!(parsed.scheme(), "http" | "https" | "ws" | "wss") => {
+            Some(format!("Unsupported scheme \"{}\"; expected http:// or https://", parsed.scheme()))
+        }
+        Ok(_) => None,
+        Err(e) => Some(format!("Malformed URL: {}", e)),
+    }
+}
+
+/// Split `unix:///var/run/docker.sock:/v1.41/info` into the socket path and
+/// the request path (defaulting to `/`)
+fn split_unix_socket_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.trim().strip_prefix("unix://")?;
+    let (socket, path) = match rest.find(":/") {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, "/"),
+    };
+    (!socket.is_empty()).then_some((socket, path))
+}
+
+#[cfg(unix)]
+fn with_unix_socket(builder: reqwest::ClientBuilder, socket: &str) -> Result<reqwest::ClientBuilder, String> {
+    Ok(builder.unix_socket(socket))
+}
+
+#[cfg(not(unix))]
+fn with_unix_socket(_builder: reqwest::ClientBuilder, _socket: &str) -> Result<reqwest::ClientBuilder, String> {
+    Err("Unix sockets are not supported on this platform".to_string())
+}
+
+/// Read a client certificate for mutual TLS. Returns the identity and a
+/// human-readable description of it (the certificate subject when available).
+fn load_client_identity(cert: &ClientCert) -> Result<(reqwest::Identity, String), String> {
+    let cert_path = cert.cert_path.trim();
+    let cert_bytes = std::fs::read(cert_path)
+        .map_err(|e| format!("Could not read client certificate {}: {}", cert_path, e))?;
+
+    let is_pkcs12 = std::path::Path::new(cert_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("p12") || e.eq_ignore_ascii_case("pfx"));
+
+    if is_pkcs12 {
+        let identity = reqwest::Identity::from_pkcs12_der(&cert_bytes, &cert.passphrase)
+            .map_err(|e| format!("Could not open PKCS#12 file (wrong passphrase?): {}", e))?;
+        return Ok((identity, "PKCS#12 identity".to_string()));
+    }
+
+    // PEM: the key may live in the same file as the certificate
+    let key_path = cert.key_path.trim();
+    let key_bytes = if key_path.is_empty() {
+        cert_bytes.clone()
+    } else {
+        std::fs::read(key_path).map_err(|e| format!("Could not read private key {}: {}", key_path, e))?
+    };
+    let identity = reqwest::Identity::from_pkcs8_pem(&cert_bytes, &key_bytes)
+        .map_err(|e| format!("Invalid PEM certificate or PKCS#8 key: {}", e))?;
+    let subject = pem_certificate_subject(&cert_bytes).unwrap_or_else(|| "PEM identity".to_string());
+    Ok((identity, subject))
+}
+
+/// Run the first two legs of NTLM on `probe` (the request without its body)
+/// and return the `Authorization` value that completes it. Servers that only
+/// offer Negotiate get the same NTLM tokens under that scheme, which SPNEGO
+/// accepts in place of Kerberos.
+async fn ntlm_handshake(probe: reqwest::RequestBuilder, user: &str, domain: &str, password: &str) -> Result<String, String> {
+    use base64::Engine;
+    let engine = base64::engine::general_purpose::STANDARD;
+    let negotiate = engine.encode(ntlm_negotiate_message());
+
+    let mut offered = Vec::new();
+    for scheme in ["NTLM", "Negotiate"] {
+        let attempt = probe.try_clone().ok_or("NTLM: request can't be repeated for the handshake")?;
+        let response = attempt
+            .header("Authorization", format!("{} {}", scheme, negotiate))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let challenges: Vec<String> = response.headers()
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|v| v.to_str().ok().map(str::to_string))
+            .collect();
+        // Read the body out so the connection goes back to the pool for the next leg
+        let _ = response.bytes().await;
+        if status != 401 {
+            return Err(format!("NTLM: expected a 401 challenge, the server answered {}", status));
+        }
+
+        let token = challenges.iter().find_map(|c| {
+            let (name, token) = c.trim().split_once(' ')?;
+            name.eq_ignore_ascii_case(scheme).then(|| token.trim().to_string())
+        });
+        if let Some(token) = token {
+            let challenge = engine.decode(&token).map_err(|_| "NTLM: challenge isn't base64".to_string())?;
+            let challenge = parse_ntlm_challenge(&challenge)?;
+            let mut client_challenge = [0u8; 8];
+            getrandom::fill(&mut client_challenge).map_err(|e| e.to_string())?;
+            let authenticate = ntlm_authenticate_message(
+                &challenge,
+                user,
+                domain,
+                password,
+                client_challenge,
+                windows_filetime(std::time::SystemTime::now()),
+            );
+            return Ok(format!("{} {}", scheme, engine.encode(authenticate)));
+        }
+        offered = challenges;
+        if !offered.iter().any(|c| c.trim().to_ascii_lowercase().starts_with("negotiate")) {
+            break;
+        }
+    }
+
+    let schemes: Vec<&str> = offered.iter()
+        .filter_map(|c| c.split_whitespace().next())
+        .collect();
+    Err(if schemes.is_empty() {
+        "NTLM: the server didn't offer any authentication scheme".to_string()
+    } else {
+        format!("NTLM: the server doesn't offer NTLM (offers {})", schemes.join(", "))
+    })
+}
+
+const NTLM_SIGNATURE: &[u8; 8] = b"NTLMSSP\0";
+const NTLM_NEGOTIATE_UNICODE: u32 = 0x0000_0001;
+const NTLM_NEGOTIATE_OEM: u32 = 0x0000_0002;
+const NTLM_REQUEST_TARGET: u32 = 0x0000_0004;
+const NTLM_NEGOTIATE_NTLM: u32 = 0x0000_0200;
+const NTLM_NEGOTIATE_ALWAYS_SIGN: u32 = 0x0000_8000;
+const NTLM_NEGOTIATE_EXTENDED_SESSIONSECURITY: u32 = 0x0008_0000;
+const NTLM_NEGOTIATE_128: u32 = 0x2000_0000;
+const NTLM_NEGOTIATE_56: u32 = 0x8000_0000;
+const NTLM_FLAGS: u32 = NTLM_NEGOTIATE_UNICODE
+    | NTLM_NEGOTIATE_OEM
+    | NTLM_REQUEST_TARGET
+    | NTLM_NEGOTIATE_NTLM
+    | NTLM_NEGOTIATE_ALWAYS_SIGN
+    | NTLM_NEGOTIATE_EXTENDED_SESSIONSECURITY
+    | NTLM_NEGOTIATE_128
+    | NTLM_NEGOTIATE_56;
+/// AV pair id of the server's timestamp in the challenge's target info
+const NTLM_AV_TIMESTAMP: u16 = 7;
+
+/// The fields of a CHALLENGE_MESSAGE (MS-NLMP 2.2.1.2) used to answer it
+#[derive(Debug, Clone, PartialEq)]
+struct NtlmChallenge {
+    flags: u32,
+    server_challenge: [u8; 8],
+    target_info: Vec<u8>,
+}
+
+/// NEGOTIATE_MESSAGE (MS-NLMP 2.2.1.1) without domain or workstation
+fn ntlm_negotiate_message() -> Vec<u8> {
+    let mut message = NTLM_SIGNATURE.to_vec();
+    message.extend(1u32.to_le_bytes());
+    message.extend(NTLM_FLAGS.to_le_bytes());
+    // Empty domain and workstation fields
+    message.extend([0u8; 16]);
+    message
+}
+
+fn parse_ntlm_challenge(message: &[u8]) -> Result<NtlmChallenge, String> {
+    let u16_at = |i: usize| message.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let u32_at = |i: usize| message.get(i..i + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    if message.len() < 32 || &message[..8] != NTLM_SIGNATURE || u32_at(8) != Some(2) {
+        return Err("NTLM: the server's challenge is malformed".to_string());
+    }
+    let mut server_challenge = [0u8; 8];
+    server_challenge.copy_from_slice(&message[24..32]);
+    // Older servers stop before the target info field
+    let target_info = match (u16_at(40), u32_at(44)) {
+        (Some(len), Some(offset)) => message.get(offset as usize..offset as usize + len)
+            .ok_or("NTLM: the server's challenge is truncated")?
+            .to_vec(),
+        _ => Vec::new(),
+    };
+    Ok(NtlmChallenge { flags: u32_at(20).unwrap_or(0), server_challenge, target_info })
+}
+
+/// AUTHENTICATE_MESSAGE (MS-NLMP 2.2.1.3) carrying NTLMv2 responses.
+/// `timestamp` is a Windows FILETIME; the server's own is used when it sends one.
+fn ntlm_authenticate_message(
+    challenge: &NtlmChallenge,
+    user: &str,
+    domain: &str,
+    password: &str,
+    client_challenge: [u8; 8],
+    timestamp: u64,
+) -> Vec<u8> {
+    let server_timestamp = ntlm_av_pair(&challenge.target_info, NTLM_AV_TIMESTAMP)
+        .and_then(|value| value.try_into().ok())
+        .map(u64::from_le_bytes);
+
+    let ntowf = ntowf_v2(user, domain, password);
+    let mut blob = vec![1, 1, 0, 0, 0, 0, 0, 0];
+    blob.extend(server_timestamp.unwrap_or(timestamp).to_le_bytes());
+    blob.extend(client_challenge);
+    blob.extend([0u8; 4]);
+    blob.extend(&challenge.target_info);
+    blob.extend([0u8; 4]);
+    let mut nt_response = hmac_md5(&ntowf, &[&challenge.server_challenge[..], &blob].concat()).to_vec();
+    nt_response.extend(blob);
+    // With a server timestamp the LMv2 response must be zeroed (MS-NLMP 3.1.5.1.2)
+    let lm_response = if server_timestamp.is_some() {
+        vec![0u8; 24]
+    } else {
+        let mut lm = hmac_md5(&ntowf, &[challenge.server_challenge, client_challenge].concat()).to_vec();
+        lm.extend(client_challenge);
+        lm
+    };
+
+    let fields = [lm_response, nt_response, utf16le(domain), utf16le(user), Vec::new(), Vec::new()];
+    let mut header = NTLM_SIGNATURE.to_vec();
+    header.extend(3u32.to_le_bytes());
+    let mut payload: Vec<u8> = Vec::new();
+    let mut offset = 64;
+    for field in &fields {
+        header.extend((field.len() as u16).to_le_bytes());
+        header.extend((field.len() as u16).to_le_bytes());
+        header.extend((offset as u32).to_le_bytes());
+        offset += field.len();
+        payload.extend(field);
+    }
+    header.extend((challenge.flags & NTLM_FLAGS).to_le_bytes());
+    header.extend(payload);
+    header
+}
+
+/// The value of the first AV pair with `id` in a target info block
+fn ntlm_av_pair(target_info: &[u8], id: u16) -> Option<&[u8]> {
+    let mut rest = target_info;
+    while rest.len() >= 4 {
+        let pair_id = u16::from_le_bytes([rest[0], rest[1]]);
+        let len = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+        let value = rest.get(4..4 + len)?;
+        if pair_id == 0 {
+            return None;
+        }
+        if pair_id == id {
+            return Some(value);
+        }
+        rest = &rest[4 + len..];
+    }
+    None
+}
+
+/// NTOWFv2: HMAC-MD5 keyed with the MD4 password hash over the upper-cased
+/// user name and the domain
+fn ntowf_v2(user: &str, domain: &str, password: &str) -> [u8; 16] {
+    use md4::Digest;
+    let nt_hash: [u8; 16] = md4::Md4::digest(utf16le(password)).into();
+    hmac_md5(&nt_hash, &utf16le(&format!("{}{}", user.to_uppercase(), domain)))
+}
+
+fn hmac_md5(key: &[u8], data: &[u8]) -> [u8; 16] {
+    use hmac::Mac;
+    let mut mac = hmac::Hmac::<md5::Md5>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+fn utf16le(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// 100ns intervals since 1601-01-01, the clock NTLMv2 timestamps use
+fn windows_filetime(time: std::time::SystemTime) -> u64 {
+    const UNIX_EPOCH_AS_FILETIME: u64 = 116_444_736_000_000_000;
+    let since_epoch = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    UNIX_EPOCH_AS_FILETIME + since_epoch.as_nanos() as u64 / 100
+}
+
+fn pem_certificate_subject(pem: &[u8]) -> Option<String> {
+    x509_parser::pem::Pem::iter_from_buffer(pem)
+        .filter_map(Result::ok)
+        .find(|p| p.label == "CERTIFICATE")
+        .and_then(|p| p.parse_x509().ok().map(|c| c.subject().to_string()))
+}
+
+async fn send_request(request: OutgoingRequest) -> Result<Response, String> {
+    use base64::Engine;
+    let OutgoingRequest {
+        url,
+        method,
+        mut body,
+        mut body_file,
+        headers: headers_str,
+        auth_type,
+        auth_token,
+        auth_username,
+        auth_password,
+        auth_domain,
+        default_content_type,
+        client,
+        body_any_method,
+        user_agent,
+        chunked,
+        gzip: compress,
+        pooled,
+    } = request;
+    let start = StdInstant::now();
+    let timeouts = (client.connect_timeout, client.timeout);
+
+    // NTLM authenticates a connection, so the handshake and the request
+    // must share one kept-alive HTTP/1.1 connection
+    let (client, pooled) = if auth_type == AuthType::Ntlm {
+        let client = ClientOptions { pool_max_idle_per_host: 1, ..client };
+        let ntlm_client = client.clone().client_builder()?.http1_only().build().map_err(|e| e.to_string())?;
+        (client, Some(ntlm_client))
+    } else {
+        (client, pooled)
+    };
+
+    // `unix:///path/to.sock:/request/path` goes over the socket instead of TCP
+    let (client, url) = match (split_unix_socket_url(&url), pooled) {
+        (Some((socket, path)), _) => {
+            let client_builder = with_unix_socket(client.client_builder()?, socket)?;
+            (client_builder.build().map_err(|e| e.to_string())?, format!("http://localhost{}", path))
+        }
+        (None, Some(pooled)) => (pooled, url),
+        (None, None) => (client.client_builder()?.build().map_err(|e| e.to_string())?, url),
+    };
+
+    let mut builder = match method {
+        Method::GET => client.get(&url),
+        Method::POST => client.post(&url),
+        Method::PUT => client.put(&url),
+        Method::PATCH => client.patch(&url),
+        Method::DELETE => client.delete(&url),
+        Method::HEAD => client.head(&url),
+        Method::OPTIONS => client.request(reqwest::Method::OPTIONS, &url),
+    };
+
+    // Add auth header
+    match auth_type {
+        AuthType::None => {}
+        AuthType::Bearer => {
+            if !auth_token.is_empty() {
+                builder = builder.header("Authorization", format!("Bearer {}", auth_token));
+            }
+        }
+        AuthType::Basic => {
+            if !auth_username.is_empty() {
+                let credentials = format!("{}:{}", auth_username, auth_password);
+                let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+                builder = builder.header("Authorization", format!("Basic {}", encoded));
+            }
+        }
+        // Added once the handshake below has produced it
+        AuthType::Ntlm => {}
+    }
+
+    let mut has_content_type = false;
+    let mut has_user_agent = false;
+    let mut has_content_encoding = false;
+    // Malformed lines are flagged under the editor and skipped here
+    for (key, value) in parse_header_lines(&headers_str).0 {
+        has_content_type |= key.eq_ignore_ascii_case("content-type");
+        has_user_agent |= key.eq_ignore_ascii_case("user-agent");
+        has_content_encoding |= key.eq_ignore_ascii_case("content-encoding");
+        builder = builder.header(key, value);
+    }
+    if let Some(user_agent) = user_agent
+        && !has_user_agent
+    {
+        builder = builder.header("User-Agent", user_agent);
+    }
+    if let Some(content_type) = default_content_type
+        && !has_content_type
+    {
+        builder = builder.header("Content-Type", content_type);
+    }
+
+    // Cloned before the body goes on, since a streamed body can't be cloned
+    let ntlm_probe = if auth_type == AuthType::Ntlm { builder.try_clone() } else { None };
+
+    let sends_body = method.has_body() || body_any_method;
+    if sends_body && compress && (body_file.is_some() || !body.is_empty()) {
+        // Compressing needs the whole body, so a file is read into memory first
+        let plain = match body_file.take() {
+            Some(path) => tokio::fs::read(&path).await.map_err(|e| format!("Could not read {}: {}", path, e))?,
+            None => body,
+        };
+        body = gzip(&plain).map_err(|e| format!("Could not gzip the body: {}", e))?;
+        if !has_content_encoding {
+            builder = builder.header("Content-Encoding", "gzip");
+        }
+    }
+
+    if sends_body {
+        if let Some(path) = body_file {
+            // Stream from disk so large uploads aren't buffered in memory
+            let file = tokio::fs::File::open(&path).await.map_err(|e| format!("Could not open {}: {}", path, e))?;
+            let len = file.metadata().await.map_err(|e| e.to_string())?.len();
+            if !chunked {
+                builder = builder.header("Content-Length", len);
+            }
+            builder = builder.body(reqwest::Body::from(file));
+        } else if !body.is_empty() {
+            builder = if chunked {
+                // A body of unknown length goes out chunked
+                let chunks = futures_util::stream::iter([Ok::<_, std::io::Error>(body)]);
+                builder.body(reqwest::Body::wrap_stream(chunks))
+            } else {
+                builder.body(body)
+            };
+        }
+    }
+
+    let mut request = builder.build().map_err(|e| e.to_string())?;
+    if let Some(probe) = ntlm_probe {
+        let (user, domain) = match auth_username.split_once('\\') {
+            Some((domain, user)) if auth_domain.is_empty() => (user, domain),
+            _ => (auth_username.as_str(), auth_domain.as_str()),
+        };
+        let authorization = ntlm_handshake(probe, user, domain, &auth_password).await?;
+        let value = reqwest::header::HeaderValue::from_str(&authorization).map_err(|e| e.to_string())?;
+        request.headers_mut().insert(reqwest::header::AUTHORIZATION, value);
+    }
+    let raw_request = format_raw_request(&request);
+    let response = client.execute(request).await.map_err(|e| send_error(e, timeouts))?;
+    let duration = start.elapsed();
+
+    let mut raw_response_headers = format!(
+        "{:?} {} {}\r\n",
+        response.version(),
+        response.status().as_u16(),
+        response.status().canonical_reason().unwrap_or(""),
+    );
+    for (key, value) in response.headers() {
+        raw_response_headers.push_str(&format!("{}: {}\r\n", key, String::from_utf8_lossy(value.as_bytes())));
+    }
+
+    let final_url = response.url().to_string();
+    let status = response.status().as_u16();
+    let status_text = response
+        .status()
+        .canonical_reason()
+        .unwrap_or("")
+        .to_string();
+
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+
+    // Anything that isn't text keeps its bytes, so saving a download writes
+    // the file exactly as received
+    let textual = headers.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .is_none_or(|(_, v)| is_text_mime(v));
+    let (body, raw_body) = if textual {
+        (response.text().await.map_err(|e| send_error(e, timeouts))?, None)
+    } else {
+        let bytes = response.bytes().await.map_err(|e| send_error(e, timeouts))?;
+        match String::from_utf8(bytes.to_vec()) {
+            Ok(text) => (text, None),
+            Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), Some(e.into_bytes())),
+        }
+    };
+    let size = raw_body.as_ref().map_or(body.len(), Vec::len);
+
+    Ok(Response {
+        url: final_url,
+        status,
+        status_text,
+        headers,
+        body,
+        raw_body,
+        duration,
+        size,
+        raw_request,
+        raw_response_headers,
+    })
+}
+
+/// Say which of the (connect, request) timeouts tripped, if either did
+fn send_error(e: reqwest::Error, (connect_timeout, timeout): (Option<Duration>, Option<Duration>)) -> String {
+    if !e.is_timeout() {
+        return e.to_string();
+    }
+    match (e.is_connect(), connect_timeout, timeout) {
+        (true, Some(limit), _) => format!(
+            "Connect timeout: no connection within {} s; the host may be unreachable (Settings → Connect timeout)",
+            limit.as_secs(),
+        ),
+        (false, _, Some(limit)) => format!(
+            "Request timeout: no complete response within {} s (Settings → Request timeout)",
+            limit.as_secs(),
+        ),
+        _ => e.to_string(),
+    }
+}
+
+/// Sorted keys at every level and no insignificant whitespace, so equal
+/// documents serialize to the same bytes
+fn canonical_json(body: &str) -> Option<String> {
+    let mut value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value.sort_all_objects();
+    serde_json::to_string(&value).ok()
+}
+
+fn gzip(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Reconstruct the request as it goes over the wire. Headers the client adds
+/// on its own at send time (e.g. `accept`) aren't known yet and don't appear.
+fn format_raw_request(request: &reqwest::Request) -> String {
+    let url = request.url();
+    let mut target = url.path().to_string();
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        _ => String::new(),
+    };
+
+    let mut raw = format!("{} {} {:?}\r\nHost: {}\r\n", request.method(), target, request.version(), host);
+    for (key, value) in request.headers() {
+        raw.push_str(&format!("{}: {}\r\n", key, String::from_utf8_lossy(value.as_bytes())));
+    }
+    // Framing is added by the connection, so spell out what it will be
+    if let Some(body) = request.body()
+        && !request.headers().contains_key(reqwest::header::CONTENT_LENGTH)
+    {
+        match body.as_bytes() {
+            Some(bytes) => raw.push_str(&format!("content-length: {}\r\n", bytes.len())),
+            None => raw.push_str("transfer-encoding: chunked\r\n"),
+        }
+    }
+    raw.push_str("\r\n");
+
+    if let Some(body) = request.body() {
+        match body.as_bytes() {
+            Some(bytes) => match std::str::from_utf8(bytes) {
+                Ok(body) => raw.push_str(body),
+                Err(_) => raw.push_str(&format!("<{} bytes of binary data>", bytes.len())),
+            },
+            None => raw.push_str("<streamed body>"),
+        }
+    }
+    raw
+}
+
+/// Hide credential header values in raw HTTP text, keeping the auth scheme
+fn mask_credentials(raw: &str) -> String {
+    let (head, body) = raw.split_once("\r\n\r\n").unwrap_or((raw, ""));
+    let head = head.split("\r\n")
+        .map(|line| match line.split_once(':') {
+            Some((key, value))
+                if key.eq_ignore_ascii_case("authorization") || key.eq_ignore_ascii_case("proxy-authorization") =>
+            {
+                match value.trim().split_once(' ') {
+                    Some((scheme, _)) => format!("{}: {} ********", key, scheme),
+                    None => format!("{}: ********", key),
+                }
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    format!("{}\r\n\r\n{}", head, body)
+}
+
+fn format_json(s: &str) -> String {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(s) {
+        serde_json::to_string_pretty(&value).unwrap_or_else(|_| s.to_string())
+    } else {
+        s.to_string()
+    }
+}
+
+/// Token classes shared by the response viewer and the body editor highlighter
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SyntaxKind {
+    Key,
+    String,
+    Number,
+    Literal,
+    Punctuation,
+    Tag,
+    Attribute,
+    Comment,
+}
+
+impl SyntaxKind {
+    fn color(self) -> iced::Color {
+        match self {
+            SyntaxKind::Key | SyntaxKind::Tag => colors::ACCENT_PURPLE,
+            SyntaxKind::String => colors::SUCCESS,
+            SyntaxKind::Number | SyntaxKind::Attribute => colors::ACCENT_CORAL,
+            SyntaxKind::Literal => colors::WARNING,
+            SyntaxKind::Punctuation | SyntaxKind::Comment => colors::TEXT_SECONDARY,
+        }
+    }
+}
+
+/// Lexer for a response body, from its content type or, failing that, its first character
+fn response_syntax(response: &Response) -> BodySyntax {
+    match response.header("content-type").map(str::to_ascii_lowercase) {
+        Some(ct) if ct.contains("json") => BodySyntax::Json,
+        Some(ct) if ct.contains("xml") || ct.contains("html") => BodySyntax::Xml,
+        _ => match response.body.trim_start().chars().next() {
+            Some('{' | '[') => BodySyntax::Json,
+            Some('<') => BodySyntax::Xml,
+            _ => BodySyntax::Plain,
+        },
+    }
+}
+
+/// Read-only, selectable view of response text, highlighted like the body editor.
+/// Callers attach `on_action` and drop edit actions.
+fn response_viewer(content: &text_editor::Content, syntax: BodySyntax) -> iced::widget::TextEditor<'_, BodyHighlighter, Message> {
+    text_editor(content)
+        .highlight_with::<BodyHighlighter>(syntax, |kind, _theme| {
+            iced::advanced::text::highlighter::Format {
+                color: Some(kind.color()),
+                font: None,
+            }
+        })
+        .size(11)
+        .padding(12)
+        .height(Fill)
+        .style(|_, _| text_editor::Style {
+            background: colors::BG_PANEL.into(),
+            border: iced::Border::default(),
+            placeholder: colors::TEXT_SECONDARY,
+            value: colors::TEXT_PRIMARY,
+            selection: colors::ACCENT_PURPLE,
+        })
+}
+
+/// Which lexer the body editor highlights with
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BodySyntax {
+    Plain,
+    Json,
+    Xml,
+    /// The headers editor: only disabled lines are marked, to dim them
+    Headers,
+}
+
+/// Line-at-a-time highlighter for the request body editor. Each line is
+/// lexed on its own, so constructs spanning lines (XML comments) aren't tracked.
+struct BodyHighlighter {
+    syntax: BodySyntax,
+    current_line: usize,
+}
+
+impl iced::advanced::text::Highlighter for BodyHighlighter {
+    type Settings = BodySyntax;
+    type Highlight = SyntaxKind;
+    type Iterator<'a> = std::vec::IntoIter<(std::ops::Range<usize>, SyntaxKind)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self { syntax: *settings, current_line: 0 }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        self.syntax = *new_settings;
+        self.current_line = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.current_line = self.current_line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        self.current_line += 1;
+        match self.syntax {
+            BodySyntax::Plain => Vec::new(),
+            BodySyntax::Json => highlight_json_line(line),
+            BodySyntax::Xml => highlight_xml_line(line),
+            BodySyntax::Headers if is_disabled_header(line) => vec![(0..line.len(), SyntaxKind::Comment)],
+            BodySyntax::Headers => Vec::new(),
+        }
+        .into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current_line
+    }
+}
+
+/// Byte index just past the closing quote of the string starting at `start`
+fn quoted_end(line: &str, start: usize) -> usize {
+    let bytes = line.as_bytes();
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn highlight_json_line(line: &str) -> Vec<(std::ops::Range<usize>, SyntaxKind)> {
+    let bytes = line.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        match bytes[i] {
+            b'"' => {
+                i = quoted_end(line, i);
+                let is_key = line[i..].trim_start().starts_with(':');
+                out.push((start..i, if is_key { SyntaxKind::Key } else { SyntaxKind::String }));
+            }
+            b'-' | b'0'..=b'9' => {
+                i += 1;
+                while i < bytes.len() && matches!(bytes[i], b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') {
+                    i += 1;
+                }
+                out.push((start..i, SyntaxKind::Number));
+            }
+            b'a'..=b'z' => {
+                while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                if matches!(&line[start..i], "true" | "false" | "null") {
+                    out.push((start..i, SyntaxKind::Literal));
+                }
+            }
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                i += 1;
+                out.push((start..i, SyntaxKind::Punctuation));
+            }
+            _ => i += 1,
+        }
+    }
+    out
+}
+
+fn highlight_xml_line(line: &str) -> Vec<(std::ops::Range<usize>, SyntaxKind)> {
+    let bytes = line.as_bytes();
+    let mut out = Vec::new();
+    let mut in_tag = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        if !in_tag {
+            if bytes[i..].starts_with(b"<!--") {
+                i = bytes[i..].windows(3).position(|w| w == b"-->").map_or(bytes.len(), |end| i + end + 3);
+                out.push((start..i, SyntaxKind::Comment));
+            } else if bytes[i] == b'<' {
+                // `<name`, `</name`, `<?xml`
+                i += 1;
+                if i < bytes.len() && matches!(bytes[i], b'/' | b'?' | b'!') {
+                    i += 1;
+                }
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'>' | b'/') {
+                    i += 1;
+                }
+                out.push((start..i, SyntaxKind::Tag));
+                in_tag = true;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        match bytes[i] {
+            b'>' => {
+                i += 1;
+                out.push((start..i, SyntaxKind::Tag));
+                in_tag = false;
+            }
+            b'/' | b'?' if bytes.get(i + 1) == Some(&b'>') => {
+                i += 2;
+                out.push((start..i, SyntaxKind::Tag));
+                in_tag = false;
+            }
+            b'"' | b'\'' => {
+                i = quoted_end(line, i);
+                out.push((start..i, SyntaxKind::String));
+            }
+            b'=' => {
+                i += 1;
+                out.push((start..i, SyntaxKind::Punctuation));
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => {
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>' | b'/' | b'"' | b'\'') {
+                    i += 1;
+                }
+                if i == start {
+                    i += 1;
+                }
+                out.push((start..i, SyntaxKind::Attribute));
+            }
+        }
+    }
+    out
+}
+
+/// Extend a `data.items[0]` style path with an object key.
+/// Keys that aren't plain identifiers are written `["like this"]`.
+fn push_json_key(path: &mut String, key: &str) {
+    let plain = key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if plain {
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(key);
+    } else {
+        path.push_str(&format!("[{}]", serde_json::Value::from(key)));
+    }
+}
+
+/// Every leaf as a `data.items[0].name = "foo"` line, in document order.
+fn flatten_json(value: &serde_json::Value) -> String {
+    fn walk(value: &serde_json::Value, path: &mut String, out: &mut Vec<String>) {
+        let len = path.len();
+        match value {
+            serde_json::Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    push_json_key(path, key);
+                    walk(child, path, out);
+                    path.truncate(len);
+                }
+            }
+            serde_json::Value::Array(items) if !items.is_empty() => {
+                for (i, child) in items.iter().enumerate() {
+                    path.push_str(&format!("[{}]", i));
+                    walk(child, path, out);
+                    path.truncate(len);
+                }
+            }
+            // Scalars, plus empty objects and arrays so they aren't lost
+            leaf => {
+                let path = if path.is_empty() { "(root)" } else { path.as_str() };
+                out.push(format!("{} = {}", path, leaf));
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    walk(value, &mut String::new(), &mut lines);
+    lines.join("\n")
+}
+
+/// The JSON string, number or literal under the start of the editor's
+/// selection, if there is one there. Keys don't count.
+fn json_value_at(content: &text_editor::Content) -> Option<String> {
+    let cursor = content.cursor();
+    let start = match cursor.selection {
+        Some(other) if (other.line, other.column) < (cursor.position.line, cursor.position.column) => other,
+        _ => cursor.position,
+    };
+    let line = content.line(start.line)?;
+    highlight_json_line(&line.text)
+        .into_iter()
+        .find(|(range, kind)| {
+            range.contains(&start.column)
+                && matches!(kind, SyntaxKind::String | SyntaxKind::Number | SyntaxKind::Literal)
+        })
+        .and_then(|(range, _)| line.text.get(range))
+        .map(json_token_value)
+}
+
+/// Text to copy for a clicked JSON token: strings lose their quotes and escapes,
+/// anything else is taken as written
+fn json_token_value(token: &str) -> String {
+    if token.starts_with('"') {
+        serde_json::from_str::<String>(token).unwrap_or_else(|_| token.trim_matches('"').to_string())
+    } else {
+        token.to_string()
+    }
+}
+
+/// What clicking a span in the Body tab does
+#[derive(Debug, Clone)]
+enum SpanAction {
+    /// A scalar value and its `data.items[0]` style path
+    Value { value: String, path: String },
+    /// Fold or unfold the long string with this `expanded_strings` key
+    ToggleString(String),
+}
+
+impl SpanAction {
+    fn message(self) -> Message {
+        match self {
+            SpanAction::Value { value, path } => Message::JsonValueClicked { value, path },
+            SpanAction::ToggleString(key) => Message::ToggleLongString(key),
+        }
+    }
+}
+
+/// Colorize JSON as it would be pretty-printed. With `annotate_types`, scalar
+/// values get a dim type label after them so `"123"` and `123` can't be confused.
+/// Bodies that don't parse as JSON are returned as a single plain span.
+/// Value spans link to their value and path, so a click can copy the value and
+/// show where it sits. With `unfolded` set, long strings not in it are
+/// shortened and clicking them expands them instead.
+fn json_to_spans<'a>(
+    s: &str,
+    annotate_types: bool,
+    unfolded: Option<&std::collections::HashSet<String>>,
+) -> Vec<iced::widget::text::Span<'a, SpanAction, iced::Font>> {
+    type Spans<'a> = Vec<iced::widget::text::Span<'a, SpanAction, iced::Font>>;
+
+    fn punct<'a>(spans: &mut Spans<'a>, s: &str) {
+        spans.push(span(s.to_string()).color(SyntaxKind::Punctuation.color()));
+    }
+
+    fn walk<'a>(
+        value: &serde_json::Value,
+        path: &mut String,
+        indent: usize,
+        annotate_types: bool,
+        unfolded: Option<&std::collections::HashSet<String>>,
+        // Counts string values, to key the ones that are unfolded
+        string_index: &mut usize,
+        spans: &mut Spans<'a>,
+    ) {
+        let shown_path = if path.is_empty() { "(root)".to_string() } else { path.clone() };
+        let action = |value: String| SpanAction::Value { value, path: shown_path.clone() };
+        let label = match value {
+            serde_json::Value::Object(map) if !map.is_empty() => {
+                punct(spans, "{");
+                for (i, (key, child)) in map.iter().enumerate() {
+                    spans.push(span(format!("\n{}", "  ".repeat(indent + 1))).color(colors::TEXT_PRIMARY));
+                    spans.push(span(serde_json::Value::from(key.as_str()).to_string()).color(SyntaxKind::Key.color()));
+                    punct(spans, ":");
+                    spans.push(span(" ").color(colors::TEXT_PRIMARY));
+                    let len = path.len();
+                    push_json_key(path, key);
+                    walk(child, path, indent + 1, annotate_types, unfolded, string_index, spans);
+                    path.truncate(len);
+                    if i + 1 < map.len() {
+                        punct(spans, ",");
+                    }
+                }
+                spans.push(span(format!("\n{}", "  ".repeat(indent))).color(colors::TEXT_PRIMARY));
+                punct(spans, "}");
+                return;
+            }
+            serde_json::Value::Array(items) if !items.is_empty() => {
+                punct(spans, "[");
+                for (i, child) in items.iter().enumerate() {
+                    spans.push(span(format!("\n{}", "  ".repeat(indent + 1))).color(colors::TEXT_PRIMARY));
+                    let len = path.len();
+                    path.push_str(&format!("[{}]", i));
+                    walk(child, path, indent + 1, annotate_types, unfolded, string_index, spans);
+                    path.truncate(len);
+                    if i + 1 < items.len() {
+                        punct(spans, ",");
+                    }
+                }
+                spans.push(span(format!("\n{}", "  ".repeat(indent))).color(colors::TEXT_PRIMARY));
+                punct(spans, "]");
+                return;
+            }
+            serde_json::Value::Object(_) => {
+                punct(spans, "{}");
+                return;
+            }
+            serde_json::Value::Array(_) => {
+                punct(spans, "[]");
+                return;
+            }
+            serde_json::Value::String(s) => {
+                let written = value.to_string();
+                let key = format!("#{}", string_index);
+                *string_index += 1;
+                match unfolded {
+                    Some(unfolded) if s.chars().count() > FOLD_STRING_OVER => {
+                        let open = unfolded.contains(&key);
+                        let shown = if open { written } else { folded_string(s) };
+                        spans.push(span(shown).color(SyntaxKind::String.color()).link(action(s.clone())));
+                        spans.push(
+                            span(if open { " ‹fold›" } else { " ‹expand›" })
+                                .color(colors::TEXT_SECONDARY)
+                                .link(SpanAction::ToggleString(key)),
+                        );
+                    }
+                    _ => spans.push(span(written).color(SyntaxKind::String.color()).link(action(s.clone()))),
+                }
+                "string"
+            }
+            serde_json::Value::Number(n) => {
+                spans.push(span(n.to_string()).color(SyntaxKind::Number.color()).link(action(n.to_string())));
+                "number"
+            }
+            serde_json::Value::Bool(b) => {
+                spans.push(span(b.to_string()).color(SyntaxKind::Literal.color()).link(action(b.to_string())));
+                "bool"
+            }
+            serde_json::Value::Null => {
+                spans.push(span("null").color(SyntaxKind::Literal.color()).link(action("null".to_string())));
+                "null"
+            }
+        };
+        if annotate_types {
+            spans.push(span(format!(" ‹{}›", label)).color(colors::TEXT_SECONDARY));
+        }
+    }
+
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(s) else {
+        return vec![span(s.to_string()).color(colors::TEXT_PRIMARY)];
+    };
+    let mut spans = Vec::new();
+    walk(&value, &mut String::new(), 0, annotate_types, unfolded, &mut 0, &mut spans);
+    spans
+}
+
+/// What a recognised GraphQL or JSON:API response carries, for the banner
+/// above the Body tab
+#[derive(Debug, Clone)]
+struct Envelope {
+    kind: &'static str,
+    summary: String,
+    errors: Vec<String>,
+}
+
+fn detect_envelope(body: &str) -> Option<Envelope> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let object = value.as_object()?;
+    let only = |allowed: &[&str]| object.keys().all(|k| allowed.contains(&k.as_str()));
+    let errors = |field: &str| -> Vec<String> {
+        object.get(field).and_then(|e| e.as_array()).map(|errors| {
+            errors.iter().map(|error| {
+                // GraphQL uses message/path, JSON:API title/detail/source
+                let text = ["message", "detail", "title"].iter()
+                    .find_map(|k| error.get(*k).and_then(|v| v.as_str()))
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string());
+                let path = match error.get("path").or_else(|| error.pointer("/source/pointer")) {
+                    Some(serde_json::Value::Array(segments)) => segments.iter()
+                        .map(|s| s.as_str().map_or_else(|| s.to_string(), str::to_string))
+                        .collect::<Vec<_>>()
+                        .join("."),
+                    Some(serde_json::Value::String(pointer)) => pointer.clone(),
+                    _ => String::new(),
+                };
+                if path.is_empty() { text } else { format!("{} (at {})", text, path) }
+            }).collect()
+        }).unwrap_or_default()
+    };
+    let resource_type = |item: &serde_json::Value| {
+        item.get("id").is_some().then(|| item.get("type").and_then(|t| t.as_str())).flatten().map(str::to_string)
+    };
+
+    let data = object.get("data");
+    let data_items: Vec<&serde_json::Value> = match data {
+        Some(serde_json::Value::Array(items)) => items.iter().collect(),
+        Some(item @ serde_json::Value::Object(_)) => vec![item],
+        _ => Vec::new(),
+    };
+    let is_json_api = only(&["data", "errors", "meta", "jsonapi", "links", "included"])
+        && (object.contains_key("jsonapi")
+            || object.contains_key("included")
+            || (!data_items.is_empty() && data_items.iter().all(|item| resource_type(item).is_some())));
+    if is_json_api {
+        let count_types = |items: &[&serde_json::Value]| {
+            let mut counts: Vec<(String, usize)> = Vec::new();
+            for ty in items.iter().filter_map(|item| resource_type(item)) {
+                match counts.iter_mut().find(|(t, _)| *t == ty) {
+                    Some((_, n)) => *n += 1,
+                    None => counts.push((ty, 1)),
+                }
+            }
+            counts.iter().map(|(t, n)| format!("{} {}", n, t)).collect::<Vec<_>>().join(", ")
+        };
+        let mut summary = match data {
+            Some(_) if data_items.is_empty() => "data: none".to_string(),
+            Some(_) => format!("data: {}", count_types(&data_items)),
+            None => "no data".to_string(),
+        };
+        if let Some(included) = object.get("included").and_then(|i| i.as_array()) {
+            let included: Vec<&serde_json::Value> = included.iter().collect();
+            summary.push_str(&format!(" · included: {}", count_types(&included)));
+        }
+        return Some(Envelope { kind: "JSON:API", summary, errors: errors("errors") });
+    }
+
+    // A bare `{"data": [...]}` is a common REST wrapper, not GraphQL
+    let graphql_data = matches!(data, Some(serde_json::Value::Object(_) | serde_json::Value::Null));
+    if only(&["data", "errors", "extensions"]) && (graphql_data || object.contains_key("errors")) {
+        let fields: Vec<&str> = data.and_then(|d| d.as_object()).map(|d| d.keys().map(String::as_str).collect()).unwrap_or_default();
+        let summary = if fields.is_empty() { "no data".to_string() } else { format!("data: {}", fields.join(", ")) };
+        return Some(Envelope { kind: "GraphQL", summary, errors: errors("errors") });
+    }
+    None
+}
+
+/// At least two different kinds of Markdown block syntax, and not JSON
+fn looks_like_markdown(body: &str) -> bool {
+    if body.trim_start().starts_with(['{', '[', '<']) {
+        return false;
+    }
+    let (mut heading, mut fence, mut list, mut quote) = (false, false, false, false);
+    for line in body.lines().take(200) {
+        let line = line.trim_start();
+        heading |= line.starts_with('#') && line.trim_start_matches('#').starts_with(' ');
+        fence |= line.starts_with("```");
+        list |= line.starts_with("- ") || line.starts_with("* ") || ordered_list_marker(line).is_some();
+        quote |= line.starts_with("> ");
+    }
+    [heading, fence, list, quote].iter().filter(|&&seen| seen).count() >= 2
+}
+
+/// `12. item` -> `("12.", "item")`
+fn ordered_list_marker(line: &str) -> Option<(&str, &str)> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = line.get(digits..)?;
+    (digits > 0 && rest.starts_with(". ")).then(|| (&line[..digits + 1], &rest[2..]))
+}
+
+/// Inline Markdown: `code`, **bold**, *emphasis* and [links](url).
+/// `_` is left alone so snake_case identifiers survive.
+fn markdown_spans<'a>(line: &str, base: iced::Color, font: Font) -> Vec<iced::widget::text::Span<'a, Font>> {
+    let bold = Font { weight: iced::font::Weight::Bold, ..font };
+    let italic = Font { style: iced::font::Style::Italic, ..font };
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = line;
+
+    while let Some(ch) = rest.chars().next() {
+        let styled = match ch {
+            '`' => rest[1..].find('`').map(|end| {
+                (span(rest[1..1 + end].to_string()).color(colors::ACCENT_CORAL).font(font), end + 2)
+            }),
+            '*' if rest.starts_with("**") => rest[2..].find("**").filter(|&end| end > 0).map(|end| {
+                (span(rest[2..2 + end].to_string()).color(base).font(bold), end + 4)
+            }),
+            '*' if !rest[1..].starts_with(' ') => rest[1..].find('*').filter(|&end| end > 0).map(|end| {
+                (span(rest[1..1 + end].to_string()).color(base).font(italic), end + 2)
+            }),
+            '[' => rest.find("](").and_then(|mid| {
+                let close = rest[mid..].find(')')? + mid;
+                Some((span(rest[1..mid].to_string()).color(colors::ACCENT_PURPLE).underline(true).font(font), close + 1))
+            }),
+            _ => None,
+        };
+        match styled {
+            Some((styled, consumed)) => {
+                if !plain.is_empty() {
+                    spans.push(span(std::mem::take(&mut plain)).color(base).font(font));
+                }
+                spans.push(styled);
+                rest = &rest[consumed..];
+            }
+            None => {
+                plain.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(span(plain).color(base).font(font));
+    }
+    spans
+}
+
+/// Render Markdown as widgets: headings, paragraphs, lists, quotes, rules
+/// and fenced code blocks. Unsupported syntax falls through as text.
+fn render_markdown<'a>(s: &str) -> Element<'a, Message> {
+    let font = Font::DEFAULT;
+    let mut blocks: Vec<Element<'a, Message>> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut lines = s.lines();
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Element<'a, Message>>| {
+        if !paragraph.is_empty() {
+            let joined = paragraph.join(" ");
+            blocks.push(rich_text(markdown_spans(&joined, colors::TEXT_PRIMARY, font)).size(12).into());
+            paragraph.clear();
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let indent = (line.len() - trimmed.len()) as f32 * 6.0;
+        let heading_level = trimmed.chars().take_while(|&c| c == '#').count();
+
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            let code: Vec<&str> = lines.by_ref().take_while(|l| !l.trim_start().starts_with("```")).collect();
+            blocks.push(
+                container(text(code.join("\n")).size(11).color(colors::TEXT_PRIMARY))
+                    .padding(8)
+                    .width(Fill)
+                    .style(|_| container::Style {
+                        background: Some(colors::BG_ELEVATED.into()),
+                        ..Default::default()
+                    })
+                    .into(),
+            );
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if (1..=6).contains(&heading_level) && trimmed[heading_level..].starts_with(' ') {
+            flush(&mut paragraph, &mut blocks);
+            let size = match heading_level { 1 => 20, 2 => 17, 3 => 15, _ => 13 };
+            let bold = Font { weight: iced::font::Weight::Bold, ..font };
+            blocks.push(rich_text(markdown_spans(trimmed[heading_level..].trim(), colors::TEXT_PRIMARY, bold)).size(size).into());
+        } else if trimmed.len() >= 3 && ["-", "*", "_"].iter().any(|c| trimmed.replace(c, "").is_empty()) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(
+                container(text(""))
+                    .width(Fill)
+                    .height(1)
+                    .style(|_| container::Style {
+                        background: Some(colors::BORDER.into()),
+                        ..Default::default()
+                    })
+                    .into(),
+            );
+        } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")).or_else(|| trimmed.strip_prefix("+ ")) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(
+                row![
+                    text("•").size(12).color(colors::TEXT_SECONDARY),
+                    rich_text(markdown_spans(item, colors::TEXT_PRIMARY, font)).size(12),
+                ]
+                .spacing(6)
+                .padding(Padding { left: indent + 4.0, ..Padding::ZERO })
+                .into(),
+            );
+        } else if let Some((marker, item)) = ordered_list_marker(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(
+                row![
+                    text(marker.to_string()).size(12).color(colors::TEXT_SECONDARY),
+                    rich_text(markdown_spans(item, colors::TEXT_PRIMARY, font)).size(12),
+                ]
+                .spacing(6)
+                .padding(Padding { left: indent + 4.0, ..Padding::ZERO })
+                .into(),
+            );
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(
+                row![
+                    text("│").size(12).color(colors::BORDER),
+                    rich_text(markdown_spans(quote.trim_start(), colors::TEXT_SECONDARY, font)).size(12),
+                ]
+                .spacing(8)
+                .into(),
+            );
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    Column::from_vec(blocks).spacing(10).width(Fill).into()
+}
+
+/// Trim header names/values and align the values into one column.
+/// Lines that aren't `Name: value` are kept as they are.
+fn normalize_headers(headers: &str) -> String {
+    let parsed: Vec<Option<(&str, &str)>> = headers.lines()
+        .map(|line| {
+            line.split_once(':')
+                .map(|(name, value)| (name.trim(), value.trim()))
+                .filter(|(name, _)| !name.is_empty() && !name.contains(char::is_whitespace))
+        })
+        .collect();
+    let width = parsed.iter().flatten().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    headers.lines()
+        .zip(&parsed)
+        .map(|(line, header)| match header {
+            Some((name, value)) => format!("{:<width$} {}", format!("{}:", name), value, width = width + 1)
+                .trim_end()
+                .to_string(),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `content-type` -> `Content-Type`, `x-REQUEST-id` -> `X-Request-Id`
+fn canonical_header_name(name: &str) -> String {
+    name.split('-')
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Rewrite every header name, disabled ones included, in canonical casing.
+/// Values, spacing and lines that aren't headers are left exactly as they are.
+fn canonicalize_headers(headers: &str) -> String {
+    headers.split('\n')
+        .map(|line| {
+            // Keep a disabled line's `#` and indentation in front of the name
+            let body = line.trim_start().trim_start_matches('#').trim_start();
+            let prefix = &line[..line.len() - body.len()];
+            match body.split_once(':') {
+                Some((name, value)) if is_header_name(name.trim_end()) => {
+                    let padding = &name[name.trim_end().len()..];
+                    format!("{}{}{}:{}", prefix, canonical_header_name(name.trim_end()), padding, value)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Trim, sort by key and drop exact duplicate `key=value` lines
+fn normalize_params(params: &str) -> String {
+    let mut lines: Vec<&str> = params.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    lines.retain(|l| seen.insert(*l));
+    // Stable, so repeated keys keep their relative order
+    lines.sort_by_key(|l| l.split_once('=').map_or(*l, |(k, _)| k));
+    lines.join("\n")
+}
+
+/// Decode a hex string like `DE AD BE EF` or `0xdeadbeef` into bytes.
+/// Whitespace, commas and colons between bytes are ignored.
+fn parse_hex(input: &str) -> Result<Vec<u8>, String> {
+    let mut digits = Vec::new();
+    for group in input.split(|c: char| c.is_whitespace() || c == ',' || c == ':') {
+        let group = group.strip_prefix("0x").or_else(|| group.strip_prefix("0X")).unwrap_or(group);
+        for c in group.chars() {
+            let digit = c.to_digit(16).ok_or_else(|| format!("'{}' is not a hex digit", c))?;
+            digits.push(digit as u8);
+        }
+    }
+    if digits.len() % 2 != 0 {
+        return Err(format!("odd number of hex digits ({})", digits.len()));
+    }
+    Ok(digits.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect())
+}
+
+/// Pair up the lines of two texts for side-by-side display. Lines common to both
+/// (by longest common subsequence) share a row; a run of removals followed by
+/// additions is paired row by row so edited lines sit next to each other.
+/// Very large inputs fall back to pairing by position.
+fn align_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Option<&'a str>, Option<&'a str>)> {
+    const MAX_CELLS: usize = 4_000_000;
+
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let mut rows: Vec<(Option<&str>, Option<&str>)> = old[..prefix].iter().map(|l| (Some(*l), Some(*l))).collect();
+    let pair_run = |rows: &mut Vec<(Option<&'a str>, Option<&'a str>)>, removed: &[&'a str], added: &[&'a str]| {
+        for i in 0..removed.len().max(added.len()) {
+            rows.push((removed.get(i).copied(), added.get(i).copied()));
+        }
+    };
+
+    if (a.len() + 1).saturating_mul(b.len() + 1) > MAX_CELLS {
+        pair_run(&mut rows, a, b);
+    } else {
+        // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+        let width = b.len() + 1;
+        let mut lcs = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        let (mut run_a, mut run_b) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                pair_run(&mut rows, &a[run_a..i], &b[run_b..j]);
+                rows.push((Some(a[i]), Some(b[j])));
+                i += 1;
+                j += 1;
+                (run_a, run_b) = (i, j);
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        pair_run(&mut rows, &a[run_a..], &b[run_b..]);
+    }
+
+    rows.extend(old[old.len() - suffix..].iter().map(|l| (Some(*l), Some(*l))));
+    rows
+}
+
+/// Content type for an uploaded file, guessed from its extension
+fn file_content_type(path: &str) -> &'static str {
+    mime_guess::from_path(path).first_raw().unwrap_or("application/octet-stream")
+}
+
+fn is_object_array(body: &str) -> bool {
+    matches!(
+        serde_json::from_str::<serde_json::Value>(body),
+        Ok(serde_json::Value::Array(items)) if !items.is_empty() && items.iter().all(|v| v.is_object())
+    )
+}
+
+/// Render a JSON array of objects as delimited rows for pasting into a spreadsheet.
+/// Columns are the union of all object keys; missing keys become empty cells
+/// and nested values are written as compact JSON.
+fn json_to_table(body: &str, delimiter: char) -> Option<String> {
+    let serde_json::Value::Array(items) = serde_json::from_str::<serde_json::Value>(body).ok()? else {
+        return None;
+    };
+    let rows: Vec<&serde_json::Map<String, serde_json::Value>> =
+        items.iter().map(|v| v.as_object()).collect::<Option<_>>()?;
+    if rows.is_empty() {
+        return None;
+    }
+
+    let mut columns: Vec<&str> = Vec::new();
+    for row in &rows {
+        for key in row.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let escape = |field: &str| {
+        if field.contains(delimiter) || field.contains('"') || field.contains('\n') || field.contains('\r') {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+    let separator = delimiter.to_string();
+
+    let mut lines = vec![columns.iter().map(|c| escape(c)).collect::<Vec<_>>().join(&separator)];
+    for row in rows {
+        let cells: Vec<String> = columns.iter()
+            .map(|column| match row.get(*column) {
+                None | Some(serde_json::Value::Null) => String::new(),
+                Some(serde_json::Value::String(s)) => escape(s),
+                Some(value) => escape(&value.to_string()),
+            })
+            .collect();
+        lines.push(cells.join(&separator));
+    }
+    Some(lines.join("\n"))
+}
+
+/// Inferred structure of a JSON value
+#[derive(Debug, Clone, PartialEq)]
+enum Schema {
+    Null,
+    Bool,
+    Integer,
+    Number,
+    String,
+    /// Element type of an array; `Unknown` for empty arrays
+    Array(Box<Schema>),
+    Object(Vec<SchemaField>),
+    Union(Vec<Schema>),
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SchemaField {
+    name: String,
+    schema: Schema,
+    /// Missing from some of the array elements this object was merged from
+    optional: bool,
+}
+
+fn infer_schema(value: &serde_json::Value) -> Schema {
+    use serde_json::Value;
+    match value {
+        Value::Null => Schema::Null,
+        Value::Bool(_) => Schema::Bool,
+        Value::Number(n) if n.is_f64() => Schema::Number,
+        Value::Number(_) => Schema::Integer,
+        Value::String(_) => Schema::String,
+        Value::Array(items) => Schema::Array(Box::new(
+            items.iter().map(infer_schema).reduce(merge_schema).unwrap_or(Schema::Unknown),
+        )),
+        Value::Object(map) => Schema::Object(
+            map.iter()
+                .map(|(name, value)| SchemaField {
+                    name: name.clone(),
+                    schema: infer_schema(value),
+                    optional: false,
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Combine the schemas of two values seen in the same position (e.g. two array elements)
+fn merge_schema(a: Schema, b: Schema) -> Schema {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (Schema::Unknown, other) | (other, Schema::Unknown) => other,
+        (Schema::Integer, Schema::Number) | (Schema::Number, Schema::Integer) => Schema::Number,
+        (Schema::Array(a), Schema::Array(b)) => Schema::Array(Box::new(merge_schema(*a, *b))),
+        (Schema::Object(a), Schema::Object(b)) => {
+            let mut fields: Vec<SchemaField> = a.into_iter()
+                .map(|mut field| {
+                    field.optional |= !b.iter().any(|f| f.name == field.name);
+                    field
+                })
+                .collect();
+            for field in b {
+                match fields.iter_mut().find(|f| f.name == field.name) {
+                    Some(existing) => {
+                        existing.schema = merge_schema(existing.schema.clone(), field.schema);
+                        existing.optional |= field.optional;
+                    }
+                    None => fields.push(SchemaField { optional: true, ..field }),
+                }
+            }
+            Schema::Object(fields)
+        }
+        (a, b) => {
+            let mut variants = Vec::new();
+            for schema in [a, b] {
+                match schema {
+                    Schema::Union(inner) => variants.extend(inner),
+                    other => variants.push(other),
+                }
+            }
+            // Fold variants of the same kind together (objects with objects etc.)
+            let mut merged: Vec<Schema> = Vec::new();
+            for variant in variants {
+                match merged.iter().position(|m| same_kind(m, &variant)) {
+                    Some(i) => merged[i] = merge_schema(merged[i].clone(), variant),
+                    None => merged.push(variant),
+                }
+            }
+            if merged.len() == 1 { merged.remove(0) } else { Schema::Union(merged) }
+        }
+    }
+}
+
+fn same_kind(a: &Schema, b: &Schema) -> bool {
+    matches!(
+        (a, b),
+        (Schema::Object(_), Schema::Object(_))
+            | (Schema::Array(_), Schema::Array(_))
+            | (Schema::Integer | Schema::Number, Schema::Integer | Schema::Number)
+    ) || a == b
+}
+
+/// Render a schema as colored text fragments, one field per line
+fn render_schema(schema: &Schema) -> Vec<(String, iced::Color)> {
+    fn walk(schema: &Schema, indent: usize, out: &mut Vec<(String, iced::Color)>) {
+        let mut push = |s: &str, color| out.push((s.to_string(), color));
+        match schema {
+            Schema::Null => push("null", colors::WARNING),
+            Schema::Bool => push("boolean", colors::WARNING),
+            Schema::Integer => push("integer", colors::ACCENT_CORAL),
+            Schema::Number => push("number", colors::ACCENT_CORAL),
+            Schema::String => push("string", colors::SUCCESS),
+            Schema::Unknown => push("unknown", colors::TEXT_SECONDARY),
+            Schema::Array(element) => {
+                push("[", colors::TEXT_SECONDARY);
+                walk(element, indent, out);
+                out.push(("]".to_string(), colors::TEXT_SECONDARY));
+            }
+            Schema::Union(variants) => {
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        out.push((" | ".to_string(), colors::TEXT_SECONDARY));
+                    }
+                    walk(variant, indent, out);
+                }
+            }
+            Schema::Object(fields) if fields.is_empty() => push("{}", colors::TEXT_SECONDARY),
+            Schema::Object(fields) => {
+                push("{\n", colors::TEXT_SECONDARY);
+                for field in fields {
+                    out.push(("  ".repeat(indent + 1), colors::TEXT_PRIMARY));
+                    out.push((field.name.clone(), colors::ACCENT_PURPLE));
+                    if field.optional {
+                        out.push(("?".to_string(), colors::TEXT_SECONDARY));
+                    }
+                    out.push((": ".to_string(), colors::TEXT_SECONDARY));
+                    walk(&field.schema, indent + 1, out);
+                    out.push(("\n".to_string(), colors::TEXT_PRIMARY));
+                }
+                out.push((format!("{}}}", "  ".repeat(indent)), colors::TEXT_SECONDARY));
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(schema, 0, &mut out);
+    out
+}
+
+/// `serde` structs for a schema, the root named `Response`. Nested structs are
+/// named after their field (singular for array elements), optional fields become
+/// `Option`, and keys that aren't snake_case identifiers get a `rename`.
+fn rust_types(schema: &Schema) -> String {
+    struct Generator {
+        structs: Vec<String>,
+        names: Vec<String>,
+    }
+
+    impl Generator {
+        fn unique_name(&mut self, base: &str) -> String {
+            let mut name = base.to_string();
+            let mut n = 2;
+            while self.names.contains(&name) {
+                name = format!("{}{}", base, n);
+                n += 1;
+            }
+            self.names.push(name.clone());
+            name
+        }
+
+        /// The Rust type for `schema`, emitting structs for any objects in it
+        fn type_of(&mut self, schema: &Schema, name: &str) -> String {
+            match schema {
+                Schema::Bool => "bool".to_string(),
+                Schema::Integer => "i64".to_string(),
+                Schema::Number => "f64".to_string(),
+                Schema::String => "String".to_string(),
+                Schema::Null => "Option<serde_json::Value>".to_string(),
+                // Only seen as the element of an empty array
+                Schema::Unknown => "serde_json::Value".to_string(),
+                Schema::Array(element) => format!("Vec<{}>", self.type_of(element, &singular(name))),
+                Schema::Object(fields) => self.emit_struct(fields, name),
+                Schema::Union(variants) => match variants.as_slice() {
+                    [Schema::Null, other] | [other, Schema::Null] => {
+                        let inner = self.type_of(other, name);
+                        if inner.starts_with("Option<") { inner } else { format!("Option<{}>", inner) }
+                    }
+                    _ => "serde_json::Value".to_string(),
+                },
+            }
+        }
+
+        fn emit_struct(&mut self, fields: &[SchemaField], name: &str) -> String {
+            let name = match pascal_case(name) {
+                // `Self` can't name a type
+                name if name == "Self" => "SelfType".to_string(),
+                name => name,
+            };
+            let name = self.unique_name(&name);
+            // Reserve the slot so a struct comes before the ones nested in it
+            let slot = self.structs.len();
+            self.structs.push(String::new());
+
+            let mut field_names: Vec<String> = Vec::new();
+            let mut body = String::new();
+            for field in fields {
+                let mut ident = snake_case(&field.name);
+                // These can't be raw identifiers, so they get a suffix and a rename
+                if RUST_PATH_KEYWORDS.contains(&ident.as_str()) {
+                    ident.push('_');
+                }
+                let base = ident.clone();
+                let mut n = 2;
+                while field_names.contains(&ident) {
+                    ident = format!("{}_{}", base, n);
+                    n += 1;
+                }
+                field_names.push(ident.clone());
+
+                let mut ty = self.type_of(&field.schema, &field.name);
+                if field.optional && !ty.starts_with("Option<") {
+                    ty = format!("Option<{}>", ty);
+                }
+                if ident != field.name {
+                    body.push_str(&format!("    #[serde(rename = {:?})]\n", field.name));
+                }
+                let ident = if RUST_KEYWORDS.contains(&ident.as_str()) { format!("r#{}", ident) } else { ident };
+                body.push_str(&format!("    pub {}: {},\n", ident, ty));
+            }
+            self.structs[slot] = format!(
+                "#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n{}}}\n",
+                name, body,
+            );
+            name
+        }
+    }
+
+    let mut generator = Generator { structs: Vec::new(), names: Vec::new() };
+    let root = generator.type_of(schema, "Response");
+    let mut out = String::from("use serde::{Deserialize, Serialize};\n\n");
+    // Only an object root is already called `Response`
+    if !matches!(schema, Schema::Object(_)) {
+        out.push_str(&format!("pub type Response = {};\n\n", root));
+    }
+    out.push_str(&generator.structs.join("\n"));
+    format!("{}\n", out.trim_end())
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box",
+    "do", "final", "gen", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords that `r#` doesn't make usable as field names
+const RUST_PATH_KEYWORDS: &[&str] = &["crate", "self", "super"];
+
+/// `userId`, `user-id` and `UserID` all become `user_id`
+fn snake_case(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_alphanumeric() {
+            // A new word starts at an upper-case letter after a lower-case one,
+            // or at the last capital of an acronym (`IDToken` -> `id_token`)
+            let boundary = c.is_uppercase()
+                && i > 0
+                && (chars[i - 1].is_lowercase()
+                    || chars[i - 1].is_numeric()
+                    || (chars[i - 1].is_uppercase() && chars.get(i + 1).is_some_and(|n| n.is_lowercase())));
+            if boundary && !out.ends_with('_') && !out.is_empty() {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    let out = out.trim_end_matches('_').to_string();
+    match out.chars().next() {
+        None => "field".to_string(),
+        Some(c) if c.is_numeric() => format!("field_{}", out),
+        Some(_) => out,
+    }
+}
+
+fn pascal_case(key: &str) -> String {
+    let name: String = snake_case(key)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_alphabetic() => name,
+        _ => format!("Type{}", name),
+    }
+}
+
+/// Rough English singular for naming array element structs: `items` -> `item`
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with("ss") || name.ends_with("us") {
+        format!("{}Item", name)
+    } else if let Some(stem) = name.strip_suffix('s').filter(|s| !s.is_empty()) {
+        stem.to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
+
+/// One shareable line: `GET https://... → 200 OK 142ms 3.4 KB`
+fn response_summary(method: Method, url: &str, response: &Response) -> String {
+    format!(
+        "{} {} → {} {} {}ms {}",
+        method,
+        url,
+        response.status,
+        response.status_text,
+        response.duration.as_millis(),
+        format_size(response.size),
+    )
+}
+
+/// `12 lines · 340 chars · 352 B`
+fn text_stats(text: &str, bytes: usize) -> String {
+    // An empty editor is still one (empty) line
+    let lines = text.lines().count().max(1) + usize::from(text.ends_with('\n'));
+    format!("{} lines · {} chars · {}", lines, text.chars().count(), format_size(bytes))
+}
+
+/// The members of an RFC 9457 (formerly 7807) problem details response
+#[derive(Debug, Clone)]
+struct Problem {
+    title: String,
+    status: Option<u64>,
+    kind: Option<String>,
+    detail: Option<String>,
+    instance: Option<String>,
+    /// Any other top-level members, as compact JSON
+    extensions: Vec<(String, String)>,
+}
+
+/// A problem details object: declared by an `application/problem+json`
+/// content type, or an error response whose body has a `title` alongside
+/// `type` or `status`
+fn detect_problem(response: &Response) -> Option<Problem> {
+    let value: serde_json::Value = serde_json::from_str(&response.body).ok()?;
+    let object = value.as_object()?;
+    let string = |key: &str| object.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let declared = response.header("content-type").is_some_and(|ct| ct.to_ascii_lowercase().contains("problem+json"));
+    let standard = string("title").is_some()
+        && (string("type").is_some() || object.get("status").is_some_and(|s| s.is_u64()))
+        && response.is_http_error();
+    if !declared && !standard {
+        return None;
+    }
+    let standard_members = ["type", "title", "status", "detail", "instance"];
+    Some(Problem {
+        title: string("title").unwrap_or_else(|| response.status_text.clone()),
+        status: object.get("status").and_then(|s| s.as_u64()),
+        kind: string("type").filter(|t| t != "about:blank"),
+        detail: string("detail"),
+        instance: string("instance"),
+        extensions: object.iter()
+            .filter(|(k, _)| !standard_members.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), v.as_str().map_or_else(|| v.to_string(), str::to_string)))
+            .collect(),
+    })
+}
+
+/// Problem title in red with the detail below, then the other members
+fn view_problem<'a>(problem: Problem) -> Element<'a, Message> {
+    let mut heading = row![text(problem.title).size(13).color(colors::ERROR)]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+    if let Some(status) = problem.status {
+        heading = heading.push(text(status.to_string()).size(10).color(colors::TEXT_SECONDARY));
+    }
+    let mut card = column![heading].spacing(4);
+    if let Some(detail) = problem.detail {
+        card = card.push(text(detail).size(11).color(colors::TEXT_PRIMARY));
+    }
+    let member = |name: &str, value: &str| {
+        text(format!("{}: {}", name, truncate_str(value, 200))).size(10).color(colors::TEXT_SECONDARY)
+    };
+    if let Some(kind) = problem.kind {
+        card = card.push(member("type", &kind));
+    }
+    if let Some(instance) = problem.instance {
+        card = card.push(member("instance", &instance));
+    }
+    for (name, value) in &problem.extensions {
+        card = card.push(member(name, value));
+    }
+    container(card)
+        .padding([8, 12])
+        .width(Fill)
+        .style(|_| container::Style {
+            background: Some(colors::BG_ELEVATED.into()),
+            border: iced::Border { color: colors::ERROR, width: 1.0, radius: 0.0.into() },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Envelope kind and contents, with its errors listed first in red
+fn view_envelope<'a>(envelope: Envelope) -> Element<'a, Message> {
+    let mut banner = column![].spacing(4);
+    if !envelope.errors.is_empty() {
+        banner = banner.push(
+            text(format!("{} errors ({})", envelope.kind, envelope.errors.len()))
+                .size(10)
+                .color(colors::ERROR),
+        );
+        for error in envelope.errors {
+            banner = banner.push(text(format!("• {}", error)).size(11).color(colors::ERROR));
+        }
+    }
+    banner = banner.push(
+        text(format!("{} · {}", envelope.kind, envelope.summary))
+            .size(10)
+            .color(colors::TEXT_SECONDARY),
+    );
+    container(banner)
+        .padding([8, 12])
+        .width(Fill)
+        .style(|_| container::Style {
+            background: Some(colors::BG_ELEVATED.into()),
+            ..Default::default()
+        })
+        .into()
+}
+
+fn stats_footer<'a>(stats: String) -> Element<'a, Message> {
+    container(text(stats).size(9).color(colors::TEXT_SECONDARY))
+        .padding([4, 12])
+        .width(Fill)
+        .align_x(iced::alignment::Horizontal::Right)
+        .into()
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// `20261014-153012-123`, UTC
+fn archive_timestamp(time: std::time::SystemTime) -> String {
+    let since_epoch = time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60, since_epoch.subsec_millis(),
+    )
+}
+
+/// Whether a `Content-Type` is something the body views can show as text
+fn is_text_mime(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    mime.starts_with("text/")
+        || ["json", "xml", "javascript", "x-www-form-urlencoded", "yaml", "graphql"].iter().any(|t| mime.contains(t))
+}
+
+/// Suggested file name for saving a response: the `Content-Disposition`
+/// filename when there is one, else the last segment of the URL path. An
+/// extension is added from the `Content-Type` when the name has none.
+fn response_file_name(response: &Response) -> String {
+    let from_header = response.header("content-disposition").and_then(content_disposition_filename);
+    let from_url = || {
+        let path = response.url.split_once("://").map_or(response.url.as_str(), |(_, rest)| rest);
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let segment = path.split_once('/').map_or("", |(_, p)| p).rsplit('/').next().unwrap_or("");
+        safe_file_name(&String::from_utf8_lossy(&percent_decode(segment)))
+    };
+    let mut name = from_header.or_else(from_url).unwrap_or_else(|| "response".to_string());
+    if !name.contains('.') {
+        let mime = response.header("content-type").unwrap_or("").split(';').next().unwrap_or("").trim();
+        let extension = match mime {
+            // mime_guess lists these under rarely-used extensions first
+            "text/plain" => Some("txt"),
+            "application/json" => Some("json"),
+            "text/html" => Some("html"),
+            _ => mime_guess::get_mime_extensions_str(mime).and_then(|e| e.first().copied()),
+        };
+        if let Some(extension) = extension {
+            name = format!("{}.{}", name, extension);
+        }
+    }
+    name
+}
+
+/// The filename from a `Content-Disposition` value. `filename*` (RFC 5987,
+/// `UTF-8''caf%C3%A9.pdf`) wins over a plain `filename`.
+fn content_disposition_filename(value: &str) -> Option<String> {
+    let mut plain = None;
+    let mut extended = None;
+    for param in split_header_params(value) {
+        let Some((key, raw)) = param.split_once('=') else { continue };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                let mut parts = raw.trim().trim_matches('"').splitn(3, '\'');
+                let (charset, _language, encoded) = (parts.next()?, parts.next()?, parts.next()?);
+                let bytes = percent_decode(encoded);
+                extended = if charset.eq_ignore_ascii_case("iso-8859-1") {
+                    Some(bytes.iter().map(|&b| b as char).collect())
+                } else {
+                    String::from_utf8(bytes).ok()
+                };
+            }
+            "filename" => {
+                let raw = raw.trim();
+                plain = Some(match raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) {
+                    Some(quoted) => quoted.replace("\\\"", "\"").replace("\\\\", "\\"),
+                    None => raw.to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+    extended.or(plain).as_deref().and_then(safe_file_name)
+}
+
+/// `;`-separated header parameters, ignoring separators inside quotes
+fn split_header_params(value: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                params.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&value[start..]);
+    params
+}
+
+/// The last path component of a suggested name, so a server can't point the
+/// save dialog at another directory
+fn safe_file_name(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or("").trim();
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    (!name.is_empty() && name != "." && name != "..").then_some(name)
+}
+
+/// Host and path of `url` squeezed into a short, filesystem-safe name
+fn archive_name(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let mut name = String::new();
+    for ch in rest.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '-' || ch == '.' {
+            name.push(ch);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name: String = name.trim_matches(['_', '.']).chars().take(60).collect();
+    if name.is_empty() { "response".to_string() } else { name }
+}
+
+/// Write the body plus a `.meta.json` sidecar (request line, status, timing,
+/// headers) to `dir`, named by time, method and URL
+fn archive_response(dir: &std::path::Path, method: Method, response: &Response) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let content_type = response.header("content-type").unwrap_or("");
+    let extension = if content_type.contains("json") {
+        "json"
+    } else if content_type.contains("html") {
+        "html"
+    } else if content_type.contains("xml") {
+        "xml"
+    } else if response.raw_body.is_some() {
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        mime_guess::get_mime_extensions_str(mime).and_then(|e| e.first().copied()).unwrap_or("bin")
+    } else {
+        "txt"
+    };
+    let now = std::time::SystemTime::now();
+    let stem = format!("{}-{}-{}", archive_timestamp(now), method, archive_name(&response.url));
+    // Same millisecond and URL: add a counter rather than overwrite
+    let mut stem_unique = stem.clone();
+    let mut n = 1;
+    while dir.join(format!("{}.{}", stem_unique, extension)).exists() {
+        stem_unique = format!("{}-{}", stem, n);
+        n += 1;
+    }
+
+    let metadata = serde_json::json!({
+        "saved_at": archive_timestamp(now),
+        "method": method.to_string(),
+        "url": response.url,
+        "status": response.status,
+        "status_text": response.status_text,
+        "duration_ms": response.duration.as_millis() as u64,
+        "size": response.size,
+        "request": mask_credentials(&response.raw_request),
+        "headers": response.headers.iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect::<serde_json::Map<_, _>>(),
+    });
+    // Binary bodies are written as received; `body` is only a lossy copy of them
+    let body = response.raw_body.as_deref().unwrap_or(response.body.as_bytes());
+    std::fs::write(dir.join(format!("{}.{}", stem_unique, extension)), body).map_err(|e| e.to_string())?;
+    let metadata = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(format!("{}.meta.json", stem_unique)), metadata).map_err(|e| e.to_string())
+}
+
+fn history_path() -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|d| d.join("badgateway").join("history.json"))
+}
+
+fn load_history() -> Vec<HistoryEntry> {
+    if let Some(path) = history_path()
+        && let Ok(data) = std::fs::read_to_string(&path)
+        && let Ok(history) = serde_json::from_str(&data)
+    {
+        return history;
+    }
+    Vec::new()
+}
+
+fn save_history(history: &[HistoryEntry]) {
+    if let Some(path) = history_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(history) {
+            let _ = std::fs::write(path, data);
+        }
+    }
+}
 
-        container(panel)
-            .width(Fill)
-            .height(Fill)
-            .style(|_| container::Style {
-                background: Some(colors::BG_PANEL.into()),
-                ..Default::default()
-            })
-            .into()
+/// Notes about skipped editor lines, the first few shown
+fn view_line_warnings<'a>(warnings: Vec<String>) -> Element<'a, Message> {
+    const SHOWN: usize = 3;
+    if warnings.is_empty() {
+        return column![].into();
     }
+    let more = warnings.len().saturating_sub(SHOWN);
+    let mut list = column(warnings.into_iter().take(SHOWN).map(|w| text(w).size(10).color(colors::WARNING).into()))
+        .spacing(2);
+    if more > 0 {
+        list = list.push(text(format!("and {} more", more)).size(10).color(colors::WARNING));
+    }
+    container(list).padding([4, 12]).into()
+}
 
-    fn view_auth_panel(&self) -> Element<Message> {
-        let auth_picker = pick_list(AuthType::ALL, Some(self.auth_type), Message::AuthTypeSelected)
-            .text_size(12)
-            .padding(10)
-            .width(200)
-            .style(|_, _| pick_list::Style {
-                text_color: colors::TEXT_PRIMARY,
-                placeholder_color: colors::TEXT_SECONDARY,
-                handle_color: colors::TEXT_SECONDARY,
-                background: colors::BG_ELEVATED.into(),
-                border: iced::Border {
-                    color: colors::BORDER,
-                    width: 1.0,
-                    radius: 0.0.into(),
-                },
-            });
+/// A header name as RFC 9110 allows it: one or more token characters
+fn is_header_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
 
-        let auth_fields: Element<Message> = match self.auth_type {
-            AuthType::None => {
-                container(
-                    text("No authentication will be sent with the request")
-                        .size(11)
-                        .color(colors::TEXT_SECONDARY)
-                )
-                .padding(16)
-                .into()
+/// The enabled headers of a Headers editor, with a note for every line that
+/// was skipped. Names and values are split on the first colon and trimmed of
+/// spaces and tabs; an empty value is kept. An indented line that isn't a
+/// header itself continues the previous value (obsolete line folding).
+fn parse_header_lines(headers: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let mut parsed: Vec<(String, String)> = Vec::new();
+    let mut warnings = Vec::new();
+    // Whether the last header line was sent, so its folded lines follow suit
+    let mut last_enabled = None;
+    let ows = [' ', '\t'];
+    for (i, line) in headers.lines().enumerate() {
+        if line.trim().is_empty() {
+            last_enabled = None;
+            continue;
+        }
+        if is_disabled_header(line) {
+            last_enabled = Some(false);
+            continue;
+        }
+        let split = line.split_once(':').map(|(name, value)| (name.trim_matches(ows), value.trim_matches(ows)));
+        let is_header = split.is_some_and(|(name, _)| is_header_name(name));
+        if line.starts_with(ows) && !is_header && let Some(enabled) = last_enabled {
+            if enabled && let Some((_, value)) = parsed.last_mut() {
+                if !value.is_empty() {
+                    value.push(' ');
+                }
+                value.push_str(line.trim_matches(ows));
             }
-            AuthType::Bearer => {
-                let token_input = text_input("Enter token", &self.auth_token)
-                    .on_input(Message::AuthTokenChanged)
-                    .padding(10)
-                    .size(12)
-                    .width(Fill)
-                    .style(|_, _| text_input::Style {
-                        background: colors::BG_ELEVATED.into(),
-                        border: iced::Border {
-                            color: colors::BORDER,
-                            width: 1.0,
-                            radius: 0.0.into(),
-                        },
-                        icon: colors::TEXT_SECONDARY,
-                        placeholder: colors::TEXT_SECONDARY,
-                        value: colors::TEXT_PRIMARY,
-                        selection: colors::ACCENT_PURPLE,
-                    });
-
-                column![
-                    text("Token").size(11).color(colors::TEXT_SECONDARY),
-                    token_input,
-                    text("Will send: Authorization: Bearer <token>")
-                        .size(10)
-                        .color(colors::TEXT_SECONDARY),
-                ]
-                .spacing(8)
-                .padding(16)
-                .into()
+            continue;
+        }
+        match split {
+            Some((name, value)) if is_header => {
+                parsed.push((name.to_string(), value.to_string()));
+                last_enabled = Some(true);
             }
-            AuthType::Basic => {
-                let username_input = text_input("Username", &self.auth_username)
-                    .on_input(Message::AuthUsernameChanged)
-                    .padding(10)
-                    .size(12)
-                    .width(Fill)
-                    .style(|_, _| text_input::Style {
-                        background: colors::BG_ELEVATED.into(),
-                        border: iced::Border {
-                            color: colors::BORDER,
-                            width: 1.0,
-                            radius: 0.0.into(),
-                        },
-                        icon: colors::TEXT_SECONDARY,
-                        placeholder: colors::TEXT_SECONDARY,
-                        value: colors::TEXT_PRIMARY,
-                        selection: colors::ACCENT_PURPLE,
-                    });
-
-                let password_input = text_input("Password", &self.auth_password)
-                    .on_input(Message::AuthPasswordChanged)
-                    .padding(10)
-                    .size(12)
-                    .width(Fill)
-                    .secure(true)
-                    .style(|_, _| text_input::Style {
-                        background: colors::BG_ELEVATED.into(),
-                        border: iced::Border {
-                            color: colors::BORDER,
-                            width: 1.0,
-                            radius: 0.0.into(),
-                        },
-                        icon: colors::TEXT_SECONDARY,
-                        placeholder: colors::TEXT_SECONDARY,
-                        value: colors::TEXT_PRIMARY,
-                        selection: colors::ACCENT_PURPLE,
-                    });
+            Some((name, _)) => {
+                warnings.push(format!("Line {} skipped: \"{}\" isn't a valid header name", i + 1, truncate_str(name, 40)));
+                last_enabled = None;
+            }
+            None => {
+                warnings.push(format!("Line {} skipped: expected Name: value", i + 1));
+                last_enabled = None;
+            }
+        }
+    }
+    (parsed, warnings)
+}
 
-                column![
-                    column![
-                        text("Username").size(11).color(colors::TEXT_SECONDARY),
-                        username_input,
-                    ].spacing(4),
-                    column![
-                        text("Password").size(11).color(colors::TEXT_SECONDARY),
-                        password_input,
-                    ].spacing(4),
-                    text("Will send: Authorization: Basic <base64>")
-                        .size(10)
-                        .color(colors::TEXT_SECONDARY),
-                ]
-                .spacing(12)
-                .padding(16)
-                .into()
+/// The `key=value` pairs of a Params editor, with a note for every line that
+/// was skipped. A value in double quotes keeps its spaces and may contain
+/// `\"` and `\\`.
+fn parse_param_lines(params: &str) -> (Vec<(String, String)>, Vec<String>) {
+    let mut parsed = Vec::new();
+    let mut warnings = Vec::new();
+    for (i, line) in params.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            warnings.push(format!("Line {} skipped: expected key=value", i + 1));
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            warnings.push(format!("Line {} skipped: the key is empty", i + 1));
+            continue;
+        }
+        let value = value.trim();
+        let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            Some(quoted) => {
+                let mut unescaped = String::with_capacity(quoted.len());
+                let mut chars = quoted.chars();
+                while let Some(c) = chars.next() {
+                    match (c, chars.clone().next()) {
+                        ('\\', Some(next @ ('"' | '\\'))) => {
+                            unescaped.push(next);
+                            chars.next();
+                        }
+                        _ => unescaped.push(c),
+                    }
+                }
+                unescaped
             }
+            _ => value.to_string(),
         };
+        parsed.push((key.to_string(), value));
+    }
+    (parsed, warnings)
+}
 
-        let content = column![
-            container(
-                column![
-                    text("AUTH TYPE").size(10).color(colors::TEXT_SECONDARY),
-                    auth_picker,
-                ]
-                .spacing(8)
-            )
-            .padding(16),
-            auth_fields,
-        ]
-        .spacing(0);
+/// A `#`-commented header line, kept in the editor but never sent
+fn is_disabled_header(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
 
-        scrollable(content).height(Fill).into()
-    }
+/// Comment out the lines of `lines` in `headers`, or uncomment them if they
+/// all already are. Blank lines are left alone.
+fn toggle_header_lines(headers: &str, lines: std::ops::RangeInclusive<usize>) -> String {
+    let all: Vec<&str> = headers.split('\n').collect();
+    let targets = || all.iter().enumerate().filter(|(i, l)| lines.contains(i) && !l.trim().is_empty());
+    let enable = targets().all(|(_, l)| is_disabled_header(l));
+    all.iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if !lines.contains(&i) || line.trim().is_empty() || (!enable && is_disabled_header(line)) {
+                line.to_string()
+            } else if enable {
+                let rest = line.trim_start().trim_start_matches('#');
+                rest.strip_prefix(' ').unwrap_or(rest).to_string()
+            } else {
+                format!("# {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    fn view_response_panel(&self) -> Element<Message> {
-        let body_active = self.response_tab == Tab::Body;
-        let headers_active = self.response_tab == Tab::Headers;
-        let timing_active = self.response_tab == Tab::Timing;
+/// Value of the first enabled `name:` line in a headers block. Trailing
+/// spaces are kept so typing into a quick header input doesn't swallow them.
+fn header_value<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+    headers.lines()
+        .filter(|l| !is_disabled_header(l))
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim_start())
+}
 
-        let body_tab = button(text("Body").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if body_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_CORAL)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if body_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::ResponseTabSelected(Tab::Body));
+/// Rewrite the first `name:` line in place, append it if missing, or drop
+/// every `name:` line when `value` is empty
+fn set_header_value(headers: &str, name: &str, value: &str) -> String {
+    let is_name = |line: &str| line.split_once(':').is_some_and(|(k, _)| k.trim().eq_ignore_ascii_case(name));
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    for line in headers.lines() {
+        if !is_name(line) {
+            lines.push(line.to_string());
+        } else if !replaced && !value.is_empty() {
+            lines.push(format!("{}: {}", name, value));
+            replaced = true;
+        }
+        // Later duplicates go away so the input and the editor agree
+    }
+    if !replaced && !value.is_empty() {
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.push(format!("{}: {}", name, value));
+    }
+    lines.join("\n")
+}
 
-        let headers_tab = button(text("Headers").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if headers_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_CORAL)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if headers_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::ResponseTabSelected(Tab::Headers));
+/// Prepend each default header line whose key the request doesn't set itself
+fn merge_default_headers(defaults: &str, headers: &str) -> String {
+    let has_key = |key: &str| {
+        headers.lines()
+            .filter(|l| !is_disabled_header(l))
+            .filter_map(|l| l.split_once(':'))
+            .any(|(k, _)| k.trim().eq_ignore_ascii_case(key.trim()))
+    };
+    let mut merged: Vec<&str> = defaults.lines()
+        .filter(|l| !is_disabled_header(l))
+        .filter(|l| l.split_once(':').is_some_and(|(k, _)| !k.trim().is_empty() && !has_key(k)))
+        .collect();
+    merged.push(headers);
+    merged.join("\n")
+}
 
-        let timing_tab = button(text("Timing").size(11))
-            .padding([10, 16])
-            .style(move |_, _| {
-                let (bg, txt, border) = if timing_active {
-                    (colors::BG_PANEL, colors::TEXT_PRIMARY, colors::ACCENT_CORAL)
-                } else {
-                    (colors::BG_DARK, colors::TEXT_SECONDARY, colors::BG_DARK)
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: txt,
-                    border: iced::Border { color: border, width: if timing_active { 2.0 } else { 0.0 }, radius: 0.0.into() },
-                    ..Default::default()
-                }
-            })
-            .on_press(Message::ResponseTabSelected(Tab::Timing));
+/// The typed history cap, if it's a positive number
+fn parse_max_history(input: &str) -> Option<usize> {
+    input.trim().parse::<usize>().ok().filter(|&max| max > 0)
+}
 
-        let tabs = row![body_tab, headers_tab, timing_tab].spacing(0);
+/// Drop the oldest entries beyond `max`. Returns whether anything was removed.
+fn trim_history(history: &mut Vec<HistoryEntry>, max: usize) -> bool {
+    if history.len() <= max {
+        return false;
+    }
+    history.drain(..history.len() - max);
+    true
+}
 
-        let status_bar: Element<Message> = if let Some(ref response) = self.response {
-            let status_color = match response.status {
-                200..=299 => colors::SUCCESS,
-                300..=399 => colors::ACCENT_PURPLE,
-                400..=499 => colors::WARNING,
-                500..=599 => colors::ERROR,
-                _ => colors::TEXT_PRIMARY,
-            };
+fn settings_path() -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|d| d.join("badgateway").join("settings.json"))
+}
 
-            row![
-                text(format!("{}", response.status))
-                    .size(11)
-                    .color(status_color),
-                text(format!("{}", response.status_text))
-                    .size(11)
-                    .color(colors::TEXT_SECONDARY),
-                text(format!("{}ms", response.duration.as_millis()))
-                    .size(10)
-                    .color(colors::TEXT_SECONDARY),
-                text(format_size(response.size))
-                    .size(10)
-                    .color(colors::TEXT_SECONDARY),
-            ]
-            .spacing(12)
-            .into()
-        } else if let Some(ref error) = self.error {
-            text(format!("Error: {}", truncate_str(error, 40)))
-                .size(11)
-                .color(colors::ERROR)
-                .into()
-        } else {
-            text("Ready")
-                .size(11)
-                .color(colors::TEXT_SECONDARY)
-                .into()
-        };
+fn load_settings() -> Settings {
+    if let Some(path) = settings_path()
+        && let Ok(data) = std::fs::read_to_string(&path)
+        && let Ok(settings) = serde_json::from_str(&data)
+    {
+        return settings;
+    }
+    Settings::default()
+}
 
-        let content: Element<Message> = if let Some(ref response) = self.response {
-            match self.response_tab {
-                Tab::Body | Tab::Params | Tab::Auth => {
-                    let spans = json_to_spans(&response.body);
-                    scrollable(
-                        container(rich_text(spans).size(11))
-                            .padding(12)
-                            .width(Fill),
-                    )
-                    .height(Fill)
-                    .into()
-                }
-                Tab::Headers => {
-                    let headers_text: String = response
-                        .headers
-                        .iter()
-                        .map(|(k, v)| format!("{}: {}", k, v))
-                        .collect::<Vec<_>>()
-                        .join("\n");
+fn save_settings(settings: &Settings) {
+    if let Some(path) = settings_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(settings) {
+            let _ = std::fs::write(path, data);
+        }
+    }
+}
 
-                    scrollable(
-                        container(text(headers_text).size(11).color(colors::TEXT_PRIMARY))
-                            .padding(12)
-                            .width(Fill),
-                    )
-                    .height(Fill)
-                    .into()
-                }
-                Tab::Timing => {
-                    self.view_timing_details(response)
+/// Parse `key=value` lines, ignoring blanks and `#` comments
+fn parse_variables(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .filter(|(k, _)| !k.is_empty())
+        .collect()
+}
+
+/// `text` with the `name=...` line set to `value`, appended if there is none
+fn set_variable_line(text: &str, name: &str, value: &str) -> String {
+    let mut found = false;
+    let mut lines: Vec<String> = text.lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            match trimmed.split_once('=') {
+                Some((key, _)) if !trimmed.starts_with('#') && key.trim() == name => {
+                    found = true;
+                    format!("{}={}", name, value)
                 }
+                _ => line.to_string(),
             }
-        } else {
-            container(
-                text("Send a request to see the response")
-                    .size(12)
-                    .color(colors::TEXT_SECONDARY),
-            )
-            .padding(16)
-            .center_x(Fill)
-            .center_y(Fill)
-            .into()
-        };
+        })
+        .collect();
+    if !found {
+        lines.push(format!("{}={}", name, value));
+    }
+    lines.join("\n")
+}
 
-        let copy_btn = button(text("COPY").size(9))
-            .padding([4, 8])
-            .style(|_, status| {
-                let bg = match status {
-                    button::Status::Hovered => colors::BG_ELEVATED,
-                    _ => colors::BG_DARK,
-                };
-                button::Style {
-                    background: Some(bg.into()),
-                    text_color: colors::TEXT_SECONDARY,
-                    border: iced::Border {
-                        color: colors::BORDER,
-                        width: 1.0,
-                        radius: 0.0.into(),
-                    },
-                    ..Default::default()
+/// Names of `{{name}}` tokens still present in `input`, without duplicates
+fn unresolved_variables(input: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let name = after[..end].trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Replace `{{name}}` tokens with their values. Unknown names are left as-is.
+fn substitute_variables(input: &str, vars: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim();
+                match vars.iter().rev().find(|(k, _)| k == name) {
+                    Some((_, value)) => out.push_str(value),
+                    None => out.push_str(&rest[start..start + 2 + end + 2]),
                 }
-            })
-            .on_press_maybe(if self.response.is_some() { Some(Message::CopyResponse) } else { None });
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
 
-        let header = row![
-            text("RESPONSE").size(10).color(colors::TEXT_SECONDARY),
-            status_bar,
-            copy_btn,
-        ].spacing(16);
+/// How keys repeated in the Params editor go into the query string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+enum ArrayStyle {
+    /// `tag=a&tag=b`
+    #[default]
+    Repeat,
+    /// `tag[]=a&tag[]=b`
+    Brackets,
+    /// `tag=a,b`
+    Comma,
+}
 
-        let panel = column![
-            container(header).padding(Padding { top: 12.0, right: 16.0, bottom: 8.0, left: 16.0 }),
-            container(tabs).style(|_| container::Style {
-                background: Some(colors::BG_DARK.into()),
-                ..Default::default()
-            }),
-            container(content).padding(0).height(Fill),
-        ].spacing(0);
+impl ArrayStyle {
+    const ALL: &'static [ArrayStyle] = &[ArrayStyle::Repeat, ArrayStyle::Brackets, ArrayStyle::Comma];
+}
 
-        container(panel)
-            .width(Fill)
-            .height(Fill)
-            .style(|_| container::Style {
-                background: Some(colors::BG_PANEL.into()),
-                ..Default::default()
-            })
-            .into()
+impl std::fmt::Display for ArrayStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrayStyle::Repeat => write!(f, "Repeat: tag=a&tag=b"),
+            ArrayStyle::Brackets => write!(f, "Brackets: tag[]=a&tag[]=b"),
+            ArrayStyle::Comma => write!(f, "Comma: tag=a,b"),
+        }
     }
+}
 
-    fn view_timing_details(&self, response: &Response) -> Element<Message> {
-        let total_ms = response.duration.as_millis() as f32;
-        let bar_width = 300.0;
-
-        // Summary stats
-        let speed = if response.duration.as_secs_f64() > 0.0 {
-            (response.size as f64 / response.duration.as_secs_f64()) / 1024.0
+/// Percent-encode what would break a query pair. Existing `%XX` escapes and
+/// `+` are left alone so already-encoded values pass through unchanged.
+fn encode_query_component(s: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        let unsafe_ascii = matches!(ch, ' ' | '"' | '#' | '<' | '>' | '&') || (is_key && ch == '=') || ch.is_ascii_control();
+        if ch.is_ascii() && !unsafe_ascii {
+            out.push(ch);
         } else {
-            0.0
-        };
+            let mut buf = [0; 4];
+            for byte in ch.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    out
+}
 
-        let summary_items = column![
-            row![
-                text("Total Time").size(12).color(colors::TEXT_SECONDARY),
-                text(format!("{}ms", response.duration.as_millis()))
-                    .size(14)
-                    .color(colors::ACCENT_CORAL),
-            ].spacing(12),
-            row![
-                text("Response Size").size(12).color(colors::TEXT_SECONDARY),
-                text(format_size(response.size)).size(14).color(colors::SUCCESS),
-            ].spacing(12),
-            row![
-                text("Transfer Speed").size(12).color(colors::TEXT_SECONDARY),
-                text(format!("{:.1} KB/s", speed)).size(14).color(colors::ACCENT_PURPLE),
-            ].spacing(12),
-        ]
-        .spacing(8);
+/// Undo `%XX` escapes; anything that isn't a valid escape is kept as it is
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escape) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
 
-        // Visual breakdown bar
-        let timing_note = text("Breakdown (total request time)")
-            .size(10)
-            .color(colors::TEXT_SECONDARY);
+/// `key=value` lines from the Params editor as an encoded query string, in
+/// order. Repeated keys are written out according to `style`; keys typed as
+/// `ids[]` are kept as they are.
+fn build_query(params: &str, style: ArrayStyle) -> String {
+    let pairs = parse_param_lines(params).0;
+    let repeated = |key: &str| pairs.iter().filter(|(k, _)| k == key).count() > 1;
+
+    let mut out: Vec<String> = Vec::new();
+    let mut merged: Vec<&str> = Vec::new();
+    for (key, value) in &pairs {
+        let key = key.as_str();
+        let encoded_key = encode_query_component(key, true);
+        match style {
+            ArrayStyle::Brackets if repeated(key) && !key.ends_with("[]") => {
+                out.push(format!("{}[]={}", encoded_key, encode_query_component(value, false)));
+            }
+            ArrayStyle::Comma if repeated(key) => {
+                // All values land at the key's first position
+                if merged.contains(&key) {
+                    continue;
+                }
+                merged.push(key);
+                let values: Vec<String> = pairs.iter()
+                    .filter(|(k, _)| k == key)
+                    .map(|(_, v)| encode_query_component(v, false))
+                    .collect();
+                out.push(format!("{}={}", encoded_key, values.join(",")));
+            }
+            _ => out.push(format!("{}={}", encoded_key, encode_query_component(value, false))),
+        }
+    }
+    out.join("&")
+}
 
-        let bar = container(column![])
-            .width(Length::Fixed(bar_width))
-            .height(16)
-            .style(|_| container::Style {
-                background: Some(colors::ACCENT_CORAL.into()),
-                ..Default::default()
-            });
+/// Join a base URL and a path with exactly one slash between them
+fn join_url(base: &str, path: &str) -> String {
+    let base = base.trim_end_matches('/');
+    if path.is_empty() {
+        base.to_string()
+    } else if path.starts_with('?') || path.starts_with('#') {
+        format!("{}{}", base, path)
+    } else {
+        format!("{}/{}", base, path.trim_start_matches('/'))
+    }
+}
 
-        let bar_bg = container(bar)
-            .width(Length::Fixed(bar_width))
-            .style(|_| container::Style {
-                background: Some(colors::BG_DARK.into()),
-                ..Default::default()
-            });
+fn environments_path() -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|d| d.join("badgateway").join("environments.json"))
+}
 
-        let timing_bar_row = row![
-            container(text("Total").size(11).color(colors::TEXT_SECONDARY))
-                .width(Length::Fixed(120.0)),
-            bar_bg,
-            container(text(format!("{:.0}ms", total_ms)).size(11).color(colors::TEXT_PRIMARY))
-                .width(Length::Fixed(80.0))
-                .padding(Padding { top: 0.0, right: 0.0, bottom: 0.0, left: 12.0 }),
-        ]
-        .spacing(8);
+fn load_environments() -> EnvironmentStore {
+    if let Some(path) = environments_path()
+        && let Ok(data) = std::fs::read_to_string(&path)
+        && let Ok(store) = serde_json::from_str(&data)
+    {
+        return store;
+    }
+    EnvironmentStore::default()
+}
 
-        let content = column![
-            container(
-                column![
-                    text("TIMING SUMMARY").size(10).color(colors::TEXT_SECONDARY),
-                    summary_items,
-                ]
-                .spacing(12)
-            )
-            .padding(16)
-            .width(Fill)
-            .style(|_| container::Style {
-                background: Some(colors::BG_ELEVATED.into()),
-                ..Default::default()
-            }),
-            container(
-                column![
-                    timing_note,
-                    timing_bar_row,
-                ]
-                .spacing(12)
-            )
-            .padding(16)
-            .width(Fill),
-        ]
-        .spacing(16);
+fn save_environments(store: &EnvironmentStore) {
+    if let Some(path) = environments_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(store) {
+            let _ = std::fs::write(path, data);
+        }
+    }
+}
 
-        scrollable(content).height(Fill).into()
+fn collections_path() -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|d| d.join("badgateway").join("collections.json"))
+}
+
+fn load_collections() -> Vec<Collection> {
+    if let Some(path) = collections_path()
+        && let Ok(data) = std::fs::read_to_string(&path)
+        && let Ok(collections) = serde_json::from_str(&data)
+    {
+        return collections;
     }
+    Vec::new()
 }
 
-fn truncate_str(s: &str, max: usize) -> String {
-    if s.len() > max {
-        format!("{}...", &s[..max.saturating_sub(3)])
-    } else {
-        s.to_string()
+fn save_collections(collections: &[Collection]) {
+    if let Some(path) = collections_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(collections) {
+            let _ = std::fs::write(path, data);
+        }
     }
 }
 
-async fn send_request(
-    url: String,
-    method: Method,
-    body: String,
-    headers_str: String,
-    auth_type: AuthType,
-    auth_token: String,
-    auth_username: String,
-    auth_password: String,
-) -> Result<Response, String> {
-    use base64::Engine;
-    let start = StdInstant::now();
-    let client = reqwest::Client::new();
+fn snippets_path() -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|d| d.join("badgateway").join("snippets.json"))
+}
 
-    let mut builder = match method {
-        Method::GET => client.get(&url),
-        Method::POST => client.post(&url),
-        Method::PUT => client.put(&url),
-        Method::PATCH => client.patch(&url),
-        Method::DELETE => client.delete(&url),
-        Method::HEAD => client.head(&url),
-        Method::OPTIONS => client.request(reqwest::Method::OPTIONS, &url),
-    };
+/// Saved snippets, or the starters if none have been saved yet
+fn load_snippets() -> Vec<SavedRequest> {
+    if let Some(path) = snippets_path()
+        && let Ok(data) = std::fs::read_to_string(&path)
+        && let Ok(snippets) = serde_json::from_str(&data)
+    {
+        return snippets;
+    }
+    starter_snippets()
+}
 
-    // Add auth header
-    match auth_type {
-        AuthType::None => {}
-        AuthType::Bearer => {
-            if !auth_token.is_empty() {
-                builder = builder.header("Authorization", format!("Bearer {}", auth_token));
-            }
+fn save_snippets(snippets: &[SavedRequest]) {
+    if let Some(path) = snippets_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
         }
-        AuthType::Basic => {
-            if !auth_username.is_empty() {
-                let credentials = format!("{}:{}", auth_username, auth_password);
-                let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
-                builder = builder.header("Authorization", format!("Basic {}", encoded));
-            }
+        if let Ok(data) = serde_json::to_string_pretty(snippets) {
+            let _ = std::fs::write(path, data);
         }
     }
+}
+
+/// Built-in snippets. URLs are relative so the active environment's base
+/// URL is prefixed.
+fn starter_snippets() -> Vec<SavedRequest> {
+    vec![
+        SavedRequest {
+            name: "Health check".to_string(),
+            url: "/health".to_string(),
+            headers: String::new(),
+            ..SavedRequest::blank()
+        },
+        SavedRequest {
+            name: "Login".to_string(),
+            method: Method::POST,
+            url: "/auth/login".to_string(),
+            body: "{\n  \"username\": \"{{username}}\",\n  \"password\": \"{{password}}\"\n}".to_string(),
+            ..SavedRequest::blank()
+        },
+        SavedRequest {
+            name: "Authenticated GET".to_string(),
+            url: "/{{resource}}".to_string(),
+            headers: "Accept: application/json\n".to_string(),
+            auth_type: AuthType::Bearer,
+            auth_token: "{{token}}".to_string(),
+            ..SavedRequest::blank()
+        },
+        SavedRequest {
+            name: "Form post".to_string(),
+            method: Method::POST,
+            url: "/{{path}}".to_string(),
+            headers: "Content-Type: application/x-www-form-urlencoded\n".to_string(),
+            body: "field={{value}}".to_string(),
+            ..SavedRequest::blank()
+        },
+        SavedRequest {
+            name: "GraphQL query".to_string(),
+            method: Method::POST,
+            url: "/graphql".to_string(),
+            body: "{\n  \"query\": \"{{query}}\",\n  \"variables\": {}\n}".to_string(),
+            auth_type: AuthType::Bearer,
+            auth_token: "{{token}}".to_string(),
+            ..SavedRequest::blank()
+        },
+    ]
+}
+
+/// Every field of a request that can hold `{{name}}` placeholders
+fn snippet_text(request: &SavedRequest) -> String {
+    [
+        &request.url,
+        &request.headers,
+        &request.body,
+        &request.params,
+        &request.auth_token,
+        &request.auth_username,
+        &request.auth_password,
+        &request.auth_domain,
+    ]
+    .map(String::as_str)
+    .join("\n")
+}
+
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// A collection as Postman Collection v2.1 JSON
+fn postman_collection(collection: &Collection) -> serde_json::Value {
+    serde_json::json!({
+        "info": {
+            "name": collection.name,
+            "schema": POSTMAN_SCHEMA,
+        },
+        "item": collection.requests.iter().map(postman_item).collect::<Vec<_>>(),
+    })
+}
 
-    for line in headers_str.lines() {
-        if let Some((key, value)) = line.split_once(':') {
-            builder = builder.header(key.trim(), value.trim());
+fn postman_item(saved: &SavedRequest) -> serde_json::Value {
+    let header: Vec<serde_json::Value> = saved.headers.lines()
+        .map(|l| (is_disabled_header(l), l.trim_start().trim_start_matches('#')))
+        .filter_map(|(disabled, l)| l.split_once(':').map(|(k, v)| (disabled, k, v)))
+        .filter(|(_, k, _)| !k.trim().is_empty())
+        .map(|(disabled, k, v)| {
+            let mut header = serde_json::json!({ "key": k.trim(), "value": v.trim() });
+            if disabled {
+                header["disabled"] = serde_json::Value::Bool(true);
+            }
+            header
+        })
+        .collect();
+    let mut request = serde_json::json!({
+        "method": saved.method.to_string(),
+        "header": header,
+        "url": postman_url(&saved.url, &saved.params),
+    });
+
+    let body = match saved.body_mode {
+        BodyMode::Text if !saved.body.is_empty() => Some(serde_json::json!({ "mode": "raw", "raw": saved.body })),
+        // Postman has no hex mode; keep the bytes when they are text
+        BodyMode::Hex => parse_hex(&saved.body).ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .filter(|s| !s.is_empty())
+            .map(|raw| serde_json::json!({ "mode": "raw", "raw": raw })),
+        BodyMode::File if !saved.body.trim().is_empty() => {
+            Some(serde_json::json!({ "mode": "file", "file": { "src": saved.body.trim() } }))
         }
+        _ => None,
+    };
+    if let Some(body) = body {
+        request["body"] = body;
     }
 
-    if matches!(method, Method::POST | Method::PUT | Method::PATCH) && !body.is_empty() {
-        builder = builder.body(body);
+    let credential = |key: &str, value: &str| serde_json::json!({ "key": key, "value": value, "type": "string" });
+    match saved.auth_type {
+        AuthType::None => {}
+        AuthType::Bearer => {
+            request["auth"] = serde_json::json!({
+                "type": "bearer",
+                "bearer": [credential("token", &saved.auth_token)],
+            });
+        }
+        AuthType::Basic => {
+            request["auth"] = serde_json::json!({
+                "type": "basic",
+                "basic": [
+                    credential("username", &saved.auth_username),
+                    credential("password", &saved.auth_password),
+                ],
+            });
+        }
+        AuthType::Ntlm => {
+            request["auth"] = serde_json::json!({
+                "type": "ntlm",
+                "ntlm": [
+                    credential("username", &saved.auth_username),
+                    credential("password", &saved.auth_password),
+                    credential("domain", &saved.auth_domain),
+                ],
+            });
+        }
     }
 
-    let response = builder.send().await.map_err(|e| e.to_string())?;
-    let duration = start.elapsed();
-
-    let status = response.status().as_u16();
-    let status_text = response
-        .status()
-        .canonical_reason()
-        .unwrap_or("")
-        .to_string();
+    serde_json::json!({ "name": saved.name, "request": request })
+}
 
-    let headers: Vec<(String, String)> = response
-        .headers()
-        .iter()
-        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+/// Postman's structured URL: the query from the URL itself and the Params
+/// editor lines are both split out into `query`
+fn postman_url(url: &str, params: &str) -> serde_json::Value {
+    let (base, url_query) = url.split_once('?').map_or((url, ""), |(b, q)| (b, q));
+    let mut query: Vec<(String, String)> = url_query.split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (k.to_string(), v.to_string())
+        })
         .collect();
+    query.extend(parse_param_lines(params).0);
 
-    let body = response.text().await.map_err(|e| e.to_string())?;
-    let size = body.len();
+    let (protocol, rest) = base.split_once("://").map_or((None, base), |(p, r)| (Some(p), r));
+    let (authority, path) = rest.split_once('/').map_or((rest, ""), |(a, p)| (a, p));
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((h, p)) if !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()) => (h, Some(p)),
+        _ => (authority, None),
+    };
 
-    Ok(Response {
-        status,
-        status_text,
-        headers,
-        body,
-        duration,
-        size,
-    })
+    let mut raw = base.to_string();
+    if !query.is_empty() {
+        let pairs: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        raw.push('?');
+        raw.push_str(&pairs.join("&"));
+    }
+
+    let mut value = serde_json::json!({
+        "raw": raw,
+        "host": host.split('.').collect::<Vec<_>>(),
+        "path": path.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>(),
+    });
+    if let Some(protocol) = protocol {
+        value["protocol"] = protocol.into();
+    }
+    if let Some(port) = port {
+        value["port"] = port.into();
+    }
+    if !query.is_empty() {
+        value["query"] = query.iter()
+            .map(|(k, v)| serde_json::json!({ "key": k, "value": v }))
+            .collect::<Vec<_>>()
+            .into();
+    }
+    value
 }
 
-fn format_json(s: &str) -> String {
-    if let Ok(value) = serde_json::from_str::<serde_json::Value>(s) {
-        serde_json::to_string_pretty(&value).unwrap_or_else(|_| s.to_string())
-    } else {
-        s.to_string()
+/// Saved requests from a Postman v2.1 collection. Top-level requests land in a
+/// collection named after the Postman one; each folder (nested folders joined
+/// with " / ") becomes its own collection. Auth is inherited from the nearest
+/// folder, or the collection, that sets it.
+fn parse_postman(data: &str) -> Result<Vec<Collection>, String> {
+    let root: serde_json::Value = serde_json::from_str(data).map_err(|e| format!("Not valid JSON: {}", e))?;
+    let name = root.pointer("/info/name").and_then(|v| v.as_str()).ok_or("Not a Postman collection")?;
+    let items = root.get("item").and_then(|v| v.as_array()).ok_or("Not a Postman collection")?;
+
+    let mut collections = Vec::new();
+    collect_postman_items(items, name, root.get("auth"), &mut collections);
+    if collections.is_empty() {
+        return Err("The collection has no requests".to_string());
     }
+    Ok(collections)
 }
 
-fn json_to_spans<'a>(s: &str) -> Vec<iced::widget::text::Span<'a, iced::Font>> {
-    let formatted = format_json(s);
-    let mut spans = Vec::new();
-    let mut chars = formatted.chars().peekable();
-    let mut current = String::new();
+fn collect_postman_items(
+    items: &[serde_json::Value],
+    collection_name: &str,
+    inherited_auth: Option<&serde_json::Value>,
+    out: &mut Vec<Collection>,
+) {
+    // Folder requests go ahead of the collections for its subfolders
+    let position = out.len();
+    let mut requests = Vec::new();
+    for item in items {
+        let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        if let Some(children) = item.get("item").and_then(|v| v.as_array()) {
+            let folder = format!("{} / {}", collection_name, name);
+            collect_postman_items(children, &folder, item.get("auth").or(inherited_auth), out);
+        } else if let Some(request) = item.get("request") {
+            requests.push(postman_request(name, request, inherited_auth));
+        }
+    }
+    if !requests.is_empty() {
+        out.insert(position, Collection { name: collection_name.to_string(), requests });
+    }
+}
 
-    while let Some(ch) = chars.next() {
-        match ch {
-            '"' => {
-                // Push any pending text
-                if !current.is_empty() {
-                    spans.push(span(std::mem::take(&mut current)).color(colors::TEXT_PRIMARY));
-                }
-                // Read the string
-                let mut string_content = String::from("\"");
-                let mut is_key = false;
-                while let Some(c) = chars.next() {
-                    string_content.push(c);
-                    if c == '"' {
-                        break;
-                    }
-                    if c == '\\' {
-                        if let Some(escaped) = chars.next() {
-                            string_content.push(escaped);
-                        }
-                    }
-                }
-                // Check if this is a key (followed by :)
-                let mut temp_chars = chars.clone();
-                while let Some(&c) = temp_chars.peek() {
-                    if c.is_whitespace() {
-                        temp_chars.next();
-                    } else {
-                        is_key = c == ':';
-                        break;
-                    }
+fn postman_request(name: &str, request: &serde_json::Value, inherited_auth: Option<&serde_json::Value>) -> SavedRequest {
+    let mut saved = SavedRequest::blank();
+    saved.name = name.to_string();
+    saved.headers = String::new();
+
+    // A bare string is shorthand for a GET of that URL
+    let (method, url) = match request {
+        serde_json::Value::String(url) => ("GET", serde_json::Value::String(url.clone())),
+        _ => (
+            request.get("method").and_then(|v| v.as_str()).unwrap_or("GET"),
+            request.get("url").cloned().unwrap_or_default(),
+        ),
+    };
+    saved.method = Method::ALL.iter().copied()
+        .find(|m| m.to_string().eq_ignore_ascii_case(method))
+        .unwrap_or(Method::GET);
+    saved.url = postman_url_string(&url);
+
+    let mut headers: Vec<(String, String)> = request.get("header").and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|h| {
+            let key = h.get("key")?.as_str()?;
+            // Disabled headers come across commented out
+            let disabled = h.get("disabled").and_then(|v| v.as_bool()).unwrap_or(false);
+            let key = if disabled { format!("# {}", key) } else { key.to_string() };
+            Some((key, h.get("value").and_then(|v| v.as_str()).unwrap_or("").to_string()))
+        })
+        .collect();
+    let has_content_type = |headers: &[(String, String)]| headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("content-type"));
+
+    if let Some(body) = request.get("body") {
+        let str_at = |pointer: &str| body.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        match body.get("mode").and_then(|v| v.as_str()) {
+            Some("raw") => saved.body = str_at("/raw"),
+            Some("urlencoded") => {
+                let pairs: Vec<String> = body.get("urlencoded").and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter(|p| !p.get("disabled").and_then(|v| v.as_bool()).unwrap_or(false))
+                    .filter_map(|p| {
+                        let key = p.get("key")?.as_str()?;
+                        let value = p.get("value").and_then(|v| v.as_str()).unwrap_or("");
+                        Some(format!("{}={}", encode_query_component(key, true), encode_query_component(value, false)))
+                    })
+                    .collect();
+                saved.body = pairs.join("&");
+                if !has_content_type(&headers) {
+                    headers.push(("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()));
                 }
-                let color = if is_key {
-                    colors::ACCENT_PURPLE  // Keys in purple
-                } else {
-                    colors::SUCCESS        // String values in green
-                };
-                spans.push(span(string_content).color(color));
             }
-            c if c.is_ascii_digit() || c == '-' => {
-                if !current.is_empty() {
-                    spans.push(span(std::mem::take(&mut current)).color(colors::TEXT_PRIMARY));
+            Some("graphql") => {
+                let query = str_at("/graphql/query");
+                let variables = serde_json::from_str::<serde_json::Value>(&str_at("/graphql/variables"))
+                    .unwrap_or(serde_json::Value::Object(Default::default()));
+                saved.body = serde_json::to_string_pretty(&serde_json::json!({ "query": query, "variables": variables }))
+                    .unwrap_or_default();
+                if !has_content_type(&headers) {
+                    headers.push(("Content-Type".to_string(), "application/json".to_string()));
                 }
-                let mut num = String::from(c);
-                while let Some(&next) = chars.peek() {
-                    if next.is_ascii_digit() || next == '.' || next == 'e' || next == 'E' || next == '+' || next == '-' {
-                        num.push(chars.next().unwrap());
-                    } else {
-                        break;
-                    }
-                }
-                spans.push(span(num).color(colors::ACCENT_CORAL));  // Numbers in coral
             }
-            't' | 'f' | 'n' => {
-                if !current.is_empty() {
-                    spans.push(span(std::mem::take(&mut current)).color(colors::TEXT_PRIMARY));
-                }
-                let mut keyword = String::from(ch);
-                let expected = match ch {
-                    't' => "rue",
-                    'f' => "alse",
-                    'n' => "ull",
-                    _ => "",
-                };
-                for expected_char in expected.chars() {
-                    if chars.peek() == Some(&expected_char) {
-                        keyword.push(chars.next().unwrap());
-                    } else {
-                        break;
+            Some("file") => {
+                saved.body_mode = BodyMode::File;
+                saved.body = str_at("/file/src");
+            }
+            // formdata has no equivalent here; the request comes in without a body
+            _ => {}
+        }
+    }
+    saved.headers = headers.iter().map(|(k, v)| format!("{}: {}\n", k, v)).collect();
+
+    let auth = request.get("auth").or(inherited_auth).unwrap_or(&serde_json::Value::Null);
+    let param = |kind: &str, key: &str| -> String {
+        let value = match auth.get(kind) {
+            // v2.1 lists parameters as key/value pairs, v2.0 as an object
+            Some(serde_json::Value::Array(params)) => params.iter()
+                .find(|p| p.get("key").and_then(|k| k.as_str()) == Some(key))
+                .and_then(|p| p.get("value")),
+            Some(serde_json::Value::Object(params)) => params.get(key),
+            _ => None,
+        };
+        value.and_then(|v| v.as_str()).unwrap_or("").to_string()
+    };
+    match auth.get("type").and_then(|t| t.as_str()) {
+        Some("bearer") => {
+            saved.auth_type = AuthType::Bearer;
+            saved.auth_token = param("bearer", "token");
+        }
+        Some("basic") => {
+            saved.auth_type = AuthType::Basic;
+            saved.auth_username = param("basic", "username");
+            saved.auth_password = param("basic", "password");
+        }
+        Some("ntlm") => {
+            saved.auth_type = AuthType::Ntlm;
+            saved.auth_username = param("ntlm", "username");
+            saved.auth_password = param("ntlm", "password");
+            saved.auth_domain = param("ntlm", "domain");
+        }
+        _ => {}
+    }
+    saved
+}
+
+/// A Postman URL (string or structured) as one string. `:name` path segments
+/// take the value Postman stores for them, or become `{{name}}` variables.
+fn postman_url_string(url: &serde_json::Value) -> String {
+    let raw = match url {
+        serde_json::Value::String(raw) => raw.clone(),
+        _ => match url.get("raw").and_then(|v| v.as_str()) {
+            Some(raw) => raw.to_string(),
+            None => {
+                let list = |key: &str, sep: &str| match url.get(key) {
+                    Some(serde_json::Value::Array(parts)) => {
+                        parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>().join(sep)
                     }
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    _ => String::new(),
+                };
+                let mut raw = list("host", ".");
+                if let Some(protocol) = url.get("protocol").and_then(|v| v.as_str()) {
+                    raw = format!("{}://{}", protocol, raw);
                 }
-                if keyword == "true" || keyword == "false" || keyword == "null" {
-                    spans.push(span(keyword).color(colors::WARNING));  // Booleans/null in yellow
-                } else {
-                    spans.push(span(keyword).color(colors::TEXT_PRIMARY));
+                if let Some(port) = url.get("port").and_then(|v| v.as_str()) {
+                    raw = format!("{}:{}", raw, port);
                 }
-            }
-            '{' | '}' | '[' | ']' | ':' | ',' => {
-                if !current.is_empty() {
-                    spans.push(span(std::mem::take(&mut current)).color(colors::TEXT_PRIMARY));
+                let path = list("path", "/");
+                if !path.is_empty() {
+                    raw = format!("{}/{}", raw, path);
                 }
-                spans.push(span(ch.to_string()).color(colors::TEXT_SECONDARY));  // Punctuation dimmed
-            }
-            _ => {
-                current.push(ch);
+                raw
             }
-        }
-    }
+        },
+    };
 
-    if !current.is_empty() {
-        spans.push(span(current).color(colors::TEXT_PRIMARY));
+    let variables: Vec<(&str, &str)> = url.get("variable").and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| Some((v.get("key")?.as_str()?, v.get("value").and_then(|v| v.as_str()).unwrap_or(""))))
+        .collect();
+    let (path, query) = raw.split_once('?').map_or((raw.as_str(), None), |(p, q)| (p, Some(q)));
+    let (scheme, rest) = path.split_once("://").map_or(("", path), |(s, r)| (s, r));
+    let segments: Vec<String> = rest.split('/')
+        .enumerate()
+        .map(|(i, segment)| match segment.strip_prefix(':') {
+            // The first segment is the host (and maybe a port), never a parameter
+            Some(name) if i > 0 && !name.is_empty() => match variables.iter().find(|(k, _)| *k == name) {
+                Some((_, value)) if !value.is_empty() => value.to_string(),
+                _ => format!("{{{{{}}}}}", name),
+            },
+            _ => segment.to_string(),
+        })
+        .collect();
+
+    let mut out = if scheme.is_empty() { String::new() } else { format!("{}://", scheme) };
+    out.push_str(&segments.join("/"));
+    if let Some(query) = query {
+        out.push('?');
+        out.push_str(query);
     }
+    out
+}
 
-    if spans.is_empty() {
-        spans.push(span(s.to_string()).color(colors::TEXT_PRIMARY));
+/// The request in REST Client `.http` form: `@var = value` lines for the
+/// request's variables, the request line, `?`/`&` query lines, headers, a
+/// blank line and the body. A response is appended as a commented-out block
+/// of its own, so the file still runs.
+fn http_file(saved: &SavedRequest, response: Option<&Response>) -> String {
+    let mut out = String::new();
+    for (key, value) in parse_variables(&saved.variables) {
+        out.push_str(&format!("@{} = {}\n", key, value));
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(format!("### {}", saved.name).trim_end());
+    out.push('\n');
+    // REST Client can't do NTLM, so only say who it was for
+    if saved.auth_type == AuthType::Ntlm {
+        let user = match saved.auth_domain.trim() {
+            "" => saved.auth_username.trim().to_string(),
+            domain => format!("{}\\{}", domain, saved.auth_username.trim()),
+        };
+        let user = if user.is_empty() { String::new() } else { format!(" for {}", user) };
+        out.push_str(&format!("# NTLM auth{} is not exported\n", user));
+    }
+    out.push_str(&format!("{} {}\n", saved.method, saved.url.trim()));
+    let params = saved.params.lines().map(str::trim).filter(|l| l.contains('='));
+    for (i, param) in params.enumerate() {
+        out.push_str(&format!("    {}{}\n", if i == 0 && !saved.url.contains('?') { '?' } else { '&' }, param));
+    }
+    for line in saved.headers.lines().filter(|l| !l.trim().is_empty()) {
+        out.push_str(line.trim());
+        out.push('\n');
+    }
+    match saved.auth_type {
+        AuthType::Bearer if !saved.auth_token.is_empty() => {
+            out.push_str(&format!("Authorization: Bearer {}\n", saved.auth_token));
+        }
+        // REST Client encodes `Basic user:pass` itself
+        AuthType::Basic => {
+            out.push_str(&format!("Authorization: Basic {}:{}\n", saved.auth_username, saved.auth_password));
+        }
+        _ => {}
+    }
+    let body = match saved.body_mode {
+        BodyMode::Text => Some(saved.body.clone()),
+        BodyMode::Hex => parse_hex(&saved.body).ok().and_then(|bytes| String::from_utf8(bytes).ok()),
+        BodyMode::File if !saved.body.trim().is_empty() => Some(format!("< {}", saved.body.trim())),
+        BodyMode::File | BodyMode::Image => None,
+    };
+    if let Some(body) = body.filter(|b| !b.trim().is_empty()) {
+        out.push('\n');
+        out.push_str(body.trim_end());
+        out.push('\n');
     }
 
-    spans
+    if let Some(response) = response {
+        out.push_str(&format!("\n### Response: {} {}\n", response.status, response.status_text));
+        let mut lines: Vec<String> = response.headers.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+        lines.push(String::new());
+        lines.extend(response.body.lines().map(str::to_string));
+        for line in lines {
+            out.push_str(format!("# {}", line).trim_end());
+            out.push('\n');
+        }
+    }
+    out
 }
 
-fn format_size(bytes: usize) -> String {
-    if bytes < 1024 {
-        format!("{} B", bytes)
-    } else if bytes < 1024 * 1024 {
-        format!("{:.1} KB", bytes as f64 / 1024.0)
-    } else {
-        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+/// Requests and `@var = value` definitions from a REST Client `.http` file.
+/// Requests are separated by `###` lines, whose text (or a `# @name` comment)
+/// names the request; blocks with no request line are skipped.
+fn parse_http_file(data: &str) -> (Vec<SavedRequest>, Vec<(String, String)>) {
+    let mut variables = Vec::new();
+    let mut requests = Vec::new();
+    let mut blocks: Vec<(String, Vec<&str>)> = vec![(String::new(), Vec::new())];
+    for line in data.lines() {
+        match line.trim_start().strip_prefix("###") {
+            Some(name) => blocks.push((name.trim().to_string(), Vec::new())),
+            None => blocks.last_mut().expect("starts with a block").1.push(line),
+        }
     }
-}
 
-fn history_path() -> Option<std::path::PathBuf> {
-    dirs::data_dir().map(|d| d.join("badgateway").join("history.json"))
-}
+    for (mut name, lines) in blocks {
+        let mut lines = lines.into_iter().peekable();
+        let mut request_line = None;
+        // Comments, variables and blank lines may come before the request line
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if let Some(rest) = trimmed.strip_prefix('@')
+                && let Some((key, value)) = rest.split_once('=')
+            {
+                variables.push((key.trim().to_string(), value.trim().to_string()));
+            } else if let Some(comment) = trimmed.strip_prefix('#').or_else(|| trimmed.strip_prefix("//")) {
+                if let Some(tag) = comment.trim().strip_prefix("@name") {
+                    name = tag.trim().to_string();
+                }
+            } else if !trimmed.is_empty() {
+                request_line = Some(trimmed);
+                break;
+            }
+        }
+        let Some(request_line) = request_line else { continue };
+
+        let mut saved = SavedRequest::blank();
+        saved.headers = String::new();
+        let mut parts = request_line.split_whitespace();
+        let first = parts.next().unwrap_or_default();
+        match Method::ALL.iter().copied().find(|m| m.to_string().eq_ignore_ascii_case(first)) {
+            Some(method) => {
+                saved.method = method;
+                saved.url = parts.next().unwrap_or_default().to_string();
+            }
+            // A bare URL is a GET
+            None => saved.url = first.to_string(),
+        }
 
-fn load_history() -> Vec<HistoryEntry> {
-    if let Some(path) = history_path() {
-        if let Ok(data) = std::fs::read_to_string(&path) {
-            if let Ok(history) = serde_json::from_str(&data) {
-                return history;
+        let mut params = Vec::new();
+        while let Some(query) = lines.peek().and_then(|l| l.trim().strip_prefix(['?', '&'])) {
+            params.push(query.to_string());
+            lines.next();
+        }
+        let mut headers = Vec::new();
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                break;
+            }
+            // Commented-out headers come back disabled; other comments are dropped
+            match trimmed.strip_prefix('#').or_else(|| trimmed.strip_prefix("//")) {
+                Some(comment) if comment.contains(':') => headers.push(format!("# {}", comment.trim())),
+                Some(_) => {}
+                None => match http_file_auth(trimmed) {
+                    Some((auth_type, token, username, password)) => {
+                        saved.auth_type = auth_type;
+                        saved.auth_token = token;
+                        saved.auth_username = username;
+                        saved.auth_password = password;
+                    }
+                    None => headers.push(trimmed.to_string()),
+                },
             }
         }
+        let mut body: Vec<&str> = lines.collect();
+        while body.last().is_some_and(|l| l.trim().is_empty()) {
+            body.pop();
+        }
+        let body = body.join("\n");
+        match body.trim().strip_prefix("< ") {
+            Some(path) if !path.contains('\n') => {
+                saved.body_mode = BodyMode::File;
+                saved.body = path.trim().to_string();
+            }
+            _ => saved.body = body,
+        }
+
+        saved.params = params.join("\n");
+        saved.headers = headers.join("\n");
+        saved.name = if name.is_empty() { format!("{} {}", saved.method, saved.url) } else { name };
+        requests.push(saved);
     }
-    Vec::new()
+    (requests, variables)
 }
 
-fn save_history(history: &[HistoryEntry]) {
-    if let Some(path) = history_path() {
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        if let Ok(data) = serde_json::to_string_pretty(history) {
-            let _ = std::fs::write(path, data);
-        }
+/// An `Authorization` header line from a `.http` file as (auth type, token,
+/// username, password). Basic takes REST Client's `user:pass` and `user pass`
+/// forms as well as base64.
+fn http_file_auth(line: &str) -> Option<(AuthType, String, String, String)> {
+    let (name, value) = line.split_once(':')?;
+    if !name.trim().eq_ignore_ascii_case("authorization") {
+        return None;
+    }
+    let (scheme, credentials) = value.trim().split_once(' ')?;
+    let credentials = credentials.trim();
+    if scheme.eq_ignore_ascii_case("bearer") {
+        return Some((AuthType::Bearer, credentials.to_string(), String::new(), String::new()));
+    }
+    if !scheme.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+    let decoded = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, credentials)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|creds| creds.contains(':'));
+    let (username, password) = match &decoded {
+        Some(creds) => creds.split_once(':')?,
+        None => credentials.split_once(':').or_else(|| credentials.split_once(' '))?,
+    };
+    Some((AuthType::Basic, String::new(), username.to_string(), password.to_string()))
+}
+
+/// Ask for a file to open and read it, as (file stem, contents). `Ok(None)`
+/// if the dialog was cancelled.
+async fn import_file(filter: &'static str, extensions: &'static [&'static str]) -> Result<Option<(String, String)>, String> {
+    let Some(handle) = rfd::AsyncFileDialog::new()
+        .add_filter(filter, extensions)
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+    let stem = handle.path().file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let data = tokio::fs::read_to_string(handle.path()).await.map_err(|e| e.to_string())?;
+    Ok(Some((stem, data)))
+}
+
+/// Ask where to save `data` and write it there. `Ok(None)` if the dialog was cancelled.
+async fn export_file(
+    file_name: String,
+    data: Vec<u8>,
+    filter: Option<(&'static str, &'static [&'static str])>,
+) -> Result<Option<String>, String> {
+    let mut dialog = rfd::AsyncFileDialog::new().set_file_name(&file_name);
+    if let Some((name, extensions)) = filter {
+        dialog = dialog.add_filter(name, extensions);
     }
+    let Some(handle) = dialog.save_file().await else {
+        return Ok(None);
+    };
+    let path = handle.path().to_path_buf();
+    tokio::fs::write(&path, data).await.map_err(|e| e.to_string())?;
+    Ok(Some(path.display().to_string()))
 }
 
 struct ParsedCurl {
@@ -1584,7 +12277,11 @@ struct ParsedCurl {
     method: Method,
     headers: String,
     body: String,
+    /// `key=value` lines, from `-G` data
+    params: String,
     auth: Option<(AuthType, String, String, String)>, // (type, token, user, pass)
+    /// `-L` was given
+    follow_redirects: bool,
 }
 
 fn parse_curl(input: &str) -> Option<ParsedCurl> {
@@ -1594,10 +12291,12 @@ fn parse_curl(input: &str) -> Option<ParsedCurl> {
     }
 
     let mut url = String::new();
-    let mut method = Method::GET;
+    let mut method: Option<Method> = None;
     let mut headers = Vec::new();
-    let mut body = String::new();
+    let mut data: Vec<String> = Vec::new();
     let mut auth: Option<(AuthType, String, String, String)> = None;
+    let (mut head, mut get, mut follow_redirects) = (false, false, false);
+    let mut ntlm = false;
 
     // Simple tokenizer that handles quoted strings
     let mut tokens = Vec::new();
@@ -1631,86 +12330,99 @@ fn parse_curl(input: &str) -> Option<ParsedCurl> {
         tokens.push(current);
     }
 
+    // Split bundled value-less short flags like `-sSL` into `-s -S -L`
+    let tokens: Vec<String> = tokens.into_iter().flat_map(|token| {
+        let bundled = token.len() > 2
+            && token.starts_with('-')
+            && !token.starts_with("--")
+            && token[1..].chars().all(|c| "sSLIGkvif".contains(c));
+        if bundled {
+            token[1..].chars().map(|c| format!("-{}", c)).collect()
+        } else {
+            vec![token]
+        }
+    }).collect();
+
     let mut i = 0;
     while i < tokens.len() {
         let token = &tokens[i];
+        let has_value = i + 1 < tokens.len();
         match token.as_str() {
-            "-X" | "--request" => {
-                if i + 1 < tokens.len() {
-                    method = match tokens[i + 1].to_uppercase().as_str() {
-                        "GET" => Method::GET,
-                        "POST" => Method::POST,
-                        "PUT" => Method::PUT,
-                        "PATCH" => Method::PATCH,
-                        "DELETE" => Method::DELETE,
-                        "HEAD" => Method::HEAD,
-                        "OPTIONS" => Method::OPTIONS,
-                        _ => Method::GET,
-                    };
-                    i += 1;
+            "-X" | "--request" if has_value => {
+                method = Some(match tokens[i + 1].to_uppercase().as_str() {
+                    "GET" => Method::GET,
+                    "POST" => Method::POST,
+                    "PUT" => Method::PUT,
+                    "PATCH" => Method::PATCH,
+                    "DELETE" => Method::DELETE,
+                    "HEAD" => Method::HEAD,
+                    "OPTIONS" => Method::OPTIONS,
+                    _ => Method::GET,
+                });
+                i += 1;
+            }
+            "-L" | "--location" => follow_redirects = true,
+            "--ntlm" => ntlm = true,
+            "-I" | "--head" => head = true,
+            "-G" | "--get" => get = true,
+            "-e" | "--referer" if has_value => {
+                // `-e ";auto"` is curl's automatic referer; nothing to import
+                let referer = tokens[i + 1].trim_end_matches(";auto");
+                if !referer.is_empty() {
+                    headers.push(format!("Referer: {}", referer));
                 }
+                i += 1;
             }
-            "-H" | "--header" => {
-                if i + 1 < tokens.len() {
-                    let header = &tokens[i + 1];
-                    // Check for Authorization header
-                    if header.to_lowercase().starts_with("authorization:") {
-                        let value = header.splitn(2, ':').nth(1).unwrap_or("").trim();
-                        if value.to_lowercase().starts_with("bearer ") {
+            "-H" | "--header" if has_value => {
+                let header = &tokens[i + 1];
+                // Check for Authorization header
+                if header.to_lowercase().starts_with("authorization:") {
+                    let value = header.split_once(':').map(|(_, v)| v).unwrap_or("").trim();
+                    if value.to_lowercase().starts_with("bearer ") {
+                        auth = Some((
+                            AuthType::Bearer,
+                            value[7..].to_string(),
+                            String::new(),
+                            String::new(),
+                        ));
+                    } else if value.to_lowercase().starts_with("basic ") {
+                        // Try to decode basic auth
+                        if let Ok(decoded) = base64::Engine::decode(
+                            &base64::engine::general_purpose::STANDARD,
+                            value[6..].trim(),
+                        ) && let Ok(creds) = String::from_utf8(decoded)
+                            && let Some((user, pass)) = creds.split_once(':')
+                        {
                             auth = Some((
-                                AuthType::Bearer,
-                                value[7..].to_string(),
-                                String::new(),
+                                AuthType::Basic,
                                 String::new(),
+                                user.to_string(),
+                                pass.to_string(),
                             ));
-                        } else if value.to_lowercase().starts_with("basic ") {
-                            // Try to decode basic auth
-                            if let Ok(decoded) = base64::Engine::decode(
-                                &base64::engine::general_purpose::STANDARD,
-                                value[6..].trim(),
-                            ) {
-                                if let Ok(creds) = String::from_utf8(decoded) {
-                                    if let Some((user, pass)) = creds.split_once(':') {
-                                        auth = Some((
-                                            AuthType::Basic,
-                                            String::new(),
-                                            user.to_string(),
-                                            pass.to_string(),
-                                        ));
-                                    }
-                                }
-                            }
-                        } else {
-                            headers.push(header.clone());
                         }
                     } else {
                         headers.push(header.clone());
                     }
-                    i += 1;
+                } else {
+                    headers.push(header.clone());
                 }
+                i += 1;
             }
-            "-d" | "--data" | "--data-raw" | "--data-binary" => {
-                if i + 1 < tokens.len() {
-                    body = tokens[i + 1].clone();
-                    if method == Method::GET {
-                        method = Method::POST;
-                    }
-                    i += 1;
-                }
+            "-d" | "--data" | "--data-raw" | "--data-binary" if has_value => {
+                data.push(tokens[i + 1].clone());
+                i += 1;
             }
-            "-u" | "--user" => {
-                if i + 1 < tokens.len() {
-                    let creds = &tokens[i + 1];
-                    if let Some((user, pass)) = creds.split_once(':') {
-                        auth = Some((
-                            AuthType::Basic,
-                            String::new(),
-                            user.to_string(),
-                            pass.to_string(),
-                        ));
-                    }
-                    i += 1;
+            "-u" | "--user" if has_value => {
+                let creds = &tokens[i + 1];
+                if let Some((user, pass)) = creds.split_once(':') {
+                    auth = Some((
+                        AuthType::Basic,
+                        String::new(),
+                        user.to_string(),
+                        pass.to_string(),
+                    ));
                 }
+                i += 1;
             }
             s if s.starts_with("http://") || s.starts_with("https://") => {
                 url = s.to_string();
@@ -1724,11 +12436,433 @@ fn parse_curl(input: &str) -> Option<ParsedCurl> {
         return None;
     }
 
+    // --ntlm sends the -u credentials with NTLM; `DOMAIN\user` is split at send time
+    if ntlm && let Some((auth_type @ AuthType::Basic, ..)) = auth.as_mut() {
+        *auth_type = AuthType::Ntlm;
+    }
+
+    // curl joins repeated -d values with `&`; -G sends them as the query instead.
+    // The data is already form-encoded, and Params lines are encoded at send time.
+    let data = data.join("&");
+    let (body, params) = if get {
+        let decode = |s: &str| String::from_utf8_lossy(&percent_decode(&s.replace('+', " "))).into_owned();
+        let params: Vec<String> = data.split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| match p.split_once('=') {
+                Some((key, value)) => format!("{}={}", decode(key), decode(value)),
+                None => decode(p),
+            })
+            .collect();
+        (String::new(), params.join("\n"))
+    } else {
+        (data, String::new())
+    };
+    // An explicit -X wins, as it does in curl
+    let method = method.unwrap_or(if head {
+        Method::HEAD
+    } else if !body.is_empty() {
+        Method::POST
+    } else {
+        Method::GET
+    });
+
     Some(ParsedCurl {
         url,
         method,
         headers: headers.join("\n"),
         body,
+        params,
         auth,
+        follow_redirects,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        s.split_whitespace().map(|b| u8::from_str_radix(b, 16).unwrap()).collect()
+    }
+
+    /// Target info of the MS-NLMP 4.2.4 example: NbDomainName "Domain", NbComputerName "Server"
+    const NTLM_TARGET_INFO: &str = "02 00 0c 00 44 00 6f 00 6d 00 61 00 69 00 6e 00 \
+        01 00 0c 00 53 00 65 00 72 00 76 00 65 00 72 00 00 00 00 00";
+
+    /// CHALLENGE_MESSAGE from MS-NLMP 4.2.4.3
+    fn ntlm_challenge_message() -> Vec<u8> {
+        let mut message = hex("4e 54 4c 4d 53 53 50 00 02 00 00 00 0c 00 0c 00 38 00 00 00 \
+            33 82 8a e2 01 23 45 67 89 ab cd ef 00 00 00 00 00 00 00 00 24 00 24 00 44 00 00 00 \
+            06 00 70 17 00 00 00 0f 53 00 65 00 72 00 76 00 65 00 72 00");
+        message.extend(hex(NTLM_TARGET_INFO));
+        message
+    }
+
+    /// The bytes an AUTHENTICATE_MESSAGE field header at `at` points to
+    fn ntlm_field(message: &[u8], at: usize) -> &[u8] {
+        let len = u16::from_le_bytes([message[at], message[at + 1]]) as usize;
+        let offset = u32::from_le_bytes(message[at + 4..at + 8].try_into().unwrap()) as usize;
+        &message[offset..offset + len]
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)]) -> Response {
+        Response {
+            url: "https://api.example.com/v1/users/42".to_string(),
+            status,
+            status_text: String::new(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: String::new(),
+            raw_body: None,
+            duration: std::time::Duration::ZERO,
+            size: 0,
+            raw_request: String::new(),
+            raw_response_headers: String::new(),
+        }
+    }
+
+    #[test]
+    fn archive_keeps_binary_bytes() {
+        let dir = std::env::temp_dir().join(format!("badgateway-archive-test-{}", std::process::id()));
+        let bytes = vec![0x89, b'P', b'N', b'G', 0xff, 0x00, 0xfe];
+        let mut png = response(200, &[("Content-Type", "image/png")]);
+        png.body = String::from_utf8_lossy(&bytes).into_owned();
+        png.raw_body = Some(bytes.clone());
+        archive_response(&dir, Method::GET, &png).unwrap();
+        let saved: Vec<_> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| !path.to_string_lossy().ends_with(".meta.json"))
+            .collect();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].extension().and_then(|e| e.to_str()), Some("png"));
+        assert_eq!(std::fs::read(&saved[0]).unwrap(), bytes);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rust_types_avoid_unusable_keywords() {
+        let value = serde_json::json!({"crate": 1, "self": {"a": true}, "Super": "x", "type": "y", "crate_": 2});
+        let types = rust_types(&infer_schema(&value));
+        assert!(types.contains("    #[serde(rename = \"crate\")]\n    pub crate_: i64,"), "{}", types);
+        assert!(types.contains("    #[serde(rename = \"self\")]\n    pub self_: SelfType,"), "{}", types);
+        assert!(types.contains("    #[serde(rename = \"Super\")]\n    pub super_: String,"), "{}", types);
+        assert!(types.contains("    pub r#type: String,"), "{}", types);
+        assert!(types.contains("pub struct SelfType {"), "{}", types);
+        assert!(!types.contains("r#crate") && !types.contains("r#self") && !types.contains("pub self:"));
+    }
+
+    #[test]
+    fn captures_body_values() {
+        let body = serde_json::json!({"data": {"token": "abc", "items": [{"id": 7}, {"id": 8}], "ok": true}});
+        assert_eq!(captured_body_value(&body, ".data.token").as_deref(), Some("abc"));
+        assert_eq!(captured_body_value(&body, "data.items[1].id").as_deref(), Some("8"));
+        assert_eq!(captured_body_value(&body, ".data.items.0").as_deref(), Some(r#"{"id":7}"#));
+        assert_eq!(captured_body_value(&body, ".data.ok").as_deref(), Some("true"));
+        assert_eq!(captured_body_value(&body, ".data.missing"), None);
+        assert_eq!(captured_body_value(&body, "data.items[5]"), None);
+    }
+
+    #[test]
+    fn redirect_target_resolves_locations() {
+        let target = |location| response(302, &[("Location", location)]).redirect_target();
+        assert_eq!(target("../accounts/7").as_deref(), Some("https://api.example.com/v1/accounts/7"));
+        assert_eq!(target("/login?next=%2F").as_deref(), Some("https://api.example.com/login?next=%2F"));
+        assert_eq!(target("//cdn.example.net/a.png").as_deref(), Some("https://cdn.example.net/a.png"));
+        assert_eq!(target("http://other.example.org/x").as_deref(), Some("http://other.example.org/x"));
+    }
+
+    #[test]
+    fn redirect_target_needs_a_usable_location() {
+        assert_eq!(response(301, &[]).redirect_target(), None);
+        assert_eq!(response(302, &[("Location", "http://[::1")]).redirect_target(), None);
+        assert_eq!(response(200, &[("Location", "/elsewhere")]).redirect_target(), None);
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn header_values_split_on_first_colon() {
+        let (headers, warnings) = parse_header_lines("Referer: https://example.com:8443/a\nX-Empty:\nX-Tabs:\t v \t");
+        assert_eq!(headers, pairs(&[("Referer", "https://example.com:8443/a"), ("X-Empty", ""), ("X-Tabs", "v")]));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn header_obs_fold_continues_the_previous_value() {
+        let (headers, _) = parse_header_lines("X-Long: first\n second\n\tthird\nAccept: */*");
+        assert_eq!(headers, pairs(&[("X-Long", "first second third"), ("Accept", "*/*")]));
+        // Folded lines of a disabled header are dropped with it, not joined to the one before
+        let (headers, warnings) = parse_header_lines("Accept: */*\n# X-Off: a\n  b\nX-On: c");
+        assert_eq!(headers, pairs(&[("Accept", "*/*"), ("X-On", "c")]));
+        assert!(warnings.is_empty());
+        // An indented line that is a header on its own isn't a continuation
+        let (headers, _) = parse_header_lines("Accept: */*\n  X-Next: 1");
+        assert_eq!(headers, pairs(&[("Accept", "*/*"), ("X-Next", "1")]));
+    }
+
+    #[test]
+    fn header_lines_that_are_skipped() {
+        let (headers, warnings) = parse_header_lines("Bad Header: x\n\nAccept: */*\njust text\n\n  orphan");
+        assert_eq!(headers, pairs(&[("Accept", "*/*")]));
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("Line 1 skipped"));
+        assert!(warnings[1].starts_with("Line 4 skipped"));
+        assert!(warnings[2].starts_with("Line 6 skipped"));
+    }
+
+    #[test]
+    fn canonical_header_names() {
+        assert_eq!(canonical_header_name("x-REQUEST-id"), "X-Request-Id");
+        assert_eq!(canonical_header_name("content-type"), "Content-Type");
+        assert_eq!(canonical_header_name("WWW-AUTHENTICATE"), "Www-Authenticate");
+        assert_eq!(canonical_header_name("x--double-"), "X--Double-");
+    }
+
+    #[test]
+    fn canonicalize_header_lines() {
+        assert_eq!(
+            canonicalize_headers("content-type: Application/JSON\nx-REQUEST-id:abc\n# accept: */*\n  #cache-control : no-cache"),
+            "Content-Type: Application/JSON\nX-Request-Id:abc\n# Accept: */*\n  #Cache-Control : no-cache",
+        );
+        // Lines that aren't headers pass through untouched
+        let malformed = "not a header\nbad name: x\n\n  folded value: here\n: empty name";
+        assert_eq!(canonicalize_headers(malformed), malformed);
+    }
+
+    #[test]
+    fn param_lines() {
+        let (params, warnings) = parse_param_lines("q=a=b\n\nflag\n=value\n  empty =  \nnext=1");
+        assert_eq!(params, pairs(&[("q", "a=b"), ("empty", ""), ("next", "1")]));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("Line 3 skipped"));
+        assert!(warnings[1].starts_with("Line 4 skipped"));
+    }
+
+    #[test]
+    fn quoted_param_values() {
+        let (params, _) = parse_param_lines(r#"q="  two words  "
+say="she said \"hi\""
+path="C:\\temp\\x"
+odd="a\b""#);
+        assert_eq!(params, pairs(&[
+            ("q", "  two words  "),
+            ("say", r#"she said "hi""#),
+            ("path", r"C:\temp\x"),
+            ("odd", r"a\b"),
+        ]));
+    }
+
+    #[test]
+    fn schedule_delays() {
+        let now = chrono::Local::now();
+        assert_eq!(parse_schedule("30", now), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_schedule("in 5m", now), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_schedule("1440m", now), Ok(SCHEDULE_MAX));
+        assert!(parse_schedule("1441m", now).is_err());
+        assert!(parse_schedule("99999999999999999m", now).is_err());
+        assert!(parse_schedule("18446744073709551615s", now).is_err());
+        assert!(parse_schedule("0s", now).is_err());
+        assert!(parse_schedule("soon", now).is_err());
+        assert!(parse_schedule("at 14:05", now).is_ok_and(|delay| delay <= SCHEDULE_MAX));
+    }
+
+    #[test]
+    fn curl_follow_and_head() {
+        let parsed = parse_curl("curl -L https://example.com/download").unwrap();
+        assert!(parsed.follow_redirects);
+        assert_eq!(parsed.method, Method::GET);
+        let parsed = parse_curl("curl -I https://example.com/").unwrap();
+        assert_eq!(parsed.method, Method::HEAD);
+        assert!(!parsed.follow_redirects);
+    }
+
+    #[test]
+    fn curl_get_moves_data_to_params() {
+        let parsed = parse_curl("curl -G https://api.example.com/search -d q=rust -d 'page=2'").unwrap();
+        assert_eq!(parsed.method, Method::GET);
+        assert_eq!(parsed.body, "");
+        assert_eq!(parsed.params, "q=rust\npage=2");
+    }
+
+    #[test]
+    fn curl_get_decodes_encoded_data() {
+        let parsed = parse_curl("curl -G https://example.com/ -d 'q=a%20b' -d 'tag=c%2Bd+e'").unwrap();
+        assert_eq!(parsed.params, "q=a b\ntag=c+d e");
+    }
+
+    #[test]
+    fn curl_referer() {
+        let parsed = parse_curl("curl -e https://example.com/from https://example.com/to").unwrap();
+        assert_eq!(parsed.headers, "Referer: https://example.com/from");
+        let parsed = parse_curl("curl --referer 'https://a.example/;auto' https://b.example/").unwrap();
+        assert_eq!(parsed.headers, "Referer: https://a.example/");
+        let parsed = parse_curl("curl -e ';auto' https://b.example/").unwrap();
+        assert_eq!(parsed.headers, "");
+    }
+
+    #[test]
+    fn curl_bundled_short_flags() {
+        let parsed = parse_curl("curl -sSL https://get.example.com/install.sh").unwrap();
+        assert!(parsed.follow_redirects);
+        assert_eq!(parsed.url, "https://get.example.com/install.sh");
+        let parsed = parse_curl("curl -sIL https://example.com/").unwrap();
+        assert_eq!(parsed.method, Method::HEAD);
+        assert!(parsed.follow_redirects);
+    }
+
+    #[test]
+    fn curl_repeated_data_is_joined() {
+        let parsed = parse_curl("curl https://example.com/form -d name=ada -d 'lang=en'").unwrap();
+        assert_eq!(parsed.method, Method::POST);
+        assert_eq!(parsed.body, "name=ada&lang=en");
+        assert_eq!(parsed.params, "");
+    }
+
+    #[test]
+    fn curl_explicit_method_wins() {
+        let parsed = parse_curl("curl -X POST -G https://example.com/ -d a=1").unwrap();
+        assert_eq!(parsed.method, Method::POST);
+        assert_eq!(parsed.params, "a=1");
+        let parsed = parse_curl("curl -I -X GET https://example.com/").unwrap();
+        assert_eq!(parsed.method, Method::GET);
+    }
+
+    #[test]
+    fn local_urls_send_while_offline() {
+        let local = ["http://localhost:3000/", "http://api.localhost/", "http://127.0.0.1/", "http://[::1]:8080/", "http://192.168.1.5/"];
+        for url in local.into_iter().chain(["unix:///tmp/app.sock:/"]) {
+            assert!(is_local_url(url), "{url}");
+        }
+        for url in ["https://example.com/", "http://8.8.8.8/", "http://[2001:db8::1]/", "not a url"] {
+            assert!(!is_local_url(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn jwt_time_survives_extreme_claims() {
+        let now = 1_700_000_000;
+        assert!(jwt_time(now + 90, now).ends_with("(in 1m)"));
+        assert!(jwt_time(now - 7200, now).ends_with("(2h 0m ago)"));
+        assert!(jwt_time(i64::MIN, now).starts_with("-9223372036854775808 ("));
+        assert!(jwt_time(i64::MAX, i64::MIN).ends_with("d 7h)"));
+    }
+
+    #[test]
+    fn http_file_basic_auth_round_trip() {
+        let mut saved = SavedRequest::blank();
+        saved.headers = "Accept: */*".to_string();
+        saved.auth_type = AuthType::Basic;
+        saved.auth_username = "alice".to_string();
+        saved.auth_password = "s3:cret".to_string();
+        let (requests, _) = parse_http_file(&http_file(&saved, None));
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].auth_type, AuthType::Basic);
+        assert_eq!((requests[0].auth_username.as_str(), requests[0].auth_password.as_str()), ("alice", "s3:cret"));
+        assert_eq!(requests[0].headers, "Accept: */*");
+    }
+
+    #[test]
+    fn http_file_auth_forms() {
+        let basic = |line| http_file_auth(line).map(|(auth_type, _, user, pass)| (auth_type, user, pass));
+        let alice = Some((AuthType::Basic, "alice".to_string(), "secret".to_string()));
+        assert_eq!(basic("Authorization: Basic alice secret"), alice);
+        assert_eq!(basic("authorization: basic alice:secret"), alice);
+        assert_eq!(basic("Authorization: Basic YWxpY2U6c2VjcmV0"), alice);
+        assert_eq!(basic("Authorization: Basic alice"), None);
+        assert_eq!(basic("Authorization: Digest alice secret"), None);
+        assert_eq!(basic("X-Authorization: Basic alice:secret"), None);
+        let bearer = http_file_auth("Authorization: Bearer {{token}}");
+        assert_eq!(bearer.map(|(auth_type, token, ..)| (auth_type, token)), Some((AuthType::Bearer, "{{token}}".to_string())));
+    }
+
+    #[test]
+    fn http_file_notes_ntlm_auth() {
+        let mut saved = SavedRequest::blank();
+        saved.auth_type = AuthType::Ntlm;
+        saved.auth_username = "alice".to_string();
+        saved.auth_domain = "CORP".to_string();
+        let file = http_file(&saved, None);
+        assert!(file.contains("# NTLM auth for CORP\\alice is not exported\n"));
+        let (requests, _) = parse_http_file(&file);
+        assert_eq!(requests[0].url, saved.url);
+        assert_eq!(requests[0].auth_type, AuthType::None);
+    }
+
+    #[test]
+    fn ntowf_v2_matches_ms_nlmp() {
+        assert_eq!(
+            ntowf_v2("User", "Domain", "Password").to_vec(),
+            hex("0c 86 8a 40 3b fd 7a 93 a3 00 1e f2 2e f0 2e 3f"),
+        );
+    }
+
+    #[test]
+    fn parses_ms_nlmp_challenge() {
+        let challenge = parse_ntlm_challenge(&ntlm_challenge_message()).unwrap();
+        assert_eq!(challenge.flags, 0xe28a8233);
+        assert_eq!(challenge.server_challenge.to_vec(), hex("01 23 45 67 89 ab cd ef"));
+        assert_eq!(challenge.target_info, hex(NTLM_TARGET_INFO));
+    }
+
+    #[test]
+    fn authenticate_message_carries_ms_nlmp_responses() {
+        let challenge = parse_ntlm_challenge(&ntlm_challenge_message()).unwrap();
+        let message = ntlm_authenticate_message(&challenge, "User", "Domain", "Password", [0xaa; 8], 0);
+        assert_eq!(&message[..12], &hex("4e 54 4c 4d 53 53 50 00 03 00 00 00")[..]);
+        assert_eq!(
+            ntlm_field(&message, 12),
+            &hex("86 c3 50 97 ac 9c ec 10 25 54 76 4a 57 cc cc 19 aa aa aa aa aa aa aa aa")[..],
+        );
+        let nt_response = ntlm_field(&message, 20);
+        assert_eq!(&nt_response[..16], &hex("68 cd 0a b8 51 e5 1c 96 aa bc 92 7b eb ef 6a 1c")[..]);
+        assert_eq!(ntlm_field(&message, 28), &utf16le("Domain")[..]);
+        assert_eq!(ntlm_field(&message, 36), &utf16le("User")[..]);
+    }
+
+    #[test]
+    fn server_timestamp_zeroes_lmv2() {
+        let mut target_info = hex("07 00 08 00 01 02 03 04 05 06 07 08");
+        target_info.extend(hex(NTLM_TARGET_INFO));
+        let challenge = NtlmChallenge { flags: NTLM_FLAGS, server_challenge: [1; 8], target_info };
+        let message = ntlm_authenticate_message(&challenge, "User", "Domain", "Password", [0xaa; 8], 0);
+        assert_eq!(ntlm_field(&message, 12), &[0u8; 24][..]);
+        // The blob's timestamp follows the 16-byte proof and 8-byte header
+        assert_eq!(&ntlm_field(&message, 20)[24..32], &hex("01 02 03 04 05 06 07 08")[..]);
+    }
+
+    #[test]
+    fn rejects_malformed_challenges() {
+        let message = ntlm_challenge_message();
+        assert!(parse_ntlm_challenge(&message[..31]).is_err());
+        assert!(parse_ntlm_challenge(&[]).is_err());
+        let mut wrong_type = message.clone();
+        wrong_type[8] = 3;
+        assert!(parse_ntlm_challenge(&wrong_type).is_err());
+        let mut wrong_signature = message.clone();
+        wrong_signature[0] = b'X';
+        assert!(parse_ntlm_challenge(&wrong_signature).is_err());
+        // Target info pointing past the end
+        assert!(parse_ntlm_challenge(&message[..message.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn challenge_without_target_info() {
+        let challenge = parse_ntlm_challenge(&ntlm_challenge_message()[..32]).unwrap();
+        assert!(challenge.target_info.is_empty());
+        assert_eq!(challenge.server_challenge.to_vec(), hex("01 23 45 67 89 ab cd ef"));
+    }
+
+    #[test]
+    fn walks_av_pairs() {
+        let target_info = hex(NTLM_TARGET_INFO);
+        assert_eq!(ntlm_av_pair(&target_info, 1), Some(&utf16le("Server")[..]));
+        assert_eq!(ntlm_av_pair(&target_info, 2), Some(&utf16le("Domain")[..]));
+        assert_eq!(ntlm_av_pair(&target_info, NTLM_AV_TIMESTAMP), None);
+        // A pair whose length runs past the end
+        assert_eq!(ntlm_av_pair(&hex("01 00 10 00 53 00"), 1), None);
+        // Nothing after MsvAvEOL is read
+        assert_eq!(ntlm_av_pair(&hex("00 00 00 00 01 00 02 00 41 00"), 1), None);
+        assert_eq!(ntlm_av_pair(&[1, 0], 1), None);
+    }
+}